@@ -0,0 +1,316 @@
+/*
+ * C interface to mahc, the riichi mahjong calculator.
+ *
+ * Build the library with `make -C ffi lib`, or `cargo rustc --release --lib --crate-type cdylib
+ * --features ffi`, and link against `target/release/libmahc.so` (`mahc.dll` on Windows,
+ * `libmahc.dylib` on macOS).
+ *
+ * Every function returns MAHC_OK or an error code, and writes its result through the last
+ * argument. Results that own memory are freed with the matching `_free` function.
+ *
+ * Generated from src/ffi.rs by cbindgen with `make -C ffi header`; do not edit by hand.
+ */
+
+#ifndef MAHC_H
+#define MAHC_H
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/*
+ The call succeeded.
+ */
+#define MAHC_OK 0
+
+/*
+ A required pointer argument was null.
+ */
+#define MAHC_ERROR_NULL_POINTER -1
+
+/*
+ A string argument was not UTF-8.
+ */
+#define MAHC_ERROR_INVALID_UTF8 -2
+
+#define MAHC_ERROR_INVALID_GROUP 1
+
+#define MAHC_ERROR_INVALID_SUIT 2
+
+#define MAHC_ERROR_INVALID_SHAPE 3
+
+#define MAHC_ERROR_NO_YAKU 4
+
+#define MAHC_ERROR_NO_HAND_TILES 5
+
+#define MAHC_ERROR_NO_WIN_TILE 6
+
+#define MAHC_ERROR_DUPLICATE_RIICHI 7
+
+#define MAHC_ERROR_IPPATSU_WITHOUT_RIICHI 8
+
+#define MAHC_ERROR_DOUBLE_RIICHI_HAITEI_IPPATSU 9
+
+#define MAHC_ERROR_DOUBLE_RIICHI_HAITEI_CHANKAN 10
+
+#define MAHC_ERROR_CHANKAN_TSUMO 11
+
+#define MAHC_ERROR_RINSHAN_KAN_WITHOUT_KAN 12
+
+#define MAHC_ERROR_RINSHAN_WITHOUT_TSUMO 13
+
+#define MAHC_ERROR_RINSHAN_IPPATSU 14
+
+#define MAHC_ERROR_NO_HAN 15
+
+#define MAHC_ERROR_NO_FU 16
+
+#define MAHC_ERROR_INVALID_PAYMENT 17
+
+#define MAHC_ERROR_INVALID_RIICHI_TURN 18
+
+#define MAHC_ERROR_NOT_IISHANTEN 19
+
+#define MAHC_ERROR_RON_OWN_DISCARD 20
+
+#define MAHC_ERROR_GAME_OVER 21
+
+#define MAHC_ERROR_NOTHING_TO_UNDO 22
+
+#define MAHC_ERROR_INVALID_GAME_STATE 23
+
+#define MAHC_ERROR_GAME_STATE_FILE 24
+
+#define MAHC_ERROR_INVALID_UMA 25
+
+#define MAHC_ERROR_GAME_NOT_OVER 26
+
+#define MAHC_ERROR_SANMA_MANZU 27
+
+#define MAHC_ERROR_SANMA_CHI 28
+
+#define MAHC_ERROR_SANMA_NORTH 29
+
+#define MAHC_ERROR_TOO_MANY_NORTHS 30
+
+#define MAHC_ERROR_INVALID_WINNERS 31
+
+#define MAHC_ERROR_INVALID_HAND_EVENT 32
+
+#define MAHC_ERROR_INVALID_KYUUSHU 33
+
+#define MAHC_ERROR_NO_ABORTIVE_DRAW 34
+
+#define MAHC_ERROR_INVALID_TABLE_FORMAT 35
+
+#define MAHC_ERROR_CLAIM_WINNER_MISMATCH 36
+
+#define MAHC_ERROR_INVALID_LANG 37
+
+#define MAHC_ERROR_INVALID_RENDER_FORMAT 38
+
+#define MAHC_ERROR_INVALID_REPL_COMMAND 39
+
+#define MAHC_ERROR_INVALID_REQUEST 40
+
+#define MAHC_ERROR_UNKNOWN_ENDPOINT 41
+
+#define MAHC_ERROR_INVALID_MJAI_EVENT 42
+
+#define MAHC_ERROR_INVALID_TRIALS 43
+
+#define MAHC_ERROR_NUKIDORA_OUTSIDE_SANMA 44
+
+#define MAHC_ERROR_INVALID_HAN 45
+
+#define MAHC_ERROR_CLAIM_METHOD_MISMATCH 46
+
+#define MAHC_ERROR_INVALID_SCORE 47
+
+/*
+ A parsed hand, only handled through a pointer.
+ */
+typedef struct MahcHand MahcHand;
+
+/*
+ Number of honba (repeat counts).
+ */
+typedef uint64_t HonbaCounter;
+
+/*
+ Round context of a hand to score.
+ */
+typedef struct MahcContext {
+  /*
+   Dora indicators separated by spaces (ex: `"3p Ew"`), or null for none.
+   */
+  const char *dora;
+  bool tsumo;
+  bool riichi;
+  bool double_riichi;
+  bool ippatsu;
+  bool haitei;
+  bool rinshan;
+  bool chankan;
+  bool tenhou;
+  HonbaCounter honba;
+  /*
+   North tiles can be set aside as nukidora, as in some four-player rules.
+   */
+  bool nukidora;
+  /*
+   The draw after a nukidora counts for rinshan kaihou.
+   */
+  bool nuki_rinshan;
+} MahcContext;
+
+/*
+ Han value.
+ */
+typedef uint32_t HanValue;
+
+/*
+ Fu (minipoints) value.
+ */
+typedef uint64_t FuValue;
+
+/*
+ Number of points players pay to the winner.
+ */
+typedef uint64_t Points;
+
+/*
+ What every seat pays, including honba.
+ */
+typedef struct MahcPoints {
+  Points dealer_ron;
+  /*
+   Paid by each non-dealer.
+   */
+  Points dealer_tsumo;
+  Points non_dealer_ron;
+  /*
+   Paid by the dealer.
+   */
+  Points non_dealer_tsumo_dealer;
+  /*
+   Paid by each other non-dealer.
+   */
+  Points non_dealer_tsumo_non_dealer;
+} MahcPoints;
+
+/*
+ A yaku that was awarded.
+ */
+typedef struct MahcYaku {
+  /*
+   Id of the yaku (ex: `"riichi"`).
+   */
+  char *id;
+  /*
+   Han the yaku was worth, or 1 for each yakuman.
+   */
+  HanValue han;
+  bool yakuman;
+} MahcYaku;
+
+/*
+ A fu that was awarded.
+ */
+typedef struct MahcFu {
+  /*
+   Id of the fu (ex: `"closed_ron"`).
+   */
+  char *id;
+  FuValue fu;
+} MahcFu;
+
+/*
+ The score of a hand, freed with [`mahc_score_free`].
+ */
+typedef struct MahcScore {
+  HanValue han;
+  FuValue fu;
+  HonbaCounter honba;
+  bool open;
+  uint32_t dora;
+  struct MahcPoints points;
+  struct MahcYaku *yaku;
+  size_t yaku_len;
+  struct MahcFu *fu_breakdown;
+  size_t fu_breakdown_len;
+} MahcScore;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/*
+ Parse a hand, written to `out` and freed with [`mahc_hand_free`].
+
+ `tiles` are the groups separated by spaces, with the winning group last (ex:
+ `"123p 456p 99s 234s 678m"`).
+
+ # Safety
+
+ The strings are nul-terminated, and `out` points to writable memory.
+ */
+int32_t mahc_hand_parse(const char *tiles,
+                        const char *win,
+                        const char *seat,
+                        const char *prev,
+                        struct MahcHand **out);
+
+/*
+ Free a hand from [`mahc_hand_parse`].
+
+ # Safety
+
+ `hand` is null or a hand from [`mahc_hand_parse`] that has not been freed yet.
+ */
+void mahc_hand_free(struct MahcHand *hand);
+
+/*
+ Score a hand, written to `out` and freed with [`mahc_score_free`].
+
+ # Safety
+
+ `hand` is a hand from [`mahc_hand_parse`], `context` points to a context with a null or
+ nul-terminated `dora`, and `out` points to writable memory.
+ */
+int32_t mahc_hand_score(const struct MahcHand *hand,
+                        const struct MahcContext *context,
+                        struct MahcScore **out);
+
+/*
+ Free a score from [`mahc_hand_score`], along with its yaku and fu.
+
+ # Safety
+
+ `score` is null or a score from [`mahc_hand_score`] that has not been freed yet.
+ */
+void mahc_score_free(struct MahcScore *score);
+
+/*
+ Calculate the points of a han and fu, written to `out`.
+
+ # Safety
+
+ `out` points to writable memory.
+ */
+int32_t mahc_calculate(HanValue han, FuValue fu, HonbaCounter honba, struct MahcPoints *out);
+
+/*
+ Get the name of an error code (ex: `"no_yaku"`), or null for an unknown code.
+
+ The string is static and must not be freed.
+ */
+const char *mahc_error_name(int32_t code);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* MAHC_H */
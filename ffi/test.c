@@ -0,0 +1,101 @@
+/* Test harness for the C interface, run with `make -C ffi test`. */
+
+#include <stdio.h>
+#include <string.h>
+
+#include "mahc.h"
+
+static int failures = 0;
+
+#define CHECK(cond)                                              \
+  do {                                                           \
+    if (!(cond)) {                                               \
+      fprintf(stderr, "%s:%d: failed: %s\n", __FILE__, __LINE__, #cond); \
+      failures++;                                                \
+    }                                                            \
+  } while (0)
+
+static void test_score_hand(void) {
+  MahcHand *hand = NULL;
+  CHECK(mahc_hand_parse("123p 456p 99s 234s 678m", "6m", "Sw", "Ew", &hand) == MAHC_OK);
+
+  MahcContext context = {0};
+  context.dora = "4p";
+  context.riichi = true;
+  context.honba = 1;
+
+  MahcScore *score = NULL;
+  CHECK(mahc_hand_score(hand, &context, &score) == MAHC_OK);
+  CHECK(score->han == 3);
+  CHECK(score->fu == 30);
+  CHECK(score->dora == 1);
+  CHECK(!score->open);
+  CHECK(score->points.non_dealer_ron == 4200);
+  CHECK(score->points.dealer_ron == 6100);
+  CHECK(score->yaku_len == 2);
+  CHECK(strcmp(score->yaku[0].id, "riichi") == 0);
+  CHECK(strcmp(score->yaku[1].id, "pinfu") == 0);
+  CHECK(score->fu_breakdown_len == 2);
+  CHECK(strcmp(score->fu_breakdown[1].id, "closed_ron") == 0);
+
+  mahc_score_free(score);
+  mahc_hand_free(hand);
+}
+
+static void test_errors(void) {
+  MahcHand *hand = NULL;
+  CHECK(mahc_hand_parse("123p 456p", "6m", "Ew", "Ew", &hand) == MAHC_ERROR_INVALID_SHAPE);
+  CHECK(hand == NULL);
+  CHECK(mahc_hand_parse(NULL, "6m", "Ew", "Ew", &hand) == MAHC_ERROR_NULL_POINTER);
+
+  CHECK(mahc_hand_parse("123p 456p 99s 234s 678mo", "6m", "Ew", "Ew", &hand) == MAHC_OK);
+  MahcContext context = {0};
+  MahcScore *score = NULL;
+  int32_t code = mahc_hand_score(hand, &context, &score);
+  CHECK(code == MAHC_ERROR_NO_YAKU);
+  CHECK(strcmp(mahc_error_name(code), "no_yaku") == 0);
+  CHECK(score == NULL);
+  mahc_hand_free(hand);
+
+  CHECK(mahc_error_name(100000) == NULL);
+}
+
+static void test_nukidora(void) {
+  MahcHand *hand = NULL;
+  CHECK(mahc_hand_parse("123p 456p 789s rrrd EEw Nwk", "3p", "Ew", "Sw", &hand) == MAHC_OK);
+
+  MahcContext context = {0};
+  MahcScore *score = NULL;
+  CHECK(mahc_hand_score(hand, &context, &score) == MAHC_ERROR_NUKIDORA_OUTSIDE_SANMA);
+
+  context.nukidora = true;
+  CHECK(mahc_hand_score(hand, &context, &score) == MAHC_OK);
+  CHECK(score->dora == 1);
+  CHECK(score->han == 2);
+
+  mahc_score_free(score);
+  mahc_hand_free(hand);
+}
+
+static void test_calculate(void) {
+  MahcPoints points;
+  CHECK(mahc_calculate(4, 30, 0, &points) == MAHC_OK);
+  CHECK(points.non_dealer_ron == 7700);
+  CHECK(points.non_dealer_tsumo_dealer == 3900);
+  CHECK(points.non_dealer_tsumo_non_dealer == 2000);
+  CHECK(mahc_calculate(0, 30, 0, &points) == MAHC_ERROR_NO_HAN);
+}
+
+int main(void) {
+  test_score_hand();
+  test_errors();
+  test_nukidora();
+  test_calculate();
+
+  if (failures) {
+    fprintf(stderr, "%d checks failed\n", failures);
+    return 1;
+  }
+  printf("all checks passed\n");
+  return 0;
+}
@@ -0,0 +1,267 @@
+use crate::hand::error::HandErr;
+use crate::seat::Seat;
+use crate::settlement::MultiRonRules;
+use crate::suit::Suit;
+use crate::tenpai::split_hand;
+use crate::tile_group::TileGroup;
+
+/// Something that happened during a hand, given in the order it happened.
+#[derive(Debug, Clone, PartialEq)]
+pub enum HandEvent {
+    /// Kyuushu kyuuhai declared on a first draw, with the 14 tiles in hand.
+    Kyuushu(Seat, Vec<TileGroup>),
+    /// A discarded tile.
+    Discard(Seat, TileGroup),
+    /// A chi or pon call.
+    Call(Seat),
+    /// A kan of any kind.
+    Kan(Seat),
+    /// A riichi declaration that went through, with the stick put on the table.
+    Riichi(Seat),
+    /// A ron off the last discard.
+    Ron(Seat),
+}
+
+impl TryFrom<String> for HandEvent {
+    type Error = HandErr;
+
+    /// Parse an event written as the seat wind, the event, and the tiles for discards and
+    /// kyuushu kyuuhai, split by `:`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use mahc::abortive::HandEvent;
+    /// use mahc::seat::Seat;
+    ///
+    /// let actual: HandEvent = "Sw:discard:Nw".to_string().try_into().unwrap();
+    /// assert_eq!(actual, HandEvent::Discard(Seat::South, "Nw".to_string().try_into().unwrap()));
+    ///
+    /// let actual: HandEvent = "Ew:kyuushu:19m,19p,19s,ESWNw,grrd,5p".to_string().try_into().unwrap();
+    /// assert!(matches!(actual, HandEvent::Kyuushu(Seat::East, tiles) if tiles.len() == 14));
+    ///
+    /// assert!(HandEvent::try_from("Ew:pass".to_string()).is_err());
+    /// ```
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let parts: Vec<&str> = value.split(':').collect();
+        let seat: Seat = parts[0].to_string().try_into()?;
+
+        match parts[1..] {
+            ["kyuushu", tiles] => {
+                let tokens: Vec<String> = tiles.split(',').map(str::to_string).collect();
+                let (concealed, _) = split_hand(&tokens)?;
+                Ok(Self::Kyuushu(seat, concealed))
+            }
+            ["discard", tile] => Ok(Self::Discard(seat, tile.to_string().try_into()?)),
+            ["call"] => Ok(Self::Call(seat)),
+            ["kan"] => Ok(Self::Kan(seat)),
+            ["riichi"] => Ok(Self::Riichi(seat)),
+            ["ron"] => Ok(Self::Ron(seat)),
+            _ => Err(HandErr::InvalidHandEvent),
+        }
+    }
+}
+
+/// A hand ending in a draw before the wall runs out.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AbortiveDraw {
+    /// Nine different terminals and honors on a first draw, before any call.
+    KyuushuKyuuhai(Seat),
+    /// The same wind discarded by all four players on the first go-around, before any call.
+    SuufonRenda,
+    /// All four players in riichi.
+    SuuchaRiichi,
+    /// Four kans made by more than one player.
+    Suukaikan,
+    /// Three players winning off the same discard.
+    TripleRon,
+}
+
+impl std::fmt::Display for AbortiveDraw {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::KyuushuKyuuhai(seat) => write!(f, "Kyuushu Kyuuhai by {seat}"),
+            Self::SuufonRenda => write!(f, "Suufon Renda"),
+            Self::SuuchaRiichi => write!(f, "Suucha Riichi"),
+            Self::Suukaikan => write!(f, "Suukaikan"),
+            Self::TripleRon => write!(f, "Triple Ron"),
+        }
+    }
+}
+
+/// Find the abortive draw that ends a hand from its events.
+///
+/// A triple ron is only a draw when the rules say so.
+///
+/// # Examples
+///
+/// ```rust
+/// use mahc::abortive::{detect, AbortiveDraw, HandEvent};
+/// use mahc::settlement::MultiRonRules;
+///
+/// let events: Vec<HandEvent> = ["Ew:discard:Ww", "Sw:discard:Ww", "Ww:discard:Ww", "Nw:discard:Ww"]
+///     .iter()
+///     .map(|event| event.to_string().try_into().unwrap())
+///     .collect();
+///
+/// let draw = detect(&events, &MultiRonRules::default()).unwrap();
+/// assert_eq!(draw, AbortiveDraw::SuufonRenda);
+/// ```
+pub fn detect(events: &[HandEvent], rules: &MultiRonRules) -> Result<AbortiveDraw, HandErr> {
+    let mut interrupted = false;
+    let mut discards: Vec<(Seat, &TileGroup)> = vec![];
+    let mut riichi: Vec<Seat> = vec![];
+    let mut kans: Vec<Seat> = vec![];
+    let mut rons: Vec<Seat> = vec![];
+
+    for event in events {
+        match event {
+            HandEvent::Kyuushu(seat, tiles) => {
+                let first_draw = !interrupted && discards.iter().all(|(other, _)| other != seat);
+                if !first_draw || tiles.len() != 14 || kinds_of_yaochuu(tiles) < 9 {
+                    return Err(HandErr::InvalidKyuushu);
+                }
+                return Ok(AbortiveDraw::KyuushuKyuuhai(*seat));
+            }
+            HandEvent::Discard(seat, tile) => {
+                discards.push((*seat, tile));
+                rons.clear();
+
+                let (first, first_tile) = discards[0];
+                let same_wind = discards.len() == 4
+                    && first_tile.suit == Suit::Wind
+                    && discards
+                        .iter()
+                        .all(|(_, tile)| tile.is_same_tile(first_tile))
+                    && discards.iter().skip(1).all(|(other, _)| *other != first);
+                if !interrupted && same_wind {
+                    return Ok(AbortiveDraw::SuufonRenda);
+                }
+            }
+            HandEvent::Call(_) => interrupted = true,
+            HandEvent::Kan(seat) => {
+                interrupted = true;
+                kans.push(*seat);
+                if kans.len() == 4 && kans.iter().any(|other| other != seat) {
+                    return Ok(AbortiveDraw::Suukaikan);
+                }
+            }
+            HandEvent::Riichi(seat) => {
+                if !riichi.contains(seat) {
+                    riichi.push(*seat);
+                }
+                if riichi.len() == 4 {
+                    return Ok(AbortiveDraw::SuuchaRiichi);
+                }
+            }
+            HandEvent::Ron(seat) => {
+                if !rons.contains(seat) {
+                    rons.push(*seat);
+                }
+                if rons.len() == 3 && rules.triple_ron_draw {
+                    return Ok(AbortiveDraw::TripleRon);
+                }
+            }
+        }
+    }
+
+    Err(HandErr::NoAbortiveDraw)
+}
+
+/// Count the different terminals and honors in the tiles.
+fn kinds_of_yaochuu(tiles: &[TileGroup]) -> usize {
+    let mut kinds: Vec<&TileGroup> = vec![];
+    for tile in tiles {
+        let yaochuu = tile.is_honor() || tile.value == "1" || tile.value == "9";
+        if yaochuu && !kinds.iter().any(|kind| kind.is_same_tile(tile)) {
+            kinds.push(tile);
+        }
+    }
+    kinds.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn events(events: &[&str]) -> Vec<HandEvent> {
+        events
+            .iter()
+            .map(|event| event.to_string().try_into().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn kyuushu_kyuuhai() {
+        let rules = MultiRonRules::default();
+        let hand = "19m,19p,19s,ESWw,234p,55p";
+        let first_draw = events(&["Ew:discard:Nw", &format!("Sw:kyuushu:{hand}")]);
+        let draw = detect(&first_draw, &rules).unwrap();
+        assert_eq!(draw, AbortiveDraw::KyuushuKyuuhai(Seat::South));
+
+        // a call before the first draw breaks the go-around
+        let called = events(&["Ew:discard:Nw", "Ww:call", &format!("Sw:kyuushu:{hand}")]);
+        assert_eq!(
+            detect(&called, &rules).unwrap_err(),
+            HandErr::InvalidKyuushu
+        );
+
+        // only eight kinds of terminals and honors
+        let eight = events(&["Ew:kyuushu:19m,19p,19s,ESw,234p,555p"]);
+        assert_eq!(detect(&eight, &rules).unwrap_err(), HandErr::InvalidKyuushu);
+    }
+
+    #[test]
+    fn suufon_renda_needs_first_go_around() {
+        let rules = MultiRonRules::default();
+        let called = events(&[
+            "Ew:discard:Ew",
+            "Sw:discard:Ew",
+            "Ww:call",
+            "Ww:discard:Ew",
+            "Nw:discard:Ew",
+        ]);
+        assert_eq!(
+            detect(&called, &rules).unwrap_err(),
+            HandErr::NoAbortiveDraw
+        );
+
+        let not_wind = events(&[
+            "Ew:discard:rd",
+            "Sw:discard:rd",
+            "Ww:discard:rd",
+            "Nw:discard:rd",
+        ]);
+        assert_eq!(
+            detect(&not_wind, &rules).unwrap_err(),
+            HandErr::NoAbortiveDraw
+        );
+    }
+
+    #[test]
+    fn suucha_riichi_and_suukaikan() {
+        let rules = MultiRonRules::default();
+        let riichi = events(&["Ew:riichi", "Sw:riichi", "Ww:riichi", "Nw:riichi"]);
+        assert_eq!(detect(&riichi, &rules).unwrap(), AbortiveDraw::SuuchaRiichi);
+
+        let kans = events(&["Ew:kan", "Ew:kan", "Sw:kan", "Ew:kan"]);
+        assert_eq!(detect(&kans, &rules).unwrap(), AbortiveDraw::Suukaikan);
+
+        // four kans by one player can still become suukantsu
+        let kans = events(&["Ew:kan", "Ew:kan", "Ew:kan", "Ew:kan"]);
+        assert_eq!(detect(&kans, &rules).unwrap_err(), HandErr::NoAbortiveDraw);
+    }
+
+    #[test]
+    fn triple_ron_by_rule() {
+        let rons = events(&["Ew:discard:5p", "Sw:ron", "Ww:ron", "Nw:ron"]);
+        let rules = MultiRonRules::default();
+        assert_eq!(detect(&rons, &rules).unwrap(), AbortiveDraw::TripleRon);
+
+        let rules = MultiRonRules {
+            triple_ron_draw: false,
+            ..MultiRonRules::default()
+        };
+        assert_eq!(detect(&rons, &rules).unwrap_err(), HandErr::NoAbortiveDraw);
+    }
+}
@@ -0,0 +1,194 @@
+use crate::hand::error::HandErr;
+use crate::payment::PlayerPoints;
+use crate::tenpai::{Tenpai, TileCounts, WinContext, TILE_KINDS};
+use crate::tile_group::{count_tile, TileGroup};
+
+/// A draw and discard that leaves the hand tenpai with a better best-case score.
+#[derive(Debug)]
+pub struct Improvement {
+    /// Tile to draw.
+    pub draw: TileGroup,
+    /// Tile to discard afterwards.
+    pub discard: TileGroup,
+    /// Waits and best score of the hand after the discard.
+    pub tenpai: Tenpai,
+    /// Change in points of the best-case win, compared to the hand before the draw.
+    pub gain: PlayerPoints,
+}
+
+/// Draws and discards that improve a tenpai or iishanten hand.
+#[derive(Debug)]
+pub struct Advice {
+    /// Waits and best score of the hand as it is, if it is tenpai.
+    pub current: Option<Tenpai>,
+    /// Improvements, from the largest gain to the smallest.
+    pub improvements: Vec<Improvement>,
+}
+
+/// Find every draw and discard that keeps the hand tenpai and raises its best-case score.
+///
+/// If the hand is iishanten (one tile from tenpai), every draw and discard that reaches a tenpai
+/// hand with a yaku is listed instead.
+///
+/// # Examples
+///
+/// ```rust
+/// use mahc::advisor::advise;
+/// use mahc::tenpai::{split_hand, TileCounts, WinContext};
+///
+/// // kanchan wait on 3s with a 9m pair, so no pinfu
+/// let tokens: Vec<String> = ["234m", "567p", "678p", "99m", "24s"]
+///     .iter()
+///     .map(|t| t.to_string())
+///     .collect();
+/// let (concealed, melds) = split_hand(&tokens).unwrap();
+/// let context = WinContext {
+///     seat: "Sw".to_string().try_into().unwrap(),
+///     prev: "Ew".to_string().try_into().unwrap(),
+///     dora: None,
+///     tsumo: false,
+///     riichi: true,
+///     honba: 0,
+/// };
+///
+/// let advice = advise(&TileCounts::new(&concealed), &melds, &context).unwrap();
+/// // drawing 5s and cutting 2s turns the kanchan into a 3s-6s ryanmen for pinfu
+/// assert!(advice.improvements.iter().any(|improvement| {
+///     improvement.draw.to_string() == "5s" && improvement.discard.to_string() == "2s"
+/// }));
+/// ```
+pub fn advise(
+    concealed: &TileCounts,
+    melds: &[TileGroup],
+    context: &WinContext,
+) -> Result<Advice, HandErr> {
+    if concealed.total() + 3 * melds.len() != 13 {
+        return Err(HandErr::InvalidShape);
+    }
+
+    let current = concealed.tenpai(melds, context);
+    let current_value = current.as_ref().map_or((0, 0), |tenpai| tenpai.value());
+    let current_points = current
+        .as_ref()
+        .and_then(|tenpai| tenpai.best.as_ref())
+        .map_or(0, |score| context.points(score));
+
+    let mut reaches_tenpai = current.is_some();
+    let mut improvements: Vec<Improvement> = vec![];
+
+    for draw in 0..TILE_KINDS {
+        let draw_tile = TileCounts::tile(draw);
+        if usize::from(concealed.count(draw)) + count_tile(melds, &draw_tile) >= 4 {
+            continue;
+        }
+
+        let mut drawn = concealed.clone();
+        drawn.add(draw);
+
+        for discard in 0..TILE_KINDS {
+            if discard == draw || drawn.count(discard) == 0 {
+                continue;
+            }
+
+            let mut hand = drawn.clone();
+            hand.remove(discard);
+            let Some(tenpai) = hand.tenpai(melds, context) else {
+                continue;
+            };
+            reaches_tenpai = true;
+
+            if tenpai.best.is_none() || tenpai.value() <= current_value {
+                continue;
+            }
+
+            let points = tenpai
+                .best
+                .as_ref()
+                .map_or(0, |score| context.points(score));
+            improvements.push(Improvement {
+                draw: draw_tile.clone(),
+                discard: TileCounts::tile(discard),
+                tenpai,
+                gain: points as PlayerPoints - current_points as PlayerPoints,
+            });
+        }
+    }
+
+    if !reaches_tenpai {
+        return Err(HandErr::NotIishanten);
+    }
+
+    improvements.sort_by_key(|improvement| std::cmp::Reverse(improvement.gain));
+
+    Ok(Advice {
+        current,
+        improvements,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tenpai::split_hand;
+    use crate::yaku::Yaku;
+
+    fn context() -> WinContext {
+        WinContext {
+            seat: "Ew".to_string().try_into().unwrap(),
+            prev: "Ew".to_string().try_into().unwrap(),
+            dora: None,
+            tsumo: false,
+            riichi: false,
+            honba: 0,
+        }
+    }
+
+    fn counts(tokens: &[&str]) -> (TileCounts, Vec<TileGroup>) {
+        let tokens: Vec<String> = tokens.iter().map(|t| t.to_string()).collect();
+        let (concealed, melds) = split_hand(&tokens).unwrap();
+        (TileCounts::new(&concealed), melds)
+    }
+
+    #[test]
+    fn advise_sanshoku() {
+        // 123m 123p 12s waits on 3s for sanshoku
+        let (hand, melds) = counts(&["123m", "123p", "456p", "99s", "12s"]);
+        let advice = advise(&hand, &melds, &context()).unwrap();
+
+        // already tenpai with sanshoku on 3s
+        let current = advice.current.unwrap();
+        assert!(current.best.unwrap().yaku().contains(&Yaku::SanshokuDoujun));
+        assert!(advice
+            .improvements
+            .iter()
+            .all(|improvement| improvement.gain > 0));
+    }
+
+    #[test]
+    fn advise_iishanten() {
+        let (hand, melds) = counts(&["234m", "567p", "678p", "24s", "5s", "9m"]);
+        let advice = advise(&hand, &melds, &context()).unwrap();
+
+        assert!(advice.current.is_none());
+        assert!(!advice.improvements.is_empty());
+        let best = &advice.improvements[0];
+        assert_eq!(
+            best.gain,
+            context().points(best.tenpai.best.as_ref().unwrap()) as i64
+        );
+    }
+
+    #[test]
+    fn advise_not_iishanten() {
+        let (hand, melds) = counts(&["159m", "159p", "159s", "ESWw", "rd"]);
+        let advice = advise(&hand, &melds, &context());
+        assert_eq!(advice.unwrap_err(), HandErr::NotIishanten);
+    }
+
+    #[test]
+    fn advise_wrong_tile_count() {
+        let (hand, melds) = counts(&["123m"]);
+        let advice = advise(&hand, &melds, &context());
+        assert_eq!(advice.unwrap_err(), HandErr::InvalidShape);
+    }
+}
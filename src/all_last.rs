@@ -0,0 +1,177 @@
+use crate::calc::{self, HandValue, TABLE_FU};
+use crate::limit_hand::LimitHands;
+use crate::payment::{Payment, PlayerPoints};
+use crate::score::{HonbaCounter, RiichiCounter};
+use crate::seat::Seat;
+use crate::settlement::{Settlement, WinMethod};
+
+/// Seats in order of who wins a tie in the last round.
+///
+/// Ties go to whoever was seated closer to the first dealer. By the last round the first dealer sits
+/// South, so the current dealer loses every tie.
+const TIE_BREAK_ORDER: [Seat; 4] = [Seat::South, Seat::West, Seat::North, Seat::East];
+
+/// The smallest hands that reach the target placement with a given win method.
+#[derive(Debug, PartialEq)]
+pub struct Requirement {
+    /// How the hand is won.
+    pub method: WinMethod,
+    /// Smallest hand for each han count, from cheapest to most expensive.
+    ///
+    /// Empty if not even a yakuman reaches the target placement.
+    pub options: Vec<HandValue>,
+}
+
+/// Table state going into the last hand of the game.
+///
+/// # Examples
+///
+/// ```rust
+/// use mahc::all_last::AllLast;
+/// use mahc::calc::HandValue;
+/// use mahc::seat::Seat;
+/// use mahc::settlement::WinMethod;
+///
+/// // East, South, West and North scores.
+/// let table = AllLast::new([30_000, 24_000, 26_000, 20_000], 0, 0);
+/// let requirements = table.requirements(Seat::South, 1);
+///
+/// // South wins ties with East, so a 1,000/2,000 tsumo is enough to close the 6,000 gap.
+/// let tsumo = requirements.last().unwrap();
+/// assert_eq!(tsumo.method, WinMethod::Tsumo);
+/// assert_eq!(tsumo.options[0], HandValue::HanFu(2, 60));
+/// ```
+#[derive(Debug, Clone)]
+pub struct AllLast {
+    /// Points of each player, indexed by seat.
+    scores: [PlayerPoints; 4],
+    /// Number of repeat counters.
+    honba: HonbaCounter,
+    /// Number of riichi sticks on the table.
+    riichi_sticks: RiichiCounter,
+}
+
+impl AllLast {
+    /// Create a new [`AllLast`] from the scores of the East, South, West and North seats.
+    pub fn new(
+        scores: [PlayerPoints; 4],
+        honba: HonbaCounter,
+        riichi_sticks: RiichiCounter,
+    ) -> Self {
+        Self {
+            scores,
+            honba,
+            riichi_sticks,
+        }
+    }
+
+    /// Get the placement (1 to 4) of the seat for the given scores.
+    pub fn placement(scores: &[PlayerPoints; 4], seat: Seat) -> usize {
+        let priority = |s: Seat| TIE_BREAK_ORDER.iter().position(|&p| p == s).unwrap();
+
+        1 + Seat::ALL
+            .iter()
+            .filter(|&&other| {
+                let (theirs, ours) = (scores[other.index()], scores[seat.index()]);
+                theirs > ours || (theirs == ours && priority(other) < priority(seat))
+            })
+            .count()
+    }
+
+    /// Get the scores of every seat after the seat wins with the given payment.
+    pub fn scores_after(
+        &self,
+        seat: Seat,
+        method: WinMethod,
+        payment: &Payment,
+    ) -> [PlayerPoints; 4] {
+        // an invalid ron pays nothing to anyone
+        let deltas = Settlement::new(seat, method, self.honba, self.riichi_sticks)
+            .map_or([0; 4], |settlement| settlement.deltas(payment));
+
+        let mut scores = self.scores;
+        for (score, delta) in scores.iter_mut().zip(deltas) {
+            *score += delta;
+        }
+        scores
+    }
+
+    /// List the smallest hands the seat needs to finish at or above the target placement, for ron
+    /// off each opponent and for tsumo.
+    pub fn requirements(&self, seat: Seat, target: usize) -> Vec<Requirement> {
+        Seat::ALL
+            .into_iter()
+            .filter(|&other| other != seat)
+            .map(WinMethod::Ron)
+            .chain([WinMethod::Tsumo])
+            .map(|method| Requirement {
+                method,
+                options: self.options(seat, method, target),
+            })
+            .collect()
+    }
+
+    /// Find the smallest hand for each han count, stopping once a hand at the lowest fu is enough.
+    fn options(&self, seat: Seat, method: WinMethod, target: usize) -> Vec<HandValue> {
+        let tsumo = method == WinMethod::Tsumo;
+        let mut options: Vec<HandValue> = vec![];
+
+        for han in 1..=13 {
+            // chiitoitsu is always scored at 25 fu, so it is left out of the regular fu steps
+            let fu_options = TABLE_FU
+                .into_iter()
+                .filter(|&fu| fu != 25 && calc::is_possible_han_fu(han, fu, tsumo));
+
+            for (i, fu) in fu_options.enumerate() {
+                // can unwrap here because han and fu are never 0
+                let payment = calc::calculate(han, fu).unwrap();
+                let scores = self.scores_after(seat, method, &payment);
+                if Self::placement(&scores, seat) > target {
+                    continue;
+                }
+
+                let value = match LimitHands::get_limit_hand(han, fu) {
+                    Some(limit) => HandValue::Limit(limit),
+                    None => HandValue::HanFu(han, fu),
+                };
+                if !options.contains(&value) {
+                    options.push(value);
+                }
+
+                // a higher han count will never need less than this
+                if i == 0 || matches!(value, HandValue::Limit(_)) {
+                    return options;
+                }
+                break;
+            }
+        }
+
+        options
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn placement_tie_break() {
+        let scores = [25_000, 25_000, 25_000, 25_000];
+        assert_eq!(AllLast::placement(&scores, Seat::South), 1);
+        assert_eq!(AllLast::placement(&scores, Seat::West), 2);
+        assert_eq!(AllLast::placement(&scores, Seat::North), 3);
+        assert_eq!(AllLast::placement(&scores, Seat::East), 4);
+    }
+
+    #[test]
+    fn scores_after_riichi_sticks_and_honba() {
+        let table = AllLast::new([25_000, 25_000, 24_000, 24_000], 2, 2);
+        let payment = calc::calculate(1, 30).unwrap();
+
+        let scores = table.scores_after(Seat::West, WinMethod::Ron(Seat::East), &payment);
+        assert_eq!(scores, [23_400, 25_000, 27_600, 24_000]);
+
+        let scores = table.scores_after(Seat::East, WinMethod::Tsumo, &payment);
+        assert_eq!(scores, [29_100, 24_300, 23_300, 23_300]);
+    }
+}
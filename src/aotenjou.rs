@@ -0,0 +1,402 @@
+use crate::calc::YonmaRules;
+use crate::hand::error::HandErr;
+use crate::hand::Hand;
+use crate::payment::Points;
+use crate::score::{FuValue, HanValue, HonbaCounter, Score};
+use crate::tile_group::TileGroup;
+
+/// Han each yakuman is worth without limits.
+pub const YAKUMAN_HAN: HanValue = 13;
+
+/// Most han a hand can be scored with, far above any real hand.
+pub const MAX_HAN: HanValue = 1_000;
+
+/// Most doublings of the base points done in one multiplication, so limbs cannot overflow.
+const DOUBLING_STEP: HanValue = 20;
+
+/// Each limb holds nine decimal digits.
+const LIMB: u64 = 1_000_000_000;
+
+/// Points with no upper limit, for hands that overflow [`Points`].
+///
+/// # Examples
+///
+/// ```rust
+/// use mahc::aotenjou::BigPoints;
+///
+/// let points = BigPoints::from(u64::MAX).mul_small(10);
+/// assert_eq!(points.to_string(), "184467440737095516150");
+/// assert_eq!(points.to_points(), None);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BigPoints {
+    /// Base 10^9 digits, least significant first.
+    limbs: Vec<u64>,
+}
+
+impl From<Points> for BigPoints {
+    fn from(value: Points) -> Self {
+        let mut limbs = vec![];
+        let mut value = value;
+        while value != 0 {
+            limbs.push(value % LIMB);
+            value /= LIMB;
+        }
+        Self { limbs }
+    }
+}
+
+impl std::fmt::Display for BigPoints {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Some((last, rest)) = self.limbs.split_last() else {
+            return write!(f, "0");
+        };
+        write!(f, "{last}")?;
+        for limb in rest.iter().rev() {
+            write!(f, "{limb:09}")?;
+        }
+        Ok(())
+    }
+}
+
+impl BigPoints {
+    /// Multiply by a small number.
+    pub fn mul_small(&self, by: u32) -> Self {
+        let mut limbs = Vec::with_capacity(self.limbs.len() + 1);
+        let mut carry = 0;
+        for limb in &self.limbs {
+            let product = limb * by as u64 + carry;
+            limbs.push(product % LIMB);
+            carry = product / LIMB;
+        }
+        while carry != 0 {
+            limbs.push(carry % LIMB);
+            carry /= LIMB;
+        }
+        Self { limbs }.trimmed()
+    }
+
+    /// Add another amount of points.
+    pub fn add(&self, other: &Self) -> Self {
+        let len = self.limbs.len().max(other.limbs.len());
+        let mut limbs = Vec::with_capacity(len + 1);
+        let mut carry = 0;
+        for i in 0..len {
+            let sum = self.limbs.get(i).unwrap_or(&0) + other.limbs.get(i).unwrap_or(&0) + carry;
+            limbs.push(sum % LIMB);
+            carry = sum / LIMB;
+        }
+        if carry != 0 {
+            limbs.push(carry);
+        }
+        Self { limbs }
+    }
+
+    /// Round up to the nearest 100, as every payment is.
+    pub fn round_up_hundred(&self) -> Self {
+        match self.limbs.first() {
+            Some(lowest) if lowest % 100 != 0 => self.add(&Self::from(100 - lowest % 100)),
+            _ => self.clone(),
+        }
+    }
+
+    /// Get the points as [`Points`], if they fit.
+    pub fn to_points(&self) -> Option<Points> {
+        self.limbs
+            .iter()
+            .rev()
+            .try_fold(0 as Points, |total, &limb| {
+                total.checked_mul(LIMB)?.checked_add(limb)
+            })
+    }
+
+    fn trimmed(mut self) -> Self {
+        while self.limbs.last() == Some(&0) {
+            self.limbs.pop();
+        }
+        self
+    }
+}
+
+/// Payment amounts with no limit hands (aotenjou).
+///
+/// The base points are always `fu * 2 ^ (2 + han)`.
+///
+/// # Examples
+///
+/// ```rust
+/// use mahc::aotenjou::AotenjouPayment;
+///
+/// // 13 han 30 fu would be a yakuman, but is scored as is.
+/// let payment = AotenjouPayment::from_han_and_fu(13, 30).unwrap();
+/// assert_eq!(payment.non_dealer_ron(0).to_string(), "3932200");
+/// assert_eq!(payment.dealer_tsumo(1).to_string(), "1966200");
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct AotenjouPayment {
+    base_points: BigPoints,
+    /// The number of points each honba (repeat counter) is worth.
+    tsumibou: Points,
+}
+
+impl AotenjouPayment {
+    /// Calculate the payment amounts from the han and fu, without limits.
+    ///
+    /// Han above [`MAX_HAN`] are rejected.
+    pub fn from_han_and_fu(han: HanValue, fu: FuValue) -> Result<Self, HandErr> {
+        if han == 0 {
+            return Err(HandErr::NoHan);
+        }
+        if han > MAX_HAN {
+            return Err(HandErr::InvalidHan);
+        }
+        if fu == 0 {
+            return Err(HandErr::NoFu);
+        }
+
+        // Chiitoitsu (seven pairs) does not round the fu.
+        let fu = if fu == 25 { fu } else { fu.div_ceil(10) * 10 };
+        let mut base_points = BigPoints::from(fu);
+        let mut doublings = han + 2;
+        while doublings > 0 {
+            let step = doublings.min(DOUBLING_STEP);
+            base_points = base_points.mul_small(1 << step);
+            doublings -= step;
+        }
+
+        Ok(Self {
+            base_points,
+            tsumibou: 300,
+        })
+    }
+
+    /// Get the base points.
+    pub fn base_points(&self) -> &BigPoints {
+        &self.base_points
+    }
+
+    fn pay(&self, multiplier: u32, tsumibou: Points, honba: HonbaCounter) -> BigPoints {
+        self.base_points
+            .mul_small(multiplier)
+            .add(&BigPoints::from(tsumibou * honba))
+            .round_up_hundred()
+    }
+
+    /// Get the amount of points the player that dealt-in has to pay to a dealer.
+    pub fn dealer_ron(&self, honba: HonbaCounter) -> BigPoints {
+        self.pay(6, self.tsumibou, honba)
+    }
+
+    /// Get the amount of points each player pays when the dealer tsumos.
+    pub fn dealer_tsumo(&self, honba: HonbaCounter) -> BigPoints {
+        self.pay(2, self.tsumibou / 3, honba)
+    }
+
+    /// Get the amount the player that dealt-in pays to a non-dealer.
+    pub fn non_dealer_ron(&self, honba: HonbaCounter) -> BigPoints {
+        self.pay(4, self.tsumibou, honba)
+    }
+
+    /// Get the amount the dealer pays when a non-dealer wins by tsumo.
+    pub fn non_dealer_tsumo_to_dealer(&self, honba: HonbaCounter) -> BigPoints {
+        self.pay(2, self.tsumibou / 3, honba)
+    }
+
+    /// Get the amount non-dealer players pay when a non-dealer wins by tsumo.
+    pub fn non_dealer_tsumo_to_non_dealer(&self, honba: HonbaCounter) -> BigPoints {
+        self.pay(1, self.tsumibou / 3, honba)
+    }
+}
+
+/// Score breakdown of a hand with no limits (aotenjou).
+#[derive(Debug)]
+pub struct AotenjouScore {
+    /// Yaku, fu, and dora of the hand, as scored with limits.
+    score: Score,
+    /// Total han, with every yakuman counted as 13 han.
+    han: HanValue,
+    payment: AotenjouPayment,
+}
+
+impl AotenjouScore {
+    /// Get the yaku, fu, and dora of the hand.
+    pub fn score(&self) -> &Score {
+        &self.score
+    }
+
+    /// Get the total han, with every yakuman counted as 13 han and dora included.
+    pub fn han(&self) -> HanValue {
+        self.han
+    }
+
+    /// Get the payment breakdown.
+    pub fn payment(&self) -> &AotenjouPayment {
+        &self.payment
+    }
+}
+
+/// Get the score breakdown of the hand with no limits (aotenjou).
+///
+/// Takes the same options as [`crate::calc::get_hand_score`], under the four-player rules given.
+///
+/// # Examples
+///
+/// ```rust
+/// use mahc::aotenjou;
+/// use mahc::calc::YonmaRules;
+/// use mahc::hand::Hand;
+///
+/// let tiles = ["555z", "666z", "777z", "222zo", "11z"].map(|t| t.to_string()).to_vec();
+/// let hand = Hand::new_from_strings(tiles, "1z".into(), "1z".into(), "3z".into()).unwrap();
+/// let rules = YonmaRules::default();
+/// let score = aotenjou::get_hand_score(
+///     &rules, hand, None, false, false, false, false, false, false, false, false, 0,
+/// )
+/// .unwrap();
+///
+/// // daisangen and tsuuiisou are 26 han
+/// assert_eq!(score.han(), 26);
+/// assert_eq!(score.score().fu_score(), 60);
+/// assert_eq!(score.payment().non_dealer_ron(0).to_string(), "64424509500");
+/// ```
+#[allow(clippy::too_many_arguments)]
+pub fn get_hand_score(
+    rules: &YonmaRules,
+    hand: Hand,
+    dora: Option<Vec<TileGroup>>,
+    tsumo: bool,
+    riichi: bool,
+    doubleriichi: bool,
+    ippatsu: bool,
+    haitei: bool,
+    rinshan: bool,
+    chankan: bool,
+    tenhou: bool,
+    honba: HonbaCounter,
+) -> Result<AotenjouScore, HandErr> {
+    let score = rules.get_hand_score(
+        hand,
+        dora,
+        tsumo,
+        riichi,
+        doubleriichi,
+        ippatsu,
+        haitei,
+        rinshan,
+        chankan,
+        tenhou,
+        honba,
+    )?;
+
+    let yakuman = score.yaku().iter().filter(|yaku| yaku.is_yakuman()).count() as HanValue;
+    let han = if yakuman != 0 {
+        yakuman * YAKUMAN_HAN + score.dora_count()
+    } else {
+        score.han()
+    };
+    let payment = AotenjouPayment::from_han_and_fu(han, score.fu_score())?;
+
+    Ok(AotenjouScore {
+        score,
+        han,
+        payment,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn big_points_match_points() {
+        for han in 1..=4 {
+            for fu in [20, 25, 30, 40, 70, 110] {
+                let payment = AotenjouPayment::from_han_and_fu(han, fu).unwrap();
+                let expected = crate::payment::Payment::from_han_and_fu(han, fu);
+                for honba in 0..3 {
+                    assert_eq!(
+                        payment.dealer_ron(honba).to_points(),
+                        Some(expected.dealer_ron(honba))
+                    );
+                    assert_eq!(
+                        payment.non_dealer_tsumo_to_non_dealer(honba).to_points(),
+                        Some(expected.non_dealer_tsumo_to_non_dealer(honba))
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn aotenjou_past_u64() {
+        // 30 * 2^102 base points, far past what fits in a u64
+        let payment = AotenjouPayment::from_han_and_fu(100, 30).unwrap();
+        assert_eq!(
+            payment.base_points().to_string(),
+            "152118072027387528179604384645120"
+        );
+        assert_eq!(payment.base_points().to_points(), None);
+        assert_eq!(
+            payment.non_dealer_ron(1).to_string(),
+            "608472288109550112718417538580800"
+        );
+    }
+
+    #[test]
+    fn aotenjou_large_han() {
+        // 30 * 2^1002 base points has 304 digits
+        let payment = AotenjouPayment::from_han_and_fu(MAX_HAN, 30).unwrap();
+        let base_points = payment.base_points().to_string();
+        assert_eq!(base_points.len(), 304);
+        assert!(base_points.starts_with("12858"));
+
+        assert_eq!(
+            AotenjouPayment::from_han_and_fu(MAX_HAN + 1, 30).unwrap_err(),
+            HandErr::InvalidHan
+        );
+        assert_eq!(
+            AotenjouPayment::from_han_and_fu(HanValue::MAX, 30).unwrap_err(),
+            HandErr::InvalidHan
+        );
+    }
+
+    #[test]
+    fn aotenjou_counts_dora() {
+        let tiles = ["234p", "234p", "567s", "888m", "55p"].map(|t| t.to_string());
+        let hand =
+            Hand::new_from_strings(tiles.to_vec(), "5p".into(), "Ew".into(), "Sw".into()).unwrap();
+        let dora = vec!["7m".to_string().try_into().unwrap()];
+        let score = get_hand_score(
+            &YonmaRules::default(),
+            hand,
+            Some(dora),
+            false,
+            true,
+            false,
+            false,
+            false,
+            false,
+            false,
+            false,
+            0,
+        )
+        .unwrap();
+
+        // riichi, tanyao, iipeikou, and 3 dora would be a haneman, but pay 6 han 40 fu as is
+        assert_eq!(score.han(), 6);
+        assert_eq!(score.score().fu_score(), 40);
+        assert_eq!(score.payment().non_dealer_ron(0).to_string(), "41000");
+    }
+
+    #[test]
+    fn aotenjou_invalid() {
+        assert_eq!(
+            AotenjouPayment::from_han_and_fu(0, 30).unwrap_err(),
+            HandErr::NoHan
+        );
+        assert_eq!(
+            AotenjouPayment::from_han_and_fu(1, 0).unwrap_err(),
+            HandErr::NoFu
+        );
+    }
+}
@@ -2,8 +2,9 @@ use crate::fu::{calculate_total_fu_value, Fu};
 use crate::hand::error::HandErr;
 use crate::hand::Hand;
 use crate::limit_hand::LimitHands;
-use crate::payment::Payment;
+use crate::payment::{ClaimedPayment, Payment};
 use crate::score::{FuValue, HanValue, HonbaCounter, Score};
+use crate::score_table::TableRules;
 use crate::tile_group::TileGroup;
 use crate::yaku::Yaku;
 
@@ -24,7 +25,125 @@ impl std::fmt::Display for CalculatorErrors {
     }
 }
 
+/// Fu values that appear on a score table.
+pub(crate) const TABLE_FU: [FuValue; 11] = [20, 25, 30, 40, 50, 60, 70, 80, 90, 100, 110];
+
+/// The value of a hand, either as han and fu or as a limit hand.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum HandValue {
+    HanFu(HanValue, FuValue),
+    Limit(LimitHands),
+}
+
+impl std::fmt::Display for HandValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::HanFu(han, fu) => write!(f, "{han} Han/ {fu} Fu"),
+            Self::Limit(limit) => write!(f, "{limit}"),
+        }
+    }
+}
+
+/// Hand values that produce a claimed payment, split by who won.
+#[derive(Debug, Default, PartialEq)]
+pub struct ReverseLookup {
+    /// Hand values that produce the payment when the dealer wins.
+    pub dealer: Vec<HandValue>,
+    /// Hand values that produce the payment when a non-dealer wins.
+    pub non_dealer: Vec<HandValue>,
+}
+
+/// Rules for four-player games.
+///
+/// Nukidora are usually only taken in sanma (see [`crate::sanma::SanmaRules`]), but some
+/// four-player rules let players set aside north tiles too.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct YonmaRules {
+    /// Whether north tiles can be set aside as nukidora.
+    pub nukidora: bool,
+    /// Whether the replacement draw after a nukidora counts for rinshan kaihou.
+    pub nuki_rinshan: bool,
+}
+
+impl YonmaRules {
+    /// Get the score breakdown of a four-player hand under these rules.
+    ///
+    /// Takes the same options as [`get_hand_score`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use mahc::calc::YonmaRules;
+    /// use mahc::hand::error::HandErr;
+    /// use mahc::hand::Hand;
+    ///
+    /// let tiles = ["123p", "456p", "789s", "rrrd", "EEw", "Nwk"].map(|t| t.to_string()).to_vec();
+    /// let hand = Hand::new_from_strings(tiles, "3p".into(), "Ew".into(), "Sw".into()).unwrap();
+    ///
+    /// let rules = YonmaRules::default();
+    /// let score = rules.get_hand_score(
+    ///     hand.clone(), None, false, false, false, false, false, false, false, false, 0,
+    /// );
+    /// assert_eq!(score.unwrap_err(), HandErr::NukidoraOutsideSanma);
+    ///
+    /// let rules = YonmaRules { nukidora: true, ..YonmaRules::default() };
+    /// let score = rules.get_hand_score(
+    ///     hand, None, false, false, false, false, false, false, false, false, 0,
+    /// );
+    /// // chun and the nukidora
+    /// assert_eq!(score.unwrap().han(), 2);
+    /// ```
+    #[allow(clippy::too_many_arguments)]
+    pub fn get_hand_score(
+        &self,
+        hand: Hand,
+        dora: Option<Vec<TileGroup>>,
+        tsumo: bool,
+        riichi: bool,
+        doubleriichi: bool,
+        ippatsu: bool,
+        haitei: bool,
+        rinshan: bool,
+        chankan: bool,
+        tenhou: bool,
+        honba: HonbaCounter,
+    ) -> Result<Score, HandErr> {
+        if hand.nukidora() > 0 && !self.nukidora {
+            return Err(HandErr::NukidoraOutsideSanma);
+        }
+        let nuki_rinshan = self.nuki_rinshan && hand.nukidora() > 0;
+        if hand.kans().is_empty() && !nuki_rinshan && rinshan {
+            return Err(HandErr::RinshanKanWithoutKan);
+        }
+
+        let dora_tiles: Vec<TileGroup> = dora
+            .unwrap_or_default()
+            .iter()
+            .map(|tile| tile.next_tile())
+            .collect::<Result<_, _>>()?;
+
+        score_hand(
+            hand,
+            &dora_tiles,
+            tsumo,
+            riichi,
+            doubleriichi,
+            ippatsu,
+            haitei,
+            rinshan,
+            chankan,
+            tenhou,
+            honba,
+        )
+    }
+}
+
 /// Get the score breakdown of the hand.
+///
+/// Nukidora are only taken under [`YonmaRules`] that allow them, or in sanma with
+/// [`crate::sanma::SanmaRules::get_hand_score`].
+#[allow(clippy::too_many_arguments)]
 pub fn get_hand_score(
     hand: Hand,
     dora: Option<Vec<TileGroup>>,
@@ -38,10 +157,37 @@ pub fn get_hand_score(
     tenhou: bool,
     honba: HonbaCounter,
 ) -> Result<Score, HandErr> {
-    if hand.kans().is_empty() && rinshan {
-        return Err(HandErr::RinshanKanWithoutKan);
-    }
+    YonmaRules::default().get_hand_score(
+        hand,
+        dora,
+        tsumo,
+        riichi,
+        doubleriichi,
+        ippatsu,
+        haitei,
+        rinshan,
+        chankan,
+        tenhou,
+        honba,
+    )
+}
 
+/// Get the score breakdown of the hand, given the dora tiles themselves instead of their
+/// indicators.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn score_hand(
+    hand: Hand,
+    dora_tiles: &[TileGroup],
+    tsumo: bool,
+    riichi: bool,
+    doubleriichi: bool,
+    ippatsu: bool,
+    haitei: bool,
+    rinshan: bool,
+    chankan: bool,
+    tenhou: bool,
+    honba: HonbaCounter,
+) -> Result<Score, HandErr> {
     let yaku = get_yaku_han(
         &hand,
         riichi,
@@ -75,7 +221,7 @@ pub fn get_hand_score(
     };
 
     // get han from dora tiles
-    let dora_count = hand.get_dora_count(dora);
+    let dora_count = hand.count_dora(dora_tiles);
 
     let han = yaku.0 + dora_count;
     let fu_value = calculate_total_fu_value(&fu);
@@ -108,6 +254,7 @@ pub fn get_hand_score(
 }
 
 /// Get the yaku score and list of yaku given a hand and some round context.
+#[allow(clippy::too_many_arguments)]
 pub fn get_yaku_han(
     hand: &Hand,
     riichi: bool,
@@ -236,3 +383,152 @@ pub fn calculate(han: HanValue, fu: FuValue) -> Result<Payment, HandErr> {
 
     Ok(payment)
 }
+
+/// Calculate the payment amounts, rounding 4 han 30 fu and 3 han 60 fu up to mangan (kiriage
+/// mangan).
+///
+/// # Examples
+///
+/// ```rust
+/// use mahc::calc::calculate_kiriage;
+///
+/// let payment = calculate_kiriage(4, 30).unwrap();
+/// assert_eq!(payment.non_dealer_ron(0), 8_000);
+///
+/// let payment = calculate_kiriage(4, 25).unwrap();
+/// assert_eq!(payment.non_dealer_ron(0), 6_400);
+/// ```
+pub fn calculate_kiriage(han: HanValue, fu: FuValue) -> Result<Payment, HandErr> {
+    let rounded_fu = if fu == 25 { fu } else { fu.div_ceil(10) * 10 };
+    if (han == 4 && rounded_fu == 30) || (han == 3 && rounded_fu == 60) {
+        return Ok(LimitHands::Mangan.get_score());
+    }
+
+    calculate(han, fu)
+}
+
+/// Round the payment of a scored hand up to mangan under kiriage mangan. Yakuman are left as
+/// they are, and sanma hands keep being paid out as sanma.
+///
+/// # Examples
+///
+/// ```rust
+/// use mahc::calc::{apply_kiriage, get_hand_score};
+/// use mahc::hand::Hand;
+///
+/// // Riichi, pinfu, tanyao, and dora 1 at 30 fu: 4 han 30 fu counted as a mangan.
+/// let hand = Hand::new_from_strings(
+///     vec!["22m".to_string(), "678p".to_string(), "345s".to_string(), "567m".to_string(),
+///          "234p".to_string()],
+///     "2p".to_string(),
+///     "Ew".to_string(),
+///     "Sw".to_string(),
+/// )
+/// .unwrap();
+/// let mut score = get_hand_score(
+///     hand, Some(vec!["1p".to_string().try_into().unwrap()]),
+///     false, true, false, false, false, false, false, false, 0,
+/// )
+/// .unwrap();
+/// assert_eq!(score.payment().non_dealer_ron(0), 7_700);
+///
+/// apply_kiriage(&mut score).unwrap();
+/// assert_eq!(score.payment().non_dealer_ron(0), 8_000);
+/// ```
+pub fn apply_kiriage(score: &mut Score) -> Result<(), HandErr> {
+    if score.yaku().iter().any(Yaku::is_yakuman) {
+        return Ok(());
+    }
+
+    let base_points = calculate_kiriage(score.han(), score.fu_score())?.base_points();
+    score.payment_mut().set_base_points(base_points);
+    Ok(())
+}
+
+/// Check if the han and fu combination can be scored by a real hand.
+pub(crate) fn is_possible_han_fu(han: HanValue, fu: FuValue, tsumo: bool) -> bool {
+    match fu {
+        // Only pinfu tsumo is scored at 20 fu, which always has menzen tsumo.
+        20 => tsumo && han >= 2,
+        // Only chiitoitsu is scored at 25 fu.
+        25 => han >= 2 + u32::from(tsumo),
+        _ => true,
+    }
+}
+
+/// Find every han and fu combination, or limit hand, that produces the claimed payment under
+/// the rules (kiriage, honba and sanma).
+///
+/// Limit hands are listed once, after the han and fu combinations.
+///
+/// # Examples
+///
+/// ```rust
+/// use mahc::calc::{reverse_lookup, HandValue};
+/// use mahc::payment::ClaimedPayment;
+/// use mahc::score_table::TableRules;
+///
+/// let lookup = reverse_lookup(&ClaimedPayment::Ron(3_900), &TableRules::default());
+///
+/// assert_eq!(
+///     lookup.dealer,
+///     vec![HandValue::HanFu(1, 80), HandValue::HanFu(2, 40)]
+/// );
+/// assert_eq!(
+///     lookup.non_dealer,
+///     vec![HandValue::HanFu(2, 60), HandValue::HanFu(3, 30)]
+/// );
+/// ```
+pub fn reverse_lookup(claim: &ClaimedPayment, rules: &TableRules) -> ReverseLookup {
+    let honba = rules.honba;
+    let tsumo = !matches!(claim, ClaimedPayment::Ron(_));
+    let mut lookup = ReverseLookup::default();
+    let mut limits = ReverseLookup::default();
+
+    for han in 1..=13 {
+        for fu in TABLE_FU {
+            if !is_possible_han_fu(han, fu, tsumo) {
+                continue;
+            }
+
+            // can unwrap here because han and fu are never 0
+            let mut payment = if rules.kiriage {
+                calculate_kiriage(han, fu).unwrap()
+            } else {
+                calculate(han, fu).unwrap()
+            };
+            if let Some(sanma) = rules.sanma {
+                payment.set_sanma(sanma.tsumo, sanma.tsumibou);
+            }
+            let (dealer, non_dealer) = match *claim {
+                ClaimedPayment::Ron(points) => (
+                    payment.dealer_ron(honba) == points,
+                    payment.non_dealer_ron(honba) == points,
+                ),
+                ClaimedPayment::DealerTsumo(points) => {
+                    (payment.dealer_tsumo(honba) == points, false)
+                }
+                ClaimedPayment::NonDealerTsumo { non_dealer, dealer } => (
+                    false,
+                    payment.non_dealer_tsumo_to_non_dealer(honba) == non_dealer
+                        && payment.non_dealer_tsumo_to_dealer(honba) == dealer,
+                ),
+            };
+
+            let (found, value) = match LimitHands::get_limit_hand(han, fu) {
+                Some(limit) => (&mut limits, HandValue::Limit(limit)),
+                None => (&mut lookup, HandValue::HanFu(han, fu)),
+            };
+            if dealer && !found.dealer.contains(&value) {
+                found.dealer.push(value);
+            }
+            if non_dealer && !found.non_dealer.contains(&value) {
+                found.non_dealer.push(value);
+            }
+        }
+    }
+
+    lookup.dealer.append(&mut limits.dealer);
+    lookup.non_dealer.append(&mut limits.non_dealer);
+    lookup
+}
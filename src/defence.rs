@@ -0,0 +1,331 @@
+use crate::hand::error::HandErr;
+use crate::suit::Suit;
+use crate::tile_group::{count_tile, GroupType, TileGroup};
+
+/// How safe a tile is to discard against a riichi player.
+///
+/// Listed from safest to most dangerous.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Safety {
+    /// The riichi player has discarded this tile, or let it pass after riichi, so they cannot ron
+    /// on it.
+    Genbutsu,
+    /// Every two-sided wait on this tile would also wait on a tile the riichi player is furiten
+    /// on.
+    Suji,
+    /// Every two-sided wait on this tile is blocked by suji, or by all four copies of a tile it
+    /// needs being visible (no-chance).
+    Kabe,
+    /// Every two-sided wait on this tile is blocked by suji or kabe, or needs the last copy of a
+    /// tile.
+    OneChance,
+    /// An honor tile with the given number of copies visible.
+    Honor(usize),
+    /// None of the above.
+    Dangerous,
+}
+
+impl std::fmt::Display for Safety {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Genbutsu => write!(f, "Genbutsu"),
+            Self::Suji => write!(f, "Suji"),
+            Self::Kabe => write!(f, "Kabe"),
+            Self::OneChance => write!(f, "One-chance"),
+            Self::Honor(visible) => write!(f, "Honor: {visible} visible"),
+            Self::Dangerous => write!(f, "Dangerous"),
+        }
+    }
+}
+
+/// How well a two-sided wait on a tile is blocked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Block {
+    Suji,
+    Kabe,
+    OneChance,
+    Open,
+}
+
+/// An opponent that has declared riichi.
+#[derive(Debug, Clone)]
+pub struct RiichiOpponent {
+    /// Tiles the opponent has discarded, in order.
+    pond: Vec<TileGroup>,
+    /// Turn the opponent declared riichi on, counted from `1` for their first discard.
+    riichi_turn: usize,
+    /// Tiles the other players discarded from the riichi on, which the opponent let pass.
+    passed: Vec<TileGroup>,
+    /// Tiles the other players discarded, at any time.
+    others: Vec<TileGroup>,
+}
+
+impl RiichiOpponent {
+    /// Create a new [`RiichiOpponent`].
+    ///
+    /// The riichi turn must point at a tile in the pond.
+    pub fn new(pond: Vec<TileGroup>, riichi_turn: usize) -> Result<Self, HandErr> {
+        if riichi_turn == 0 || riichi_turn > pond.len() {
+            return Err(HandErr::InvalidRiichiTurn);
+        }
+
+        Ok(Self {
+            pond,
+            riichi_turn,
+            passed: vec![],
+            others: vec![],
+        })
+    }
+
+    /// Add the ponds of the other players, in turn order from the player after the opponent.
+    ///
+    /// Their turns are counted from `1` like the riichi turn, ignoring calls. A tile they
+    /// discarded from the riichi turn on was let pass, so the opponent is furiten on it.
+    pub fn with_other_ponds(mut self, ponds: [Vec<TileGroup>; 3]) -> Self {
+        for pond in ponds {
+            let passed = pond.iter().skip(self.riichi_turn - 1);
+            self.passed.extend(passed.flat_map(|group| group.tiles()));
+            self.others.extend(pond);
+        }
+        self
+    }
+
+    /// Get the tiles the opponent has discarded.
+    pub fn pond(&self) -> &[TileGroup] {
+        &self.pond
+    }
+
+    /// Get the tile the opponent declared riichi with.
+    pub fn riichi_tile(&self) -> TileGroup {
+        self.pond[self.riichi_turn - 1].clone()
+    }
+
+    /// Get the tiles the opponent discarded from their riichi tile on, once their wait was fixed.
+    pub fn riichi_pond(&self) -> &[TileGroup] {
+        &self.pond[self.riichi_turn - 1..]
+    }
+
+    /// Get the tiles the other players discarded from the riichi on, which the opponent let pass.
+    pub fn passed(&self) -> &[TileGroup] {
+        &self.passed
+    }
+
+    /// Check if the opponent cannot ron on a tile, as they discarded it or let it pass.
+    fn is_furiten(&self, tile: &TileGroup) -> bool {
+        count_tile(&self.pond, tile) + count_tile(&self.passed, tile) != 0
+    }
+
+    /// Get the safety of each unique tile in our hand.
+    ///
+    /// Visible tiles are every tile we can see other than the ponds and our hand, such as called
+    /// melds and dora indicators.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use mahc::defence::{RiichiOpponent, Safety};
+    /// use mahc::tile_group::TileGroup;
+    ///
+    /// let to_tiles = |tiles: &[&str]| -> Vec<TileGroup> {
+    ///     tiles.iter().map(|t| t.to_string().try_into().unwrap()).collect()
+    /// };
+    /// let opponent = RiichiOpponent::new(to_tiles(&["Nw", "9m", "4p", "1s"]), 2).unwrap();
+    /// let hand = to_tiles(&["9m", "1p", "7p", "Nw", "5s"]);
+    ///
+    /// let safety = opponent.analyse(&hand, &[]);
+    /// assert_eq!(safety[0].1, Safety::Genbutsu);
+    /// assert_eq!(safety[1].1, Safety::Suji);
+    /// assert_eq!(safety[2].1, Safety::Suji);
+    /// assert_eq!(safety[3].1, Safety::Genbutsu);
+    /// assert_eq!(safety[4].1, Safety::Dangerous);
+    /// ```
+    pub fn analyse(&self, hand: &[TileGroup], visible: &[TileGroup]) -> Vec<(TileGroup, Safety)> {
+        let hand: Vec<TileGroup> = hand.iter().flat_map(|group| group.tiles()).collect();
+        let seen: Vec<TileGroup> = self
+            .pond
+            .iter()
+            .chain(self.others.iter())
+            .chain(visible.iter())
+            .chain(hand.iter())
+            .flat_map(|group| group.tiles())
+            .collect();
+
+        let mut safety: Vec<(TileGroup, Safety)> = vec![];
+        for tile in hand {
+            if safety.iter().any(|(t, _)| t.is_same_tile(&tile)) {
+                continue;
+            }
+            let tile_safety = self.safety(&tile, &seen);
+            safety.push((tile, tile_safety));
+        }
+
+        safety
+    }
+
+    /// Get the safety of a single tile, given every tile that can be seen.
+    ///
+    /// Any tile in the pond, or passed after the riichi (see [`RiichiOpponent::passed`]), is
+    /// genbutsu. Furiten covers the whole wait, so suji is read from these tiles too.
+    pub fn safety(&self, tile: &TileGroup, seen: &[TileGroup]) -> Safety {
+        if self.is_furiten(tile) {
+            return Safety::Genbutsu;
+        }
+
+        if tile.is_honor() {
+            return Safety::Honor(count_tile(seen, tile));
+        }
+
+        // two-sided waits that win on this tile, as the two tiles held and the other winning tile
+        let value = tile.parse_u8().unwrap();
+        let mut waits: Vec<([u8; 2], u8)> = vec![];
+        if value >= 4 {
+            waits.push(([value - 2, value - 1], value - 3));
+        }
+        if value <= 6 {
+            waits.push(([value + 1, value + 2], value + 3));
+        }
+
+        let worst = waits
+            .into_iter()
+            .map(|(held, other)| self.block(&tile.suit, held, other, seen))
+            .max()
+            .unwrap();
+
+        match worst {
+            Block::Suji => Safety::Suji,
+            Block::Kabe => Safety::Kabe,
+            Block::OneChance => Safety::OneChance,
+            Block::Open => Safety::Dangerous,
+        }
+    }
+
+    /// Check how well a two-sided wait is blocked.
+    fn block(&self, suit: &Suit, held: [u8; 2], other: u8, seen: &[TileGroup]) -> Block {
+        if self.is_furiten(&single_tile(suit, other)) {
+            return Block::Suji;
+        }
+
+        let most_seen = held
+            .iter()
+            .map(|&value| count_tile(seen, &single_tile(suit, value)))
+            .max()
+            .unwrap();
+
+        match most_seen {
+            4 => Block::Kabe,
+            3 => Block::OneChance,
+            _ => Block::Open,
+        }
+    }
+}
+
+/// Create a single numbered tile.
+fn single_tile(suit: &Suit, value: u8) -> TileGroup {
+    TileGroup {
+        value: value.to_string(),
+        suit: suit.clone(),
+        isopen: false,
+        group_type: GroupType::None,
+        isterminal: value == 1 || value == 9,
+        isaka: false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn to_tiles(tiles: &[&str]) -> Vec<TileGroup> {
+        tiles
+            .iter()
+            .map(|tile| tile.to_string().try_into().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn safety_middle_suji() {
+        let opponent = RiichiOpponent::new(to_tiles(&["1m", "7m", "Ew"]), 1).unwrap();
+        let seen = to_tiles(&["1m", "7m", "Ew"]);
+
+        let tile = to_tiles(&["4m"]).remove(0);
+        assert_eq!(opponent.safety(&tile, &seen), Safety::Suji);
+
+        // only one side of 5m is covered by suji
+        let tile = to_tiles(&["5m"]).remove(0);
+        assert_eq!(opponent.safety(&tile, &seen), Safety::Dangerous);
+    }
+
+    #[test]
+    fn safety_kabe_and_one_chance() {
+        let opponent = RiichiOpponent::new(to_tiles(&["Sw", "rd"]), 2).unwrap();
+
+        // all four 8p are visible, so 9p can only be won on a single or pair wait
+        let seen = to_tiles(&["8888p"]);
+        let tile = to_tiles(&["9p"]).remove(0);
+        assert_eq!(opponent.safety(&tile, &seen), Safety::Kabe);
+
+        let seen = to_tiles(&["888p"]);
+        assert_eq!(opponent.safety(&tile, &seen), Safety::OneChance);
+
+        // 7p can still be won with 56p
+        let tile = to_tiles(&["7p"]).remove(0);
+        assert_eq!(opponent.safety(&tile, &seen), Safety::Dangerous);
+    }
+
+    #[test]
+    fn safety_honor_visible() {
+        let opponent = RiichiOpponent::new(to_tiles(&["1p"]), 1).unwrap();
+        let hand = to_tiles(&["ggd", "Ww"]);
+        let safety = opponent.analyse(&hand, &to_tiles(&["gd"]));
+
+        assert_eq!(safety.len(), 2);
+        assert_eq!(safety[0].1, Safety::Honor(3));
+        assert_eq!(safety[1].1, Safety::Honor(1));
+    }
+
+    #[test]
+    fn suji_from_the_whole_pond() {
+        let pond = to_tiles(&["1m", "4p", "Ew", "9s"]);
+        let seen = pond.clone();
+
+        // 4p was discarded before the riichi, but furiten still covers a 23p or 56p wait
+        let opponent = RiichiOpponent::new(pond, 3).unwrap();
+        let tile = to_tiles(&["1p"]).remove(0);
+        assert_eq!(opponent.safety(&tile, &seen), Safety::Suji);
+        let tile = to_tiles(&["1m"]).remove(0);
+        assert_eq!(opponent.safety(&tile, &seen), Safety::Genbutsu);
+        assert_eq!(opponent.riichi_pond(), to_tiles(&["Ew", "9s"]));
+    }
+
+    #[test]
+    fn passed_tiles_depend_on_riichi_turn() {
+        let pond = to_tiles(&["Nw", "Ew", "1s"]);
+        let others = [to_tiles(&["3m", "6p"]), to_tiles(&["Sw"]), vec![]];
+        let seen: Vec<TileGroup> = pond
+            .iter()
+            .chain(others.iter().flatten())
+            .cloned()
+            .collect();
+
+        // 6p was discarded on the riichi turn and let pass, 3m came before the riichi
+        let opponent = RiichiOpponent::new(pond, 2)
+            .unwrap()
+            .with_other_ponds(others);
+        assert_eq!(opponent.passed(), to_tiles(&["6p"]));
+        let tile = to_tiles(&["6p"]).remove(0);
+        assert_eq!(opponent.safety(&tile, &seen), Safety::Genbutsu);
+        let tile = to_tiles(&["9p"]).remove(0);
+        assert_eq!(opponent.safety(&tile, &seen), Safety::Suji);
+        let tile = to_tiles(&["3m"]).remove(0);
+        assert_eq!(opponent.safety(&tile, &seen), Safety::Dangerous);
+    }
+
+    #[test]
+    fn invalid_riichi_turn() {
+        let out = RiichiOpponent::new(to_tiles(&["1p", "2p"]), 3);
+        assert_eq!(out.unwrap_err(), HandErr::InvalidRiichiTurn);
+
+        let out = RiichiOpponent::new(to_tiles(&["1p", "2p"]), 0);
+        assert_eq!(out.unwrap_err(), HandErr::InvalidRiichiTurn);
+    }
+}
@@ -0,0 +1,514 @@
+use crate::fu::Fu;
+use crate::hand::Hand;
+use crate::score::Score;
+use crate::suit::Suit;
+use crate::tile_group::{GroupType, TileGroup};
+use crate::yaku::Yaku;
+
+/// A yaku or fu, with the groups or conditions behind it.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Reason<T> {
+    pub item: T,
+    pub reason: String,
+}
+
+/// Why each yaku and fu of a score applied, and why common yaku nearly did.
+///
+/// # Examples
+///
+/// ```rust
+/// use mahc::calc;
+/// use mahc::explain::Explanation;
+/// use mahc::hand::Hand;
+/// use mahc::yaku::Yaku;
+///
+/// let tiles = ["234p", "456p", "EEw", "234s", "789m"].map(|t| t.to_string()).to_vec();
+/// let hand = Hand::new_from_strings(tiles, "7m".into(), "Ew".into(), "Sw".into()).unwrap();
+/// let score = calc::get_hand_score(
+///     hand.clone(), None, false, true, false, false, false, false, false, false, 0,
+/// )
+/// .unwrap();
+///
+/// let explanation = Explanation::new(&hand, &score, false);
+/// assert_eq!(explanation.yaku[0].reason, "riichi declared");
+///
+/// let tanyao = explanation.denied.iter().find(|d| d.item == Yaku::Tanyao).unwrap();
+/// assert_eq!(tanyao.reason, "Ew in EEw");
+/// let pinfu = explanation.denied.iter().find(|d| d.item == Yaku::Pinfu).unwrap();
+/// assert_eq!(pinfu.reason, "pair of round wind");
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Explanation {
+    /// Every yaku of the score, in order.
+    pub yaku: Vec<Reason<Yaku>>,
+    /// Every fu of the score, in order.
+    pub fu: Vec<Reason<Fu>>,
+    /// Common yaku that were not awarded.
+    pub denied: Vec<Reason<Yaku>>,
+}
+
+/// Yaku that are worth explaining when they are missing.
+const COMMON_YAKU: [Yaku; 8] = [
+    Yaku::Tanyao,
+    Yaku::Pinfu,
+    Yaku::Yakuhai,
+    Yaku::Iipeikou,
+    Yaku::Toitoi,
+    Yaku::SanshokuDoujun,
+    Yaku::Ittsuu,
+    Yaku::Honitsu,
+];
+
+impl Explanation {
+    /// Explain the score of the hand.
+    pub fn new(hand: &Hand, score: &Score, tsumo: bool) -> Self {
+        let mut value_groups = value_groups(hand).into_iter();
+        let yaku = score
+            .yaku()
+            .iter()
+            .map(|yaku| {
+                let reason = match yaku {
+                    Yaku::Yakuhai => value_groups.next(),
+                    _ => None,
+                }
+                .unwrap_or_else(|| yaku_reason(hand, yaku, tsumo));
+                Reason {
+                    item: *yaku,
+                    reason,
+                }
+            })
+            .collect();
+
+        let mut meld_fu = meld_fu(hand, tsumo);
+        let fu = score
+            .fu()
+            .iter()
+            .map(|fu| {
+                let reason = match meld_fu.iter().position(|(meld, _)| meld == fu) {
+                    Some(i) => meld_fu.remove(i).1,
+                    None => fu_reason(hand, fu),
+                };
+                Reason { item: *fu, reason }
+            })
+            .collect();
+
+        let denied = if score.yaku().iter().any(|yaku| yaku.is_yakuman()) {
+            vec![]
+        } else {
+            COMMON_YAKU
+                .iter()
+                .filter(|yaku| !score.yaku().contains(yaku))
+                .filter_map(|yaku| {
+                    denied_reason(hand, score.yaku(), yaku).map(|reason| Reason {
+                        item: *yaku,
+                        reason,
+                    })
+                })
+                .collect()
+        };
+
+        Self { yaku, fu, denied }
+    }
+}
+
+fn join(groups: &[TileGroup]) -> String {
+    groups
+        .iter()
+        .map(|group| group.to_string())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn suit_name(suit: &Suit) -> &'static str {
+    match suit {
+        Suit::Manzu => "manzu",
+        Suit::Pinzu => "pinzu",
+        Suit::Souzu => "souzu",
+        Suit::Wind => "winds",
+        Suit::Dragon => "dragons",
+    }
+}
+
+fn triplets_and_kans(hand: &Hand) -> Vec<TileGroup> {
+    hand.triplets().into_iter().chain(hand.kans()).collect()
+}
+
+/// Get what makes each yakuhai, in the order they are counted.
+fn value_groups(hand: &Hand) -> Vec<String> {
+    let mut reasons = vec![];
+    for group in triplets_and_kans(hand) {
+        if group.value == hand.prev_tile().value {
+            reasons.push(format!("{group} of round wind"));
+        }
+        if group.value == hand.seat_tile().value {
+            reasons.push(format!("{group} of seat wind"));
+        }
+        if group.suit == Suit::Dragon {
+            reasons.push(format!("{group} of dragons"));
+        }
+    }
+    reasons
+}
+
+/// Get the value the pair is worth fu for, if any.
+fn value_pair(hand: &Hand, pair: &TileGroup) -> Option<&'static str> {
+    if pair.value == hand.seat_tile().value {
+        Some("seat wind")
+    } else if pair.value == hand.prev_tile().value {
+        Some("round wind")
+    } else if pair.suit == Suit::Dragon {
+        Some("dragons")
+    } else {
+        None
+    }
+}
+
+fn yaku_reason(hand: &Hand, yaku: &Yaku, tsumo: bool) -> String {
+    let sequences = hand.sequences();
+    match yaku {
+        Yaku::Riichi => "riichi declared".to_string(),
+        Yaku::DoubleRiichi => "riichi declared on the first turn".to_string(),
+        Yaku::Ippatsu => "won within a turn of riichi".to_string(),
+        Yaku::MenzenTsumo => "tsumo with a closed hand".to_string(),
+        Yaku::Haitei => "won on the last tile".to_string(),
+        Yaku::RinshanKaihou => "won on the replacement tile of a kan".to_string(),
+        Yaku::Chankan => "ron on a tile added to a kan".to_string(),
+        Yaku::Tenhou => "dealer won on the first draw".to_string(),
+        Yaku::Chiihou => "non-dealer won on the first draw".to_string(),
+        Yaku::Yakuhai => "triplet of dragons, seat wind or round wind".to_string(),
+        Yaku::Tanyao => format!("only simples in {}", join(hand.groups())),
+        Yaku::Pinfu => {
+            let pair = &hand.pairs()[0];
+            format!(
+                "closed, all sequences, {pair} pair is not a value tile, ryanmen wait on {}",
+                hand.win_tile()
+            )
+        }
+        Yaku::Iipeikou | Yaku::Ryanpeikou => {
+            let mut twice: Vec<TileGroup> = vec![];
+            for (i, seq) in sequences.iter().enumerate() {
+                let later = sequences[i + 1..].iter().any(|s| s.is_same_tile(seq));
+                if later && !twice.iter().any(|t| t.is_same_tile(seq)) {
+                    twice.push(seq.clone());
+                }
+            }
+            let twice: Vec<String> = twice.iter().map(|seq| format!("{seq} twice")).collect();
+            twice.join(" and ")
+        }
+        Yaku::SanshokuDoujun => {
+            let groups: Vec<TileGroup> = sequences
+                .iter()
+                .find_map(|seq| {
+                    let same: Vec<TileGroup> = [Suit::Manzu, Suit::Pinzu, Suit::Souzu]
+                        .iter()
+                        .filter_map(|suit| {
+                            sequences
+                                .iter()
+                                .find(|s| &s.suit == suit && s.value == seq.value)
+                                .cloned()
+                        })
+                        .collect();
+                    (same.len() == 3).then_some(same)
+                })
+                .unwrap_or_default();
+            format!("{} in all three suits", join(&groups))
+        }
+        Yaku::Ittsuu => {
+            let groups: Vec<TileGroup> = [Suit::Pinzu, Suit::Manzu, Suit::Souzu]
+                .iter()
+                .map(|suit| {
+                    ["1", "4", "7"]
+                        .iter()
+                        .filter_map(|value| {
+                            sequences
+                                .iter()
+                                .find(|s| &s.suit == suit && s.value == *value)
+                                .cloned()
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .find(|groups| groups.len() == 3)
+                .unwrap_or_default();
+            format!("{} in one suit", join(&groups))
+        }
+        Yaku::SanshokuDoukou => {
+            let groups = triplets_and_kans(hand);
+            let value = groups
+                .iter()
+                .find(|g| groups.iter().filter(|o| o.value == g.value).count() >= 3)
+                .map(|g| g.value.clone())
+                .unwrap_or_default();
+            let same: Vec<TileGroup> = groups.into_iter().filter(|g| g.value == value).collect();
+            format!("{} in all three suits", join(&same))
+        }
+        Yaku::Toitoi => format!("all triplets: {}", join(&triplets_and_kans(hand))),
+        Yaku::Sanankou => {
+            let last = hand.groups().last();
+            let closed: Vec<TileGroup> = triplets_and_kans(hand)
+                .into_iter()
+                .filter(|g| !g.isopen)
+                .filter(|g| tsumo || g.group_type == GroupType::Kan || Some(g) != last)
+                .collect();
+            format!("closed triplets {}", join(&closed))
+        }
+        Yaku::Sankantsu | Yaku::Suukantsu => format!("kans {}", join(&hand.kans())),
+        Yaku::Honitsu => {
+            let suit = hand
+                .groups()
+                .iter()
+                .find(|g| !g.is_honor())
+                .map(|g| suit_name(&g.suit))
+                .unwrap_or_default();
+            format!("only {suit} and honors")
+        }
+        Yaku::Chinitsu | Yaku::ChuurenPoutou | Yaku::ChuurenPoutou9SidedWait => {
+            format!("only {}", suit_name(&hand.groups()[0].suit))
+        }
+        Yaku::Shousangen => {
+            let dragons: Vec<TileGroup> = triplets_and_kans(hand)
+                .into_iter()
+                .filter(|g| g.suit == Suit::Dragon)
+                .collect();
+            format!("{} and {} pair", join(&dragons), hand.pairs()[0])
+        }
+        Yaku::Chantaiyao => "every group has a terminal or honor".to_string(),
+        Yaku::JunchanTaiyao => "every group has a terminal".to_string(),
+        Yaku::Honroutou => "only terminals and honors".to_string(),
+        Yaku::Chiitoitsu => format!("seven pairs: {}", join(&hand.pairs())),
+        Yaku::Daisangen => "triplets of all three dragons".to_string(),
+        Yaku::Suuankou => "four closed triplets".to_string(),
+        Yaku::SuuankouTankiWait => "four closed triplets, won on the pair".to_string(),
+        Yaku::Chinroutou => "only terminals".to_string(),
+        Yaku::Ryuuiisou => "only green tiles".to_string(),
+        Yaku::Tsuuiisou => "only honors".to_string(),
+        Yaku::Daichiishin => "seven pairs of honors".to_string(),
+        Yaku::Shousuushii => "three wind triplets and a wind pair".to_string(),
+        Yaku::Daisuushii => "triplets of all four winds".to_string(),
+        Yaku::KokushiMusou => "one of every terminal and honor".to_string(),
+        Yaku::KokushiMusou13SidedWait => {
+            "one of every terminal and honor, won on any of them".to_string()
+        }
+        Yaku::KazoeYakuman => "13 han or more".to_string(),
+    }
+}
+
+/// Get the fu of each triplet, kan and value pair, with the group it is for.
+fn meld_fu(hand: &Hand, tsumo: bool) -> Vec<(Fu, String)> {
+    let fu = hand.calculate_fu(tsumo);
+    let last = hand.groups().last();
+    let mut melds: Vec<(Fu, String)> = vec![];
+
+    for group in hand.triplets().iter().chain(hand.kans().iter()) {
+        let state = if group.group_type == GroupType::Triplet && !tsumo && Some(group) == last {
+            "completed by ron"
+        } else if group.isopen {
+            "open"
+        } else {
+            "closed"
+        };
+        let kind = if group.is_honor() || group.isterminal {
+            "terminal/honor"
+        } else {
+            "simple"
+        };
+        let reason = format!("{group}, {state} {kind}");
+        // take the fu in the order calculate_fu gave it
+        if let Some(fu) = fu
+            .iter()
+            .filter(|fu| is_meld_fu(fu))
+            .nth(melds.len())
+            .copied()
+        {
+            melds.push((fu, reason));
+        }
+    }
+
+    for pair in hand.pairs() {
+        if let Some(value) = value_pair(hand, &pair) {
+            melds.push((Fu::Toitsu, format!("{pair} pair of {value}")));
+        }
+    }
+
+    melds
+}
+
+fn is_meld_fu(fu: &Fu) -> bool {
+    matches!(
+        fu,
+        Fu::NonSimpleClosedTriplet
+            | Fu::SimpleClosedTriplet
+            | Fu::NonSimpleOpenTriplet
+            | Fu::SimpleOpenTriplet
+            | Fu::NonSimpleClosedKan
+            | Fu::SimpleClosedKan
+            | Fu::NonSimpleOpenKan
+            | Fu::SimpleOpenKan
+    )
+}
+
+fn fu_reason(hand: &Hand, fu: &Fu) -> String {
+    match fu {
+        Fu::BasePoints => "every winning hand".to_string(),
+        Fu::BasePointsChitoi => "seven pairs".to_string(),
+        Fu::ClosedRon => "ron with a closed hand".to_string(),
+        Fu::Tsumo => "won by tsumo".to_string(),
+        Fu::SingleWait => match hand.wait() {
+            Some(wait) => format!(
+                "{} wait on {}",
+                wait.to_string().to_lowercase(),
+                hand.win_tile()
+            ),
+            None => "single wait".to_string(),
+        },
+        _ => fu.name().to_lowercase(),
+    }
+}
+
+/// Get why a yaku was not awarded, or `None` if another yaku of the score already covers it.
+fn denied_reason(hand: &Hand, awarded: &[Yaku], yaku: &Yaku) -> Option<String> {
+    let kokushi = hand.groups().len() == 13;
+    let chiitoitsu = awarded.contains(&Yaku::Chiitoitsu);
+    let reason = match yaku {
+        Yaku::Tanyao => {
+            let (tile, group) = hand.groups().iter().find_map(|group| {
+                group
+                    .tiles()
+                    .into_iter()
+                    .find(|tile| tile.is_honor() || tile.isterminal)
+                    .map(|tile| (tile, group))
+            })?;
+            if group.group_type == GroupType::None {
+                format!("{tile}")
+            } else {
+                format!("{tile} in {group}")
+            }
+        }
+        Yaku::Pinfu => {
+            if kokushi {
+                "thirteen orphans".to_string()
+            } else if chiitoitsu {
+                "seven pairs".to_string()
+            } else if hand.is_open() {
+                "hand is open".to_string()
+            } else if let Some(group) = triplets_and_kans(hand).first() {
+                format!("{group} is not a sequence")
+            } else if let Some(value) = value_pair(hand, &hand.pairs()[0]) {
+                format!("pair of {value}")
+            } else {
+                let wait = hand.wait()?;
+                format!("{} wait", wait.to_string().to_lowercase())
+            }
+        }
+        Yaku::Yakuhai => "no triplet of dragons, seat wind or round wind".to_string(),
+        Yaku::Iipeikou => {
+            if awarded.contains(&Yaku::Ryanpeikou) {
+                return None;
+            } else if hand.is_open() {
+                "hand is open".to_string()
+            } else {
+                "no two identical sequences".to_string()
+            }
+        }
+        Yaku::Toitoi => {
+            if kokushi || chiitoitsu {
+                "not four triplets and a pair".to_string()
+            } else {
+                format!("{} is a sequence", hand.sequences().first()?)
+            }
+        }
+        Yaku::SanshokuDoujun => "no sequence in all three suits".to_string(),
+        Yaku::Ittsuu => "no 123, 456 and 789 in one suit".to_string(),
+        Yaku::Honitsu => {
+            if awarded.contains(&Yaku::Chinitsu) {
+                return None;
+            }
+            let mut suits: Vec<Suit> = hand
+                .groups()
+                .iter()
+                .filter(|g| !g.is_honor())
+                .map(|g| g.suit.clone())
+                .collect();
+            suits.sort();
+            suits.dedup();
+            let names: Vec<&str> = suits.iter().map(suit_name).collect();
+            let (last, rest) = names.split_last()?;
+            if rest.is_empty() {
+                return None;
+            }
+            format!("tiles in {} and {last}", rest.join(", "))
+        }
+        _ => return None,
+    };
+
+    Some(reason)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calc;
+
+    fn explain(tiles: &[&str], win: &str, tsumo: bool) -> Explanation {
+        let tiles = tiles.iter().map(|t| t.to_string()).collect();
+        let hand = Hand::new_from_strings(tiles, win.into(), "Ew".into(), "Ew".into()).unwrap();
+        let score = calc::get_hand_score(
+            hand.clone(),
+            None,
+            tsumo,
+            false,
+            false,
+            false,
+            false,
+            false,
+            false,
+            false,
+            0,
+        )
+        .unwrap();
+        Explanation::new(&hand, &score, tsumo)
+    }
+
+    fn reason<T: PartialEq>(reasons: &[Reason<T>], item: T) -> &str {
+        &reasons.iter().find(|r| r.item == item).unwrap().reason
+    }
+
+    #[test]
+    fn yaku_and_fu_reasons() {
+        let out = explain(&["rrrd", "EEEw", "234m", "678p", "99s"], "9s", false);
+
+        assert_eq!(
+            out.yaku
+                .iter()
+                .map(|r| r.reason.as_str())
+                .collect::<Vec<_>>(),
+            ["rrrd of dragons", "EEEw of round wind", "EEEw of seat wind"]
+        );
+        assert_eq!(
+            reason(&out.fu, Fu::NonSimpleClosedTriplet),
+            "rrrd, closed terminal/honor"
+        );
+        assert_eq!(reason(&out.fu, Fu::SingleWait), "tanki wait on 9s");
+        assert_eq!(reason(&out.denied, Yaku::Tanyao), "rd in rrrd");
+        assert_eq!(reason(&out.denied, Yaku::Pinfu), "rrrd is not a sequence");
+        assert_eq!(reason(&out.denied, Yaku::Toitoi), "234m is a sequence");
+        assert_eq!(
+            reason(&out.denied, Yaku::Honitsu),
+            "tiles in manzu, pinzu and souzu"
+        );
+    }
+
+    #[test]
+    fn superseded_yaku_are_not_denied() {
+        let out = explain(&["123p", "123p", "456p", "456p", "99p"], "9p", true);
+
+        assert_eq!(
+            reason(&out.yaku, Yaku::Ryanpeikou),
+            "123p twice and 456p twice"
+        );
+        assert_eq!(reason(&out.yaku, Yaku::Chinitsu), "only pinzu");
+        assert!(!out.denied.iter().any(|r| r.item == Yaku::Iipeikou));
+        assert!(!out.denied.iter().any(|r| r.item == Yaku::Honitsu));
+    }
+}
@@ -0,0 +1,635 @@
+//! C interface to the scorer, built into the cdylib with the `ffi` feature.
+//!
+//! The functions are declared in `ffi/mahc.h`. Every function returns [`MAHC_OK`] or an error
+//! code, and results are written through an out pointer. Results that own memory have a
+//! matching `_free` function.
+
+use std::ffi::{c_char, CStr, CString};
+use std::sync::OnceLock;
+
+use crate::calc::{self, YonmaRules};
+use crate::hand::error::HandErr;
+use crate::hand::Hand;
+use crate::payment::{Payment, Points};
+use crate::score::{FuValue, HanValue, HonbaCounter};
+use crate::tile_group::TileGroup;
+
+/// The call succeeded.
+pub const MAHC_OK: i32 = 0;
+/// A required pointer argument was null.
+pub const MAHC_ERROR_NULL_POINTER: i32 = -1;
+/// A string argument was not UTF-8.
+pub const MAHC_ERROR_INVALID_UTF8: i32 = -2;
+
+// Codes of the errors of the scorer, from 1. New errors are only ever added to the end, so the
+// codes do not change between versions.
+pub const MAHC_ERROR_INVALID_GROUP: i32 = 1;
+pub const MAHC_ERROR_INVALID_SUIT: i32 = 2;
+pub const MAHC_ERROR_INVALID_SHAPE: i32 = 3;
+pub const MAHC_ERROR_NO_YAKU: i32 = 4;
+pub const MAHC_ERROR_NO_HAND_TILES: i32 = 5;
+pub const MAHC_ERROR_NO_WIN_TILE: i32 = 6;
+pub const MAHC_ERROR_DUPLICATE_RIICHI: i32 = 7;
+pub const MAHC_ERROR_IPPATSU_WITHOUT_RIICHI: i32 = 8;
+pub const MAHC_ERROR_DOUBLE_RIICHI_HAITEI_IPPATSU: i32 = 9;
+pub const MAHC_ERROR_DOUBLE_RIICHI_HAITEI_CHANKAN: i32 = 10;
+pub const MAHC_ERROR_CHANKAN_TSUMO: i32 = 11;
+pub const MAHC_ERROR_RINSHAN_KAN_WITHOUT_KAN: i32 = 12;
+pub const MAHC_ERROR_RINSHAN_WITHOUT_TSUMO: i32 = 13;
+pub const MAHC_ERROR_RINSHAN_IPPATSU: i32 = 14;
+pub const MAHC_ERROR_NO_HAN: i32 = 15;
+pub const MAHC_ERROR_NO_FU: i32 = 16;
+pub const MAHC_ERROR_INVALID_PAYMENT: i32 = 17;
+pub const MAHC_ERROR_INVALID_RIICHI_TURN: i32 = 18;
+pub const MAHC_ERROR_NOT_IISHANTEN: i32 = 19;
+pub const MAHC_ERROR_RON_OWN_DISCARD: i32 = 20;
+pub const MAHC_ERROR_GAME_OVER: i32 = 21;
+pub const MAHC_ERROR_NOTHING_TO_UNDO: i32 = 22;
+pub const MAHC_ERROR_INVALID_GAME_STATE: i32 = 23;
+pub const MAHC_ERROR_GAME_STATE_FILE: i32 = 24;
+pub const MAHC_ERROR_INVALID_UMA: i32 = 25;
+pub const MAHC_ERROR_GAME_NOT_OVER: i32 = 26;
+pub const MAHC_ERROR_SANMA_MANZU: i32 = 27;
+pub const MAHC_ERROR_SANMA_CHI: i32 = 28;
+pub const MAHC_ERROR_SANMA_NORTH: i32 = 29;
+pub const MAHC_ERROR_TOO_MANY_NORTHS: i32 = 30;
+pub const MAHC_ERROR_INVALID_WINNERS: i32 = 31;
+pub const MAHC_ERROR_INVALID_HAND_EVENT: i32 = 32;
+pub const MAHC_ERROR_INVALID_KYUUSHU: i32 = 33;
+pub const MAHC_ERROR_NO_ABORTIVE_DRAW: i32 = 34;
+pub const MAHC_ERROR_INVALID_TABLE_FORMAT: i32 = 35;
+pub const MAHC_ERROR_CLAIM_WINNER_MISMATCH: i32 = 36;
+pub const MAHC_ERROR_INVALID_LANG: i32 = 37;
+pub const MAHC_ERROR_INVALID_RENDER_FORMAT: i32 = 38;
+pub const MAHC_ERROR_INVALID_REPL_COMMAND: i32 = 39;
+pub const MAHC_ERROR_INVALID_REQUEST: i32 = 40;
+pub const MAHC_ERROR_UNKNOWN_ENDPOINT: i32 = 41;
+pub const MAHC_ERROR_INVALID_MJAI_EVENT: i32 = 42;
+pub const MAHC_ERROR_INVALID_TRIALS: i32 = 43;
+pub const MAHC_ERROR_NUKIDORA_OUTSIDE_SANMA: i32 = 44;
+pub const MAHC_ERROR_INVALID_HAN: i32 = 45;
+pub const MAHC_ERROR_CLAIM_METHOD_MISMATCH: i32 = 46;
+pub const MAHC_ERROR_INVALID_SCORE: i32 = 47;
+
+/// Get the code of the error, as declared in the header.
+pub fn error_code(err: &HandErr) -> i32 {
+    match err {
+        HandErr::InvalidGroup => MAHC_ERROR_INVALID_GROUP,
+        HandErr::InvalidSuit => MAHC_ERROR_INVALID_SUIT,
+        HandErr::InvalidShape => MAHC_ERROR_INVALID_SHAPE,
+        HandErr::NoYaku => MAHC_ERROR_NO_YAKU,
+        HandErr::NoHandTiles => MAHC_ERROR_NO_HAND_TILES,
+        HandErr::NoWinTile => MAHC_ERROR_NO_WIN_TILE,
+        HandErr::DuplicateRiichi => MAHC_ERROR_DUPLICATE_RIICHI,
+        HandErr::IppatsuWithoutRiichi => MAHC_ERROR_IPPATSU_WITHOUT_RIICHI,
+        HandErr::DoubleRiichiHaiteiIppatsu => MAHC_ERROR_DOUBLE_RIICHI_HAITEI_IPPATSU,
+        HandErr::DoubleRiichiHaiteiChankan => MAHC_ERROR_DOUBLE_RIICHI_HAITEI_CHANKAN,
+        HandErr::ChankanTsumo => MAHC_ERROR_CHANKAN_TSUMO,
+        HandErr::RinshanKanWithoutKan => MAHC_ERROR_RINSHAN_KAN_WITHOUT_KAN,
+        HandErr::RinshanWithoutTsumo => MAHC_ERROR_RINSHAN_WITHOUT_TSUMO,
+        HandErr::RinshanIppatsu => MAHC_ERROR_RINSHAN_IPPATSU,
+        HandErr::NoHan => MAHC_ERROR_NO_HAN,
+        HandErr::NoFu => MAHC_ERROR_NO_FU,
+        HandErr::InvalidPayment => MAHC_ERROR_INVALID_PAYMENT,
+        HandErr::InvalidRiichiTurn => MAHC_ERROR_INVALID_RIICHI_TURN,
+        HandErr::NotIishanten => MAHC_ERROR_NOT_IISHANTEN,
+        HandErr::RonOwnDiscard => MAHC_ERROR_RON_OWN_DISCARD,
+        HandErr::GameOver => MAHC_ERROR_GAME_OVER,
+        HandErr::NothingToUndo => MAHC_ERROR_NOTHING_TO_UNDO,
+        HandErr::InvalidGameState => MAHC_ERROR_INVALID_GAME_STATE,
+        HandErr::GameStateFile => MAHC_ERROR_GAME_STATE_FILE,
+        HandErr::InvalidUma => MAHC_ERROR_INVALID_UMA,
+        HandErr::GameNotOver => MAHC_ERROR_GAME_NOT_OVER,
+        HandErr::SanmaManzu => MAHC_ERROR_SANMA_MANZU,
+        HandErr::SanmaChi => MAHC_ERROR_SANMA_CHI,
+        HandErr::SanmaNorth => MAHC_ERROR_SANMA_NORTH,
+        HandErr::TooManyNorths => MAHC_ERROR_TOO_MANY_NORTHS,
+        HandErr::InvalidWinners => MAHC_ERROR_INVALID_WINNERS,
+        HandErr::InvalidHandEvent => MAHC_ERROR_INVALID_HAND_EVENT,
+        HandErr::InvalidKyuushu => MAHC_ERROR_INVALID_KYUUSHU,
+        HandErr::NoAbortiveDraw => MAHC_ERROR_NO_ABORTIVE_DRAW,
+        HandErr::InvalidTableFormat => MAHC_ERROR_INVALID_TABLE_FORMAT,
+        HandErr::ClaimWinnerMismatch => MAHC_ERROR_CLAIM_WINNER_MISMATCH,
+        HandErr::InvalidLang => MAHC_ERROR_INVALID_LANG,
+        HandErr::InvalidRenderFormat => MAHC_ERROR_INVALID_RENDER_FORMAT,
+        HandErr::InvalidReplCommand => MAHC_ERROR_INVALID_REPL_COMMAND,
+        HandErr::InvalidRequest => MAHC_ERROR_INVALID_REQUEST,
+        HandErr::UnknownEndpoint => MAHC_ERROR_UNKNOWN_ENDPOINT,
+        HandErr::InvalidMjaiEvent => MAHC_ERROR_INVALID_MJAI_EVENT,
+        HandErr::InvalidTrials => MAHC_ERROR_INVALID_TRIALS,
+        HandErr::NukidoraOutsideSanma => MAHC_ERROR_NUKIDORA_OUTSIDE_SANMA,
+        HandErr::InvalidHan => MAHC_ERROR_INVALID_HAN,
+        HandErr::ClaimMethodMismatch => MAHC_ERROR_CLAIM_METHOD_MISMATCH,
+        HandErr::InvalidScore => MAHC_ERROR_INVALID_SCORE,
+    }
+}
+
+/// Get the error of a code from 1, or `None` for an unknown code.
+fn code_error(code: i32) -> Option<HandErr> {
+    let err = match code {
+        MAHC_ERROR_INVALID_GROUP => HandErr::InvalidGroup,
+        MAHC_ERROR_INVALID_SUIT => HandErr::InvalidSuit,
+        MAHC_ERROR_INVALID_SHAPE => HandErr::InvalidShape,
+        MAHC_ERROR_NO_YAKU => HandErr::NoYaku,
+        MAHC_ERROR_NO_HAND_TILES => HandErr::NoHandTiles,
+        MAHC_ERROR_NO_WIN_TILE => HandErr::NoWinTile,
+        MAHC_ERROR_DUPLICATE_RIICHI => HandErr::DuplicateRiichi,
+        MAHC_ERROR_IPPATSU_WITHOUT_RIICHI => HandErr::IppatsuWithoutRiichi,
+        MAHC_ERROR_DOUBLE_RIICHI_HAITEI_IPPATSU => HandErr::DoubleRiichiHaiteiIppatsu,
+        MAHC_ERROR_DOUBLE_RIICHI_HAITEI_CHANKAN => HandErr::DoubleRiichiHaiteiChankan,
+        MAHC_ERROR_CHANKAN_TSUMO => HandErr::ChankanTsumo,
+        MAHC_ERROR_RINSHAN_KAN_WITHOUT_KAN => HandErr::RinshanKanWithoutKan,
+        MAHC_ERROR_RINSHAN_WITHOUT_TSUMO => HandErr::RinshanWithoutTsumo,
+        MAHC_ERROR_RINSHAN_IPPATSU => HandErr::RinshanIppatsu,
+        MAHC_ERROR_NO_HAN => HandErr::NoHan,
+        MAHC_ERROR_NO_FU => HandErr::NoFu,
+        MAHC_ERROR_INVALID_PAYMENT => HandErr::InvalidPayment,
+        MAHC_ERROR_INVALID_RIICHI_TURN => HandErr::InvalidRiichiTurn,
+        MAHC_ERROR_NOT_IISHANTEN => HandErr::NotIishanten,
+        MAHC_ERROR_RON_OWN_DISCARD => HandErr::RonOwnDiscard,
+        MAHC_ERROR_GAME_OVER => HandErr::GameOver,
+        MAHC_ERROR_NOTHING_TO_UNDO => HandErr::NothingToUndo,
+        MAHC_ERROR_INVALID_GAME_STATE => HandErr::InvalidGameState,
+        MAHC_ERROR_GAME_STATE_FILE => HandErr::GameStateFile,
+        MAHC_ERROR_INVALID_UMA => HandErr::InvalidUma,
+        MAHC_ERROR_GAME_NOT_OVER => HandErr::GameNotOver,
+        MAHC_ERROR_SANMA_MANZU => HandErr::SanmaManzu,
+        MAHC_ERROR_SANMA_CHI => HandErr::SanmaChi,
+        MAHC_ERROR_SANMA_NORTH => HandErr::SanmaNorth,
+        MAHC_ERROR_TOO_MANY_NORTHS => HandErr::TooManyNorths,
+        MAHC_ERROR_INVALID_WINNERS => HandErr::InvalidWinners,
+        MAHC_ERROR_INVALID_HAND_EVENT => HandErr::InvalidHandEvent,
+        MAHC_ERROR_INVALID_KYUUSHU => HandErr::InvalidKyuushu,
+        MAHC_ERROR_NO_ABORTIVE_DRAW => HandErr::NoAbortiveDraw,
+        MAHC_ERROR_INVALID_TABLE_FORMAT => HandErr::InvalidTableFormat,
+        MAHC_ERROR_CLAIM_WINNER_MISMATCH => HandErr::ClaimWinnerMismatch,
+        MAHC_ERROR_INVALID_LANG => HandErr::InvalidLang,
+        MAHC_ERROR_INVALID_RENDER_FORMAT => HandErr::InvalidRenderFormat,
+        MAHC_ERROR_INVALID_REPL_COMMAND => HandErr::InvalidReplCommand,
+        MAHC_ERROR_INVALID_REQUEST => HandErr::InvalidRequest,
+        MAHC_ERROR_UNKNOWN_ENDPOINT => HandErr::UnknownEndpoint,
+        MAHC_ERROR_INVALID_MJAI_EVENT => HandErr::InvalidMjaiEvent,
+        MAHC_ERROR_INVALID_TRIALS => HandErr::InvalidTrials,
+        MAHC_ERROR_NUKIDORA_OUTSIDE_SANMA => HandErr::NukidoraOutsideSanma,
+        MAHC_ERROR_INVALID_HAN => HandErr::InvalidHan,
+        MAHC_ERROR_CLAIM_METHOD_MISMATCH => HandErr::ClaimMethodMismatch,
+        MAHC_ERROR_INVALID_SCORE => HandErr::InvalidScore,
+        _ => return None,
+    };
+    Some(err)
+}
+
+/// A parsed hand, only handled through a pointer.
+pub struct MahcHand(Hand);
+
+/// Round context of a hand to score.
+#[repr(C)]
+pub struct MahcContext {
+    /// Dora indicators separated by spaces (ex: `"3p Ew"`), or null for none.
+    pub dora: *const c_char,
+    pub tsumo: bool,
+    pub riichi: bool,
+    pub double_riichi: bool,
+    pub ippatsu: bool,
+    pub haitei: bool,
+    pub rinshan: bool,
+    pub chankan: bool,
+    pub tenhou: bool,
+    pub honba: HonbaCounter,
+    /// North tiles can be set aside as nukidora, as in some four-player rules.
+    pub nukidora: bool,
+    /// The draw after a nukidora counts for rinshan kaihou.
+    pub nuki_rinshan: bool,
+}
+
+/// What every seat pays, including honba.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct MahcPoints {
+    pub dealer_ron: Points,
+    /// Paid by each non-dealer.
+    pub dealer_tsumo: Points,
+    pub non_dealer_ron: Points,
+    /// Paid by the dealer.
+    pub non_dealer_tsumo_dealer: Points,
+    /// Paid by each other non-dealer.
+    pub non_dealer_tsumo_non_dealer: Points,
+}
+
+impl MahcPoints {
+    fn new(payment: &Payment, honba: HonbaCounter) -> Self {
+        Self {
+            dealer_ron: payment.dealer_ron(honba),
+            dealer_tsumo: payment.dealer_tsumo(honba),
+            non_dealer_ron: payment.non_dealer_ron(honba),
+            non_dealer_tsumo_dealer: payment.non_dealer_tsumo_to_dealer(honba),
+            non_dealer_tsumo_non_dealer: payment.non_dealer_tsumo_to_non_dealer(honba),
+        }
+    }
+}
+
+/// A yaku that was awarded.
+#[repr(C)]
+pub struct MahcYaku {
+    /// Id of the yaku (ex: `"riichi"`).
+    pub id: *mut c_char,
+    /// Han the yaku was worth, or 1 for each yakuman.
+    pub han: HanValue,
+    pub yakuman: bool,
+}
+
+/// A fu that was awarded.
+#[repr(C)]
+pub struct MahcFu {
+    /// Id of the fu (ex: `"closed_ron"`).
+    pub id: *mut c_char,
+    pub fu: FuValue,
+}
+
+/// The score of a hand, freed with [`mahc_score_free`].
+#[repr(C)]
+pub struct MahcScore {
+    pub han: HanValue,
+    pub fu: FuValue,
+    pub honba: HonbaCounter,
+    pub open: bool,
+    pub dora: u32,
+    pub points: MahcPoints,
+    pub yaku: *mut MahcYaku,
+    pub yaku_len: usize,
+    pub fu_breakdown: *mut MahcFu,
+    pub fu_breakdown_len: usize,
+}
+
+fn owned_str(s: &str) -> *mut c_char {
+    // ids never contain a nul byte
+    CString::new(s).unwrap_or_default().into_raw()
+}
+
+/// Read a string argument.
+///
+/// # Safety
+///
+/// `ptr` is null or a nul-terminated string.
+unsafe fn str_arg<'a>(ptr: *const c_char) -> Result<&'a str, i32> {
+    if ptr.is_null() {
+        return Err(MAHC_ERROR_NULL_POINTER);
+    }
+    CStr::from_ptr(ptr)
+        .to_str()
+        .map_err(|_| MAHC_ERROR_INVALID_UTF8)
+}
+
+fn words(s: &str) -> Vec<String> {
+    s.split_whitespace().map(String::from).collect()
+}
+
+/// Parse a hand, written to `out` and freed with [`mahc_hand_free`].
+///
+/// `tiles` are the groups separated by spaces, with the winning group last (ex:
+/// `"123p 456p 99s 234s 678m"`).
+///
+/// # Safety
+///
+/// The strings are nul-terminated, and `out` points to writable memory.
+#[no_mangle]
+pub unsafe extern "C" fn mahc_hand_parse(
+    tiles: *const c_char,
+    win: *const c_char,
+    seat: *const c_char,
+    prev: *const c_char,
+    out: *mut *mut MahcHand,
+) -> i32 {
+    if out.is_null() {
+        return MAHC_ERROR_NULL_POINTER;
+    }
+    let args = (|| {
+        Ok((
+            str_arg(tiles)?,
+            str_arg(win)?,
+            str_arg(seat)?,
+            str_arg(prev)?,
+        ))
+    })();
+    let (tiles, win, seat, prev) = match args {
+        Ok(args) => args,
+        Err(code) => return code,
+    };
+
+    match Hand::new_from_strings(words(tiles), win.into(), prev.into(), seat.into()) {
+        Ok(hand) => {
+            *out = Box::into_raw(Box::new(MahcHand(hand)));
+            MAHC_OK
+        }
+        Err(e) => error_code(&e),
+    }
+}
+
+/// Free a hand from [`mahc_hand_parse`].
+///
+/// # Safety
+///
+/// `hand` is null or a hand from [`mahc_hand_parse`] that has not been freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn mahc_hand_free(hand: *mut MahcHand) {
+    if !hand.is_null() {
+        drop(Box::from_raw(hand));
+    }
+}
+
+/// Score a hand, written to `out` and freed with [`mahc_score_free`].
+///
+/// # Safety
+///
+/// `hand` is a hand from [`mahc_hand_parse`], `context` points to a context with a null or
+/// nul-terminated `dora`, and `out` points to writable memory.
+#[no_mangle]
+pub unsafe extern "C" fn mahc_hand_score(
+    hand: *const MahcHand,
+    context: *const MahcContext,
+    out: *mut *mut MahcScore,
+) -> i32 {
+    if hand.is_null() || context.is_null() || out.is_null() {
+        return MAHC_ERROR_NULL_POINTER;
+    }
+    let (hand, context) = (&(*hand).0, &*context);
+
+    let dora = if context.dora.is_null() {
+        None
+    } else {
+        let dora = match str_arg(context.dora) {
+            Ok(dora) => dora,
+            Err(code) => return code,
+        };
+        match words(dora)
+            .into_iter()
+            .map(TileGroup::try_from)
+            .collect::<Result<Vec<_>, _>>()
+        {
+            Ok(dora) => Some(dora),
+            Err(e) => return error_code(&e),
+        }
+    };
+
+    let rules = YonmaRules {
+        nukidora: context.nukidora,
+        nuki_rinshan: context.nuki_rinshan,
+    };
+    let score = match rules.get_hand_score(
+        hand.clone(),
+        dora,
+        context.tsumo,
+        context.riichi,
+        context.double_riichi,
+        context.ippatsu,
+        context.haitei,
+        context.rinshan,
+        context.chankan,
+        context.tenhou,
+        context.honba,
+    ) {
+        Ok(score) => score,
+        Err(e) => return error_code(&e),
+    };
+
+    let yaku: Box<[MahcYaku]> = score
+        .yaku()
+        .iter()
+        .map(|yaku| MahcYaku {
+            id: owned_str(yaku.id()),
+            han: yaku.get_han(score.is_open()),
+            yakuman: yaku.is_yakuman(),
+        })
+        .collect();
+    let fu_breakdown: Box<[MahcFu]> = score
+        .fu()
+        .iter()
+        .map(|fu| MahcFu {
+            id: owned_str(fu.id()),
+            fu: fu.value(),
+        })
+        .collect();
+
+    let yaku_len = yaku.len();
+    let fu_breakdown_len = fu_breakdown.len();
+    *out = Box::into_raw(Box::new(MahcScore {
+        han: score.han(),
+        fu: score.fu_score(),
+        honba: score.honba(),
+        open: score.is_open(),
+        dora: score.dora_count(),
+        points: MahcPoints::new(score.payment(), score.honba()),
+        yaku: Box::into_raw(yaku) as *mut MahcYaku,
+        yaku_len,
+        fu_breakdown: Box::into_raw(fu_breakdown) as *mut MahcFu,
+        fu_breakdown_len,
+    }));
+    MAHC_OK
+}
+
+/// Free a score from [`mahc_hand_score`], along with its yaku and fu.
+///
+/// # Safety
+///
+/// `score` is null or a score from [`mahc_hand_score`] that has not been freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn mahc_score_free(score: *mut MahcScore) {
+    if score.is_null() {
+        return;
+    }
+    let score = Box::from_raw(score);
+    let yaku = Box::from_raw(std::ptr::slice_from_raw_parts_mut(
+        score.yaku,
+        score.yaku_len,
+    ));
+    for yaku in yaku.iter() {
+        drop(CString::from_raw(yaku.id));
+    }
+    let fu = Box::from_raw(std::ptr::slice_from_raw_parts_mut(
+        score.fu_breakdown,
+        score.fu_breakdown_len,
+    ));
+    for fu in fu.iter() {
+        drop(CString::from_raw(fu.id));
+    }
+}
+
+/// Calculate the points of a han and fu, written to `out`.
+///
+/// # Safety
+///
+/// `out` points to writable memory.
+#[no_mangle]
+pub unsafe extern "C" fn mahc_calculate(
+    han: HanValue,
+    fu: FuValue,
+    honba: HonbaCounter,
+    out: *mut MahcPoints,
+) -> i32 {
+    if out.is_null() {
+        return MAHC_ERROR_NULL_POINTER;
+    }
+    match calc::calculate(han, fu) {
+        Ok(payment) => {
+            *out = MahcPoints::new(&payment, honba);
+            MAHC_OK
+        }
+        Err(e) => error_code(&e),
+    }
+}
+
+/// Get the name of an error code (ex: `"no_yaku"`), or null for an unknown code.
+///
+/// The string is static and must not be freed.
+#[no_mangle]
+pub extern "C" fn mahc_error_name(code: i32) -> *const c_char {
+    static NAMES: OnceLock<Vec<CString>> = OnceLock::new();
+    let names = NAMES.get_or_init(|| {
+        (1..)
+            .map_while(code_error)
+            .map(|e| CString::new(e.code()).unwrap_or_default())
+            .collect()
+    });
+
+    let name = match code {
+        MAHC_OK => c"ok",
+        MAHC_ERROR_NULL_POINTER => c"null_pointer",
+        MAHC_ERROR_INVALID_UTF8 => c"invalid_utf8",
+        _ => match usize::try_from(code - 1).ok().and_then(|i| names.get(i)) {
+            Some(name) => name.as_c_str(),
+            None => return std::ptr::null(),
+        },
+    };
+    name.as_ptr()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn header_declares_every_error() {
+        let header = include_str!("../ffi/mahc.h");
+        let errors: Vec<HandErr> = (1..).map_while(code_error).collect();
+        for (code, err) in (1..).zip(&errors) {
+            assert_eq!(error_code(err), code);
+            let constant = format!("#define MAHC_ERROR_{} {code}\n", err.code().to_uppercase());
+            assert!(header.contains(&constant), "{constant} is not in mahc.h");
+        }
+        let declared = header
+            .lines()
+            .filter(|line| line.starts_with("#define MAHC_ERROR_"))
+            .count();
+        assert_eq!(
+            declared,
+            errors.len() + 2,
+            "mahc.h declares stale error codes"
+        );
+        for function in [
+            "mahc_hand_parse(",
+            "mahc_hand_free(",
+            "mahc_hand_score(",
+            "mahc_score_free(",
+            "mahc_calculate(",
+            "mahc_error_name(",
+        ] {
+            assert!(header.contains(function), "{function} is not in mahc.h");
+        }
+    }
+
+    #[test]
+    fn score_through_pointers() {
+        unsafe {
+            let mut hand = std::ptr::null_mut();
+            let code = mahc_hand_parse(
+                c"123p 456p 99s 234s 678m".as_ptr(),
+                c"6m".as_ptr(),
+                c"Sw".as_ptr(),
+                c"Ew".as_ptr(),
+                &mut hand,
+            );
+            assert_eq!(code, MAHC_OK);
+
+            let context = MahcContext {
+                dora: c"4p".as_ptr(),
+                tsumo: false,
+                riichi: true,
+                double_riichi: false,
+                ippatsu: false,
+                haitei: false,
+                rinshan: false,
+                chankan: false,
+                tenhou: false,
+                honba: 0,
+                nukidora: false,
+                nuki_rinshan: false,
+            };
+            let mut score = std::ptr::null_mut();
+            assert_eq!(mahc_hand_score(hand, &context, &mut score), MAHC_OK);
+            let s = &*score;
+            assert_eq!((s.han, s.fu, s.dora), (3, 30, 1));
+            assert_eq!(s.points.non_dealer_ron, 3900);
+            let yaku = std::slice::from_raw_parts(s.yaku, s.yaku_len);
+            assert_eq!(CStr::from_ptr(yaku[1].id).to_str(), Ok("pinfu"));
+
+            mahc_score_free(score);
+            mahc_hand_free(hand);
+        }
+    }
+
+    #[test]
+    fn errors_as_codes() {
+        unsafe {
+            let mut hand = std::ptr::null_mut();
+            let code = mahc_hand_parse(
+                c"123p 456p 99s 234s 678mo".as_ptr(),
+                c"6m".as_ptr(),
+                c"Ew".as_ptr(),
+                c"Ew".as_ptr(),
+                &mut hand,
+            );
+            assert_eq!(code, MAHC_OK);
+
+            let context = MahcContext {
+                dora: std::ptr::null(),
+                tsumo: false,
+                riichi: false,
+                double_riichi: false,
+                ippatsu: false,
+                haitei: false,
+                rinshan: false,
+                chankan: false,
+                tenhou: false,
+                honba: 0,
+                nukidora: false,
+                nuki_rinshan: false,
+            };
+            let mut score = std::ptr::null_mut();
+            let code = mahc_hand_score(hand, &context, &mut score);
+            assert_eq!(code, error_code(&HandErr::NoYaku));
+            assert_eq!(
+                CStr::from_ptr(mahc_error_name(code)).to_str(),
+                Ok("no_yaku")
+            );
+            assert!(score.is_null());
+            mahc_hand_free(hand);
+
+            let mut points = MahcPoints::default();
+            assert_eq!(
+                mahc_calculate(0, 30, 0, &mut points),
+                error_code(&HandErr::NoHan)
+            );
+            assert_eq!(
+                mahc_hand_parse(
+                    std::ptr::null(),
+                    c"6m".as_ptr(),
+                    c"Ew".as_ptr(),
+                    c"Ew".as_ptr(),
+                    &mut hand
+                ),
+                MAHC_ERROR_NULL_POINTER
+            );
+            assert!(mahc_error_name(1000).is_null());
+        }
+    }
+}
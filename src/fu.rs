@@ -1,6 +1,8 @@
 use crate::score::FuValue;
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "python", pyo3::pyclass(eq, eq_int, module = "mahc"))]
 pub enum Fu {
     BasePoints,
     BasePointsChitoi,
@@ -40,6 +42,46 @@ impl std::fmt::Display for Fu {
 }
 
 impl Fu {
+    /// Get the id of the fu, which does not change between versions.
+    pub fn id(&self) -> &'static str {
+        match self {
+            Self::BasePoints => "base_points",
+            Self::BasePointsChitoi => "base_points_chiitoitsu",
+            Self::ClosedRon => "closed_ron",
+            Self::Tsumo => "tsumo",
+            Self::NonSimpleClosedTriplet => "non_simple_closed_triplet",
+            Self::SimpleClosedTriplet => "simple_closed_triplet",
+            Self::NonSimpleOpenTriplet => "non_simple_open_triplet",
+            Self::SimpleOpenTriplet => "simple_open_triplet",
+            Self::NonSimpleClosedKan => "non_simple_closed_kan",
+            Self::SimpleClosedKan => "simple_closed_kan",
+            Self::NonSimpleOpenKan => "non_simple_open_kan",
+            Self::SimpleOpenKan => "simple_open_kan",
+            Self::Toitsu => "toitsu",
+            Self::SingleWait => "single_wait",
+        }
+    }
+
+    /// Get the name of the fu.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::BasePoints => "Base Points",
+            Self::BasePointsChitoi => "Base Points (Chiitoitsu)",
+            Self::ClosedRon => "Closed Ron",
+            Self::Tsumo => "Tsumo",
+            Self::NonSimpleClosedTriplet => "Closed Terminal/Honor Triplet",
+            Self::SimpleClosedTriplet => "Closed Simple Triplet",
+            Self::NonSimpleOpenTriplet => "Open Terminal/Honor Triplet",
+            Self::SimpleOpenTriplet => "Open Simple Triplet",
+            Self::NonSimpleClosedKan => "Closed Terminal/Honor Kan",
+            Self::SimpleClosedKan => "Closed Simple Kan",
+            Self::NonSimpleOpenKan => "Open Terminal/Honor Kan",
+            Self::SimpleOpenKan => "Open Simple Kan",
+            Self::Toitsu => "Value Pair",
+            Self::SingleWait => "Single Wait",
+        }
+    }
+
     /// Get the minipoint value.
     pub fn value(&self) -> FuValue {
         match self {
@@ -63,7 +105,7 @@ impl Fu {
 
 /// Sum up all of the fu, rounding to the nearest 10.
 pub fn calculate_total_fu_value(fu: &[Fu]) -> FuValue {
-    ((fu.iter().map(|f| f.value()).sum::<FuValue>() + 9) / 10) * 10
+    fu.iter().map(|f| f.value()).sum::<FuValue>().div_ceil(10) * 10
 }
 
 #[cfg(test)]
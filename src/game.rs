@@ -0,0 +1,686 @@
+use serde_json::{json, Value};
+
+use crate::abortive::{self, AbortiveDraw, HandEvent};
+use crate::hand::error::HandErr;
+use crate::payment::{Payment, PlayerPoints, RIICHI_STICK};
+use crate::score::{HonbaCounter, RiichiCounter, Score};
+use crate::seat::Seat;
+use crate::settlement::{MultiRonRules, Settlement, WinMethod};
+
+/// Points the noten players pay to the tenpai players in an exhaustive draw.
+const NOTEN_PAYMENT: PlayerPoints = 3_000;
+/// Points a chombo pays to each non-dealer, doubled for the dealer (a reverse mangan).
+const CHOMBO_PAYMENT: PlayerPoints = 2_000;
+
+/// Number of wind rounds in a game.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GameLength {
+    /// East round only.
+    Tonpuusen,
+    /// East and South rounds.
+    Hanchan,
+}
+
+impl GameLength {
+    /// Get the number of wind rounds before sudden death.
+    pub fn winds(&self) -> usize {
+        match self {
+            Self::Tonpuusen => 1,
+            Self::Hanchan => 2,
+        }
+    }
+}
+
+/// Rules for a game.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rules {
+    /// Number of wind rounds in the game.
+    pub length: GameLength,
+    /// Points each player starts with.
+    pub starting_points: PlayerPoints,
+    /// Points someone needs for the game to end after the last round. Until then the game goes
+    /// on into the next wind (sudden death).
+    pub target: PlayerPoints,
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Self {
+            length: GameLength::Hanchan,
+            starting_points: 25_000,
+            target: 30_000,
+        }
+    }
+}
+
+/// State of the table between hands.
+///
+/// Players are numbered from `0` in seating order, starting from the first dealer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Table {
+    /// Prevalent wind.
+    pub round_wind: Seat,
+    /// Player that is the dealer.
+    pub dealer: usize,
+    /// Number of repeat counters.
+    pub honba: HonbaCounter,
+    /// Number of riichi sticks on the table.
+    pub riichi_sticks: RiichiCounter,
+    /// Points of each player.
+    pub scores: [PlayerPoints; 4],
+    /// Whether the game has ended.
+    pub finished: bool,
+}
+
+impl Table {
+    /// Get the seat of the player.
+    pub fn seat(&self, player: usize) -> Seat {
+        Seat::ALL[(player + 4 - self.dealer) % 4]
+    }
+
+    /// Get the player in the seat.
+    pub fn player(&self, seat: Seat) -> usize {
+        (seat.index() + self.dealer) % 4
+    }
+
+    /// Get the players from first to last place.
+    ///
+    /// Ties go to the player seated closest to the first dealer.
+    pub fn standings(&self) -> [usize; 4] {
+        let mut players = [0, 1, 2, 3];
+        players.sort_by_key(|&player| (std::cmp::Reverse(self.scores[player]), player));
+        players
+    }
+
+    fn to_json(&self) -> Value {
+        json!({
+            "roundWind" : self.round_wind.index(),
+            "dealer" : self.dealer,
+            "honba" : self.honba,
+            "riichiSticks" : self.riichi_sticks,
+            "scores" : self.scores,
+            "finished" : self.finished
+        })
+    }
+
+    fn from_json(value: &Value) -> Option<Self> {
+        let scores: Vec<PlayerPoints> = value["scores"]
+            .as_array()?
+            .iter()
+            .map(|score| score.as_i64())
+            .collect::<Option<_>>()?;
+        let dealer = value["dealer"].as_u64()? as usize;
+        if dealer > 3 {
+            return None;
+        }
+
+        Some(Self {
+            round_wind: *Seat::ALL.get(value["roundWind"].as_u64()? as usize)?,
+            dealer,
+            honba: value["honba"].as_u64()?,
+            riichi_sticks: value["riichiSticks"].as_u64()?,
+            scores: scores.try_into().ok()?,
+            finished: value["finished"].as_bool()?,
+        })
+    }
+}
+
+/// A whole game, scored hand by hand.
+///
+/// # Examples
+///
+/// ```rust
+/// use mahc::game::{Game, Rules};
+/// use mahc::payment::Payment;
+/// use mahc::seat::Seat;
+/// use mahc::settlement::WinMethod;
+///
+/// let mut game = Game::new(Rules::default());
+///
+/// // the dealer wins 3,900 off South and keeps the deal
+/// game.agari(Seat::East, WinMethod::Ron(Seat::South), &Payment::new(640), &[])
+///     .unwrap();
+/// assert_eq!(game.table().scores, [28_900, 21_100, 25_000, 25_000]);
+/// assert_eq!(game.table().honba, 1);
+///
+/// // West tsumo with riichi, so the deal passes to South
+/// game.agari(Seat::West, WinMethod::Tsumo, &Payment::new(320), &[Seat::West])
+///     .unwrap();
+/// assert_eq!(game.table().dealer, 1);
+/// assert_eq!(game.table().honba, 0);
+///
+/// game.undo().unwrap();
+/// assert_eq!(game.table().dealer, 0);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Game {
+    rules: Rules,
+    table: Table,
+    /// Table before each hand, for undo.
+    history: Vec<Table>,
+}
+
+impl TryFrom<String> for Game {
+    type Error = HandErr;
+
+    /// Parse a game saved with [`Game::to_json`].
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let value: Value = serde_json::from_str(&value).map_err(|_| HandErr::InvalidGameState)?;
+        let parse = || -> Option<Self> {
+            let length = match value["length"].as_str()? {
+                "tonpuusen" => GameLength::Tonpuusen,
+                "hanchan" => GameLength::Hanchan,
+                _ => return None,
+            };
+            Some(Self {
+                rules: Rules {
+                    length,
+                    starting_points: value["startingPoints"].as_i64()?,
+                    target: value["target"].as_i64()?,
+                },
+                table: Table::from_json(&value["table"])?,
+                history: value["history"]
+                    .as_array()?
+                    .iter()
+                    .map(Table::from_json)
+                    .collect::<Option<_>>()?,
+            })
+        };
+
+        parse().ok_or(HandErr::InvalidGameState)
+    }
+}
+
+impl Game {
+    /// Create a new [`Game`] with the first dealer in East 1.
+    pub fn new(rules: Rules) -> Self {
+        let table = Table {
+            round_wind: Seat::East,
+            dealer: 0,
+            honba: 0,
+            riichi_sticks: 0,
+            scores: [rules.starting_points; 4],
+            finished: false,
+        };
+
+        Self {
+            rules,
+            table,
+            history: vec![],
+        }
+    }
+
+    /// Get the rules of the game.
+    pub fn rules(&self) -> &Rules {
+        &self.rules
+    }
+
+    /// Get the current state of the table.
+    pub fn table(&self) -> &Table {
+        &self.table
+    }
+
+    /// Save the game, including its history, as JSON.
+    pub fn to_json(&self) -> String {
+        let length = match self.rules.length {
+            GameLength::Tonpuusen => "tonpuusen",
+            GameLength::Hanchan => "hanchan",
+        };
+        json!({
+            "length" : length,
+            "startingPoints" : self.rules.starting_points,
+            "target" : self.rules.target,
+            "table" : self.table.to_json(),
+            "history" : self.history.iter().map(Table::to_json).collect::<Vec<Value>>()
+        })
+        .to_string()
+    }
+
+    /// Score a win.
+    ///
+    /// Seats that declared riichi this hand put their stick on the table first. The winner takes
+    /// the payment, honba, and every riichi stick. The dealer keeps the deal if they won.
+    pub fn agari(
+        &mut self,
+        winner: Seat,
+        method: WinMethod,
+        payment: &Payment,
+        riichi: &[Seat],
+    ) -> Result<(), HandErr> {
+        self.check_playing()?;
+        let sticks = self.table.riichi_sticks + riichi.len() as RiichiCounter;
+        let settlement = Settlement::new(winner, method, self.table.honba, sticks)?;
+
+        self.history.push(self.table.clone());
+        self.declare_riichi(riichi);
+        for (seat, delta) in Seat::ALL.into_iter().zip(settlement.deltas(payment)) {
+            let player = self.table.player(seat);
+            self.table.scores[player] += delta;
+        }
+        self.table.riichi_sticks = 0;
+
+        let repeat = winner.is_dealer();
+        if repeat {
+            self.table.honba += 1;
+        } else {
+            self.table.honba = 0;
+        }
+        self.next_hand(repeat);
+
+        Ok(())
+    }
+
+    /// Score a win from the score of the hand.
+    ///
+    /// Works like [`Game::agari`], paying out yakuman and any other limit the hand was scored
+    /// with. Honba are always the ones on the table.
+    pub fn agari_score(
+        &mut self,
+        winner: Seat,
+        method: WinMethod,
+        score: &Score,
+        riichi: &[Seat],
+    ) -> Result<(), HandErr> {
+        self.agari(winner, method, score.payment(), riichi)
+    }
+
+    /// Score an exhaustive draw.
+    ///
+    /// Noten players pay the tenpai players 3,000 points in total. Riichi sticks stay on the
+    /// table, honba goes up, and the dealer keeps the deal if they are tenpai.
+    pub fn draw(&mut self, tenpai: &[Seat], riichi: &[Seat]) -> Result<(), HandErr> {
+        self.check_playing()?;
+
+        self.history.push(self.table.clone());
+        self.declare_riichi(riichi);
+
+        let tenpai_count = Seat::ALL
+            .iter()
+            .filter(|seat| tenpai.contains(seat))
+            .count();
+        if tenpai_count != 0 && tenpai_count != 4 {
+            let count = tenpai_count as PlayerPoints;
+            for seat in Seat::ALL {
+                let player = self.table.player(seat);
+                self.table.scores[player] += if tenpai.contains(&seat) {
+                    NOTEN_PAYMENT / count
+                } else {
+                    -NOTEN_PAYMENT / (4 - count)
+                };
+            }
+        }
+
+        self.table.honba += 1;
+        self.next_hand(tenpai.contains(&Seat::East));
+
+        Ok(())
+    }
+
+    /// Score an abortive draw found in the events of the hand.
+    ///
+    /// Riichi sticks from the events stay on the table, honba goes up, and the dealer keeps the
+    /// deal.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use mahc::abortive::{AbortiveDraw, HandEvent};
+    /// use mahc::game::{Game, Rules};
+    /// use mahc::settlement::MultiRonRules;
+    ///
+    /// let mut game = Game::new(Rules::default());
+    /// let events: Vec<HandEvent> = ["Ew:riichi", "Sw:riichi", "Ww:riichi", "Nw:riichi"]
+    ///     .iter()
+    ///     .map(|event| event.to_string().try_into().unwrap())
+    ///     .collect();
+    ///
+    /// let draw = game.abort(&events, &MultiRonRules::default()).unwrap();
+    /// assert_eq!(draw, AbortiveDraw::SuuchaRiichi);
+    /// assert_eq!(game.table().riichi_sticks, 4);
+    /// assert_eq!(game.table().honba, 1);
+    /// assert_eq!(game.table().dealer, 0);
+    /// ```
+    pub fn abort(
+        &mut self,
+        events: &[HandEvent],
+        rules: &MultiRonRules,
+    ) -> Result<AbortiveDraw, HandErr> {
+        self.check_playing()?;
+        let draw = abortive::detect(events, rules)?;
+
+        let mut riichi: Vec<Seat> = vec![];
+        for event in events {
+            if let HandEvent::Riichi(seat) = event {
+                if !riichi.contains(seat) {
+                    riichi.push(*seat);
+                }
+            }
+        }
+
+        self.history.push(self.table.clone());
+        self.declare_riichi(&riichi);
+        self.table.honba += 1;
+        // the dealer keeps the deal, but only a win lets them stop
+        self.check_end(false);
+
+        Ok(draw)
+    }
+
+    /// Score a chombo (penalty), paid as a reverse mangan.
+    ///
+    /// The hand is replayed, so the dealer, honba, and riichi sticks stay the same.
+    pub fn chombo(&mut self, offender: Seat) -> Result<(), HandErr> {
+        self.check_playing()?;
+
+        self.history.push(self.table.clone());
+        for seat in Seat::ALL.into_iter().filter(|&seat| seat != offender) {
+            let paid = if seat.is_dealer() || offender.is_dealer() {
+                2 * CHOMBO_PAYMENT
+            } else {
+                CHOMBO_PAYMENT
+            };
+            let (from, to) = (self.table.player(offender), self.table.player(seat));
+            self.table.scores[from] -= paid;
+            self.table.scores[to] += paid;
+        }
+        self.check_end(false);
+
+        Ok(())
+    }
+
+    /// Go back to the table before the last hand.
+    pub fn undo(&mut self) -> Result<(), HandErr> {
+        self.table = self.history.pop().ok_or(HandErr::NothingToUndo)?;
+        Ok(())
+    }
+
+    fn check_playing(&self) -> Result<(), HandErr> {
+        if self.table.finished {
+            return Err(HandErr::GameOver);
+        }
+        Ok(())
+    }
+
+    fn declare_riichi(&mut self, riichi: &[Seat]) {
+        for &seat in riichi {
+            let player = self.table.player(seat);
+            self.table.scores[player] -= RIICHI_STICK as PlayerPoints;
+            self.table.riichi_sticks += 1;
+        }
+    }
+
+    /// Move on to the next hand, passing the deal unless the dealer repeats.
+    fn next_hand(&mut self, repeat: bool) {
+        if !repeat {
+            self.table.dealer = (self.table.dealer + 1) % 4;
+            if self.table.dealer == 0 {
+                self.table.round_wind = self.table.round_wind.next();
+            }
+        }
+
+        self.check_end(repeat);
+    }
+
+    /// End the game if someone is below zero, the last round is over, or the dealer is first in the
+    /// last hand and chooses to stop.
+    ///
+    /// The dealer can only stop after keeping the deal with a win or tenpai, not after a hand that
+    /// is replayed.
+    fn check_end(&mut self, may_stop: bool) {
+        let table = &self.table;
+        let winds = self.rules.length.winds();
+        let wind = table.round_wind.index();
+        let leader = table.standings()[0];
+        let target_reached = table.scores[leader] >= self.rules.target;
+
+        let busted = table.scores.iter().any(|&score| score < 0);
+        // one extra wind of sudden death, until someone reaches the target
+        let sudden_death_over = wind > winds || (wind == winds && target_reached);
+        // the dealer repeating in the last hand can stop while in first place (agari-yame)
+        let dealer_stops =
+            may_stop && wind + 1 == winds && table.dealer == 3 && leader == 3 && target_reached;
+
+        if busted || sudden_death_over || dealer_stops {
+            // leftover riichi sticks go to first place
+            self.table.scores[leader] += (self.table.riichi_sticks * RIICHI_STICK) as PlayerPoints;
+            self.table.riichi_sticks = 0;
+            self.table.finished = true;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::limit_hand::LimitHands;
+    use crate::yaku::Yaku;
+
+    fn tonpuusen() -> Game {
+        Game::new(Rules {
+            length: GameLength::Tonpuusen,
+            ..Rules::default()
+        })
+    }
+
+    #[test]
+    fn dealer_rotation() {
+        let mut game = tonpuusen();
+
+        // South wins, so player 1 deals next
+        game.agari(
+            Seat::South,
+            WinMethod::Ron(Seat::West),
+            &Payment::new(320),
+            &[],
+        )
+        .unwrap();
+        let table = game.table();
+        assert_eq!(table.dealer, 1);
+        assert_eq!(table.seat(0), Seat::North);
+        assert_eq!(table.player(Seat::East), 1);
+        assert_eq!(table.scores, [25_000, 26_300, 23_700, 25_000]);
+    }
+
+    #[test]
+    fn yakuman_from_score() {
+        let mut game = tonpuusen();
+        game.draw(&[], &[]).unwrap();
+
+        // the honba come from the table, not from the score
+        let score = Score::new(
+            LimitHands::KazoeYakuman.get_score(),
+            vec![Yaku::Daisangen],
+            vec![],
+            13,
+            30,
+            0,
+            true,
+            0,
+        );
+        game.agari_score(Seat::West, WinMethod::Ron(Seat::North), &score, &[])
+            .unwrap();
+        let table = game.table();
+        assert_eq!(table.scores, [-7_300, 25_000, 25_000, 57_300]);
+    }
+
+    #[test]
+    fn draw_noten_payments() {
+        let mut game = tonpuusen();
+        game.draw(&[Seat::South], &[Seat::South]).unwrap();
+
+        // dealer noten, so the deal passes with a honba and the stick left on the table
+        let table = game.table();
+        assert_eq!(table.scores, [24_000, 27_000, 24_000, 24_000]);
+        assert_eq!(table.dealer, 1);
+        assert_eq!(table.honba, 1);
+        assert_eq!(table.riichi_sticks, 1);
+
+        // the new dealer is tenpai and keeps the deal
+        game.draw(&[Seat::East, Seat::South], &[]).unwrap();
+        let table = game.table();
+        assert_eq!(table.scores, [22_500, 28_500, 25_500, 22_500]);
+        assert_eq!(table.dealer, 1);
+        assert_eq!(table.honba, 2);
+    }
+
+    #[test]
+    fn riichi_sticks_go_to_winner() {
+        let mut game = tonpuusen();
+        game.draw(&[], &[Seat::West]).unwrap();
+        game.agari(
+            Seat::South,
+            WinMethod::Ron(Seat::North),
+            &Payment::new(320),
+            &[Seat::South],
+        )
+        .unwrap();
+
+        // South is player 2 in East 2, North is player 0
+        let table = game.table();
+        assert_eq!(table.scores, [23_400, 25_000, 26_600, 25_000]);
+        assert_eq!(table.riichi_sticks, 0);
+        assert_eq!(table.honba, 0);
+    }
+
+    #[test]
+    fn chombo_replays_hand() {
+        let mut game = tonpuusen();
+        game.chombo(Seat::South).unwrap();
+
+        let table = game.table();
+        assert_eq!(table.scores, [29_000, 17_000, 27_000, 27_000]);
+        assert_eq!(table.dealer, 0);
+        assert_eq!(table.honba, 0);
+    }
+
+    #[test]
+    fn game_end_and_sudden_death() {
+        let mut game = tonpuusen();
+        for _ in 0..4 {
+            game.draw(&[], &[]).unwrap();
+        }
+
+        // nobody reached 30,000, so the game goes into the South round
+        let table = game.table();
+        assert!(!table.finished);
+        assert_eq!(table.round_wind, Seat::South);
+
+        // anyone reaching 30,000 in sudden death ends the game
+        game.agari(Seat::West, WinMethod::Tsumo, &Payment::new(2_000), &[])
+            .unwrap();
+        assert!(game.table().finished);
+        assert_eq!(game.draw(&[], &[]).unwrap_err(), HandErr::GameOver);
+    }
+
+    #[test]
+    fn game_end_on_bust() {
+        let mut game = tonpuusen();
+        game.draw(&[], &[Seat::North]).unwrap();
+        // yakuman off the dealer
+        game.agari(
+            Seat::North,
+            WinMethod::Ron(Seat::East),
+            &Payment::new(8_000),
+            &[],
+        )
+        .unwrap();
+
+        let table = game.table();
+        assert!(table.finished);
+        assert_eq!(table.standings(), [0, 2, 3, 1]);
+        assert_eq!(table.scores, [58_300, -7_300, 25_000, 24_000]);
+    }
+
+    #[test]
+    fn leftover_sticks_to_first() {
+        let mut game = tonpuusen();
+        for _ in 0..7 {
+            game.draw(&[], &[]).unwrap();
+        }
+        // South 4 is the last hand of sudden death, with South as player 0
+        game.draw(&[Seat::South], &[Seat::North]).unwrap();
+
+        let table = game.table();
+        assert!(table.finished);
+        assert_eq!(table.riichi_sticks, 0);
+        assert_eq!(table.scores, [29_000, 24_000, 23_000, 24_000]);
+    }
+
+    #[test]
+    fn dealer_stops_in_first() {
+        let mut game = tonpuusen();
+        for _ in 0..3 {
+            game.draw(&[], &[]).unwrap();
+        }
+        game.agari(Seat::East, WinMethod::Tsumo, &Payment::new(2_000), &[])
+            .unwrap();
+
+        let table = game.table();
+        assert!(table.finished);
+        assert_eq!(table.scores, [20_700, 20_700, 20_700, 37_900]);
+    }
+
+    #[test]
+    fn chombo_does_not_stop_the_game() {
+        let mut game = tonpuusen();
+        for _ in 0..3 {
+            game.draw(&[], &[]).unwrap();
+        }
+        game.chombo(Seat::South).unwrap();
+        game.chombo(Seat::West).unwrap();
+
+        // the dealer is first, but the hand is replayed
+        let table = game.table();
+        assert!(!table.finished);
+        assert_eq!(table.scores[3], 33_000);
+        assert_eq!(table.dealer, 3);
+    }
+
+    #[test]
+    fn abort_does_not_stop_the_game() {
+        let mut game = tonpuusen();
+        for _ in 0..3 {
+            game.draw(&[], &[]).unwrap();
+        }
+        game.chombo(Seat::South).unwrap();
+        game.chombo(Seat::West).unwrap();
+        let events: Vec<HandEvent> = ["Ew:riichi", "Sw:riichi", "Ww:riichi", "Nw:riichi"]
+            .iter()
+            .map(|event| event.to_string().try_into().unwrap())
+            .collect();
+        game.abort(&events, &MultiRonRules::default()).unwrap();
+
+        let table = game.table();
+        assert!(!table.finished);
+        assert_eq!(table.scores[3], 32_000);
+        assert_eq!(table.riichi_sticks, 4);
+
+        // a win in first place still lets the dealer stop
+        game.agari(
+            Seat::East,
+            WinMethod::Ron(Seat::South),
+            &Payment::new(320),
+            &[],
+        )
+        .unwrap();
+        assert!(game.table().finished);
+    }
+
+    #[test]
+    fn save_and_undo() {
+        let mut game = Game::new(Rules::default());
+        assert_eq!(game.undo().unwrap_err(), HandErr::NothingToUndo);
+
+        game.chombo(Seat::West).unwrap();
+        game.draw(&[Seat::North], &[Seat::North]).unwrap();
+        let saved: Game = game.to_json().try_into().unwrap();
+        assert_eq!(saved, game);
+
+        game.undo().unwrap();
+        game.undo().unwrap();
+        assert_eq!(game, Game::new(Rules::default()));
+
+        let invalid = Game::try_from("{\"length\":\"hanchan\"}".to_string());
+        assert_eq!(invalid.unwrap_err(), HandErr::InvalidGameState);
+    }
+}
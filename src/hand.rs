@@ -5,7 +5,17 @@ use crate::suit::Suit;
 use crate::tile_group::{GroupType, TileGroup};
 use error::HandErr;
 
-#[derive(Debug)]
+/// A winning hand, with the winds it was won under.
+///
+/// With the `serde` feature, a hand is (de)serialized with its tiles in notation (ex:
+/// `{"groups": ["123p", "55s"], "win_tile": "5s", "seat_tile": "Sw", "prev_tile": "Ew",
+/// "nukidora": 0}`), and checked the same way as [`Hand::new`] when deserializing.
+#[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "HandRepr", into = "HandRepr")
+)]
 pub struct Hand {
     groups: Vec<TileGroup>,
     win_tile: TileGroup,
@@ -13,6 +23,40 @@ pub struct Hand {
     /// Prevalent or round wind.
     prev_tile: TileGroup,
     isopen: bool,
+    /// Number of North tiles extracted as nukidora.
+    nukidora: u8,
+}
+
+/// How the winning tile completed the hand.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum Wait {
+    /// Two-sided sequence wait (ex: `23` waiting on `1` or `4`).
+    Ryanmen,
+    /// Closed wait for the middle of a sequence (ex: `13` waiting on `2`).
+    Kanchan,
+    /// Edge wait (ex: `12` waiting on `3`).
+    Penchan,
+    /// Single tile wait for the pair.
+    Tanki,
+    /// Wait on either of two pairs becoming a triplet.
+    Shanpon,
+}
+
+impl std::fmt::Display for Wait {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Ryanmen => write!(f, "Ryanmen"),
+            Self::Kanchan => write!(f, "Kanchan"),
+            Self::Penchan => write!(f, "Penchan"),
+            Self::Tanki => write!(f, "Tanki"),
+            Self::Shanpon => write!(f, "Shanpon"),
+        }
+    }
 }
 
 impl Hand {
@@ -78,6 +122,7 @@ impl Hand {
             seat_tile,
             prev_tile,
             isopen,
+            nukidora: 0,
         })
     }
     pub fn new_from_strings(
@@ -87,9 +132,14 @@ impl Hand {
         seat: String,
     ) -> Result<Self, HandErr> {
         let mut tile_groups: Vec<TileGroup> = Vec::new();
+        let mut nukidora = 0;
 
         // NOTE: Strings are complicated in Rust and needs evaluation about how to iterate over one. Because the string is expected to contain ASCII characters, `.chars()` should be okay.
         for i in &tiles {
+            if i.ends_with('k') {
+                nukidora += parse_nukidora(i)?;
+                continue;
+            }
             let tile: TileGroup = i.to_string().try_into()?;
             tile_groups.push(tile);
         }
@@ -100,7 +150,43 @@ impl Hand {
 
         let hand = Hand::new(tile_groups, win_tile, seat_tile, prev_tile)?;
 
-        Ok(hand)
+        hand.with_nukidora(nukidora)
+    }
+
+    /// Set the number of North tiles extracted as nukidora.
+    ///
+    /// Extracted tiles still count towards the four copies of North.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use mahc::hand::error::HandErr;
+    /// use mahc::hand::Hand;
+    ///
+    /// let tiles = ["111m", "999m", "NNNw", "789s", "ggd"].map(|t| t.to_string()).to_vec();
+    /// let hand = Hand::new_from_strings(tiles, "gd".into(), "Ew".into(), "Sw".into()).unwrap();
+    ///
+    /// let actual = hand.with_nukidora(2);
+    /// assert_eq!(actual.unwrap_err(), HandErr::TooManyNorths);
+    /// ```
+    pub fn with_nukidora(mut self, count: u8) -> Result<Self, HandErr> {
+        let norths: u8 = self
+            .groups
+            .iter()
+            .filter(|group| group.suit == Suit::Wind && group.value == "N")
+            .map(|group| group.group_type.tile_count())
+            .sum();
+        if norths + count > 4 {
+            return Err(HandErr::TooManyNorths);
+        }
+
+        self.nukidora = count;
+        Ok(self)
+    }
+
+    /// Get the number of North tiles extracted as nukidora.
+    pub fn nukidora(&self) -> u8 {
+        self.nukidora
     }
 
     /// Calculate the fu types in the hand.
@@ -229,17 +315,29 @@ impl Hand {
     /// assert_eq!(dora, 14);
     /// ```
     pub fn get_dora_count(&self, dora_indicator_tiles: Option<Vec<TileGroup>>) -> u32 {
-        let mut count = 0;
+        let dora_tiles: Vec<TileGroup> = dora_indicator_tiles
+            .unwrap_or_default()
+            .iter()
+            .map(|tile| tile.next_tile().unwrap())
+            .collect();
+        self.count_dora(&dora_tiles)
+    }
+
+    /// Get the number of akadora, nukidora and copies of the given dora tiles (not their
+    /// indicators) in the hand, including extracted North tiles.
+    pub fn count_dora(&self, dora_tiles: &[TileGroup]) -> u32 {
+        let mut count = self.nukidora as u32;
+        for dora_tile in dora_tiles {
+            if dora_tile.suit == Suit::Wind && dora_tile.value == "N" {
+                count += self.nukidora as u32;
+            }
+        }
         for group in &self.groups {
             if group.isaka {
                 count += 1;
             }
         }
-        if dora_indicator_tiles.is_none() {
-            return count;
-        }
-        for tile in dora_indicator_tiles.unwrap() {
-            let dora_tile = tile.next_tile().unwrap();
+        for dora_tile in dora_tiles {
             for triplet in self.triplets() {
                 if triplet.value == dora_tile.value && triplet.suit == dora_tile.suit {
                     count += 3;
@@ -320,6 +418,11 @@ impl Hand {
             .collect()
     }
 
+    /// Get every group in the hand.
+    pub fn groups(&self) -> &[TileGroup] {
+        &self.groups
+    }
+
     /// Get the winning tile the completes the hand.
     pub fn win_tile(&self) -> TileGroup {
         self.win_tile.clone()
@@ -340,6 +443,37 @@ impl Hand {
         self.isopen
     }
 
+    /// Get how the winning tile completed the last group of the hand.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use mahc::hand::{Hand, Wait};
+    ///
+    /// let tiles = ["123p", "456p", "99s", "234s", "789m"].map(|t| t.to_string()).to_vec();
+    /// let hand = Hand::new_from_strings(tiles, "7m".into(), "Ew".into(), "Sw".into()).unwrap();
+    ///
+    /// assert_eq!(hand.wait(), Some(Wait::Penchan));
+    /// ```
+    pub fn wait(&self) -> Option<Wait> {
+        let group = self.groups.last()?;
+        match group.group_type {
+            GroupType::Pair => Some(Wait::Tanki),
+            GroupType::Triplet => Some(Wait::Shanpon),
+            GroupType::Sequence => {
+                let mid_tile = group.parse_u8().ok()? + 1;
+                if self.win_tile.parse_u8().ok()? == mid_tile {
+                    Some(Wait::Kanchan)
+                } else if !self.win_tile.isterminal && group.isterminal {
+                    Some(Wait::Penchan)
+                } else {
+                    Some(Wait::Ryanmen)
+                }
+            }
+            GroupType::Kan | GroupType::None => None,
+        }
+    }
+
     //yaku validation
 
     /// Check if the hand only contains simple tiles -- no terminal or honor tiles.
@@ -459,13 +593,11 @@ impl Hand {
             if list_of_seqs[0].0 == list_of_seqs[1].0 && list_of_seqs[1].0 == list_of_seqs[2].0 {
                 return true;
             }
-        } else if list_of_seqs.len() == 4 {
-            if list_of_seqs[1].0 == list_of_seqs[2].0 {
-                if list_of_seqs[0].0 == list_of_seqs[1].0 || list_of_seqs[2].0 == list_of_seqs[3].0
-                {
-                    return true;
-                }
-            }
+        } else if list_of_seqs.len() == 4
+            && list_of_seqs[1].0 == list_of_seqs[2].0
+            && (list_of_seqs[0].0 == list_of_seqs[1].0 || list_of_seqs[2].0 == list_of_seqs[3].0)
+        {
+            return true;
         }
 
         false
@@ -657,10 +789,8 @@ impl Hand {
             if list_of_vals[0] == list_of_vals[1] {
                 return true;
             }
-            if list_of_vals.len() == 4 {
-                if list_of_vals[2] == list_of_vals[3] {
-                    return true;
-                }
+            if list_of_vals.len() == 4 && list_of_vals[2] == list_of_vals[3] {
+                return true;
             }
         }
 
@@ -954,6 +1084,57 @@ impl Hand {
     }
 }
 
+/// Parse extracted North tiles, written as North tiles followed by `k` (ex: `Nwk`, `NNwk`,
+/// `44zk`).
+fn parse_nukidora(token: &str) -> Result<u8, HandErr> {
+    let tiles = token.strip_suffix('k').ok_or(HandErr::InvalidGroup)?;
+    let (values, north) = match tiles.chars().last() {
+        Some('w') => (&tiles[..tiles.len() - 1], 'N'),
+        Some('z') => (&tiles[..tiles.len() - 1], '4'),
+        _ => return Err(HandErr::InvalidGroup),
+    };
+    if values.is_empty() || values.len() > 4 || !values.chars().all(|value| value == north) {
+        return Err(HandErr::InvalidGroup);
+    }
+
+    Ok(values.len() as u8)
+}
+
+/// How a [`Hand`] is (de)serialized.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct HandRepr {
+    groups: Vec<TileGroup>,
+    win_tile: TileGroup,
+    seat_tile: TileGroup,
+    prev_tile: TileGroup,
+    #[serde(default)]
+    nukidora: u8,
+}
+
+#[cfg(feature = "serde")]
+impl From<Hand> for HandRepr {
+    fn from(hand: Hand) -> Self {
+        Self {
+            groups: hand.groups,
+            win_tile: hand.win_tile,
+            seat_tile: hand.seat_tile,
+            prev_tile: hand.prev_tile,
+            nukidora: hand.nukidora,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<HandRepr> for Hand {
+    type Error = HandErr;
+
+    fn try_from(repr: HandRepr) -> Result<Self, Self::Error> {
+        Hand::new(repr.groups, repr.win_tile, repr.seat_tile, repr.prev_tile)?
+            .with_nukidora(repr.nukidora)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::Hand;
@@ -2566,11 +2747,38 @@ mod tests {
         );
         assert_eq!(out.unwrap_err(), HandErr::InvalidShape);
     }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn hand_serde_round_trip() {
+        let tiles = ["123p", "456p", "99s", "234s", "678m", "Nwk"].map(|t| t.to_string());
+        let hand =
+            Hand::new_from_strings(tiles.to_vec(), "6m".into(), "Ew".into(), "Sw".into()).unwrap();
+        let json = serde_json::to_value(&hand).unwrap();
+        assert_eq!(json["groups"][0], "123p");
+        assert_eq!(json["seat_tile"], "Sw");
+        assert_eq!(json["nukidora"], 1);
+
+        let actual: Hand = serde_json::from_value(json).unwrap();
+        assert_eq!(actual.groups(), hand.groups());
+        assert_eq!(actual.nukidora(), 1);
+
+        // deserialized hands are checked the same way as parsed ones
+        let json = serde_json::json!({
+            "groups" : ["123p", "456p"],
+            "win_tile" : "6p",
+            "seat_tile" : "Sw",
+            "prev_tile" : "Ew"
+        });
+        let err = serde_json::from_value::<Hand>(json).unwrap_err();
+        assert_eq!(err.to_string(), HandErr::InvalidShape.to_string());
+    }
 }
 
 #[cfg(test)]
 mod tile_group_tests {
     use super::Hand;
+    use crate::hand::error::HandErr;
     use crate::suit::Suit;
     use crate::tile_group::{GroupType, TileGroup};
 
@@ -2766,4 +2974,75 @@ mod tile_group_tests {
         let dora = out.get_dora_count(None);
         assert_eq!(dora, 1);
     }
+    #[test]
+    fn nukidora_counted_as_dora() {
+        let out = Hand::new_from_strings(
+            vec![
+                "111m".to_string(),
+                "999m".to_string(),
+                "456p".to_string(),
+                "789s".to_string(),
+                "ggd".to_string(),
+                "NNwk".to_string(),
+            ],
+            "gd".to_string(),
+            "Ew".to_string(),
+            "Sw".to_string(),
+        )
+        .unwrap();
+        assert_eq!(out.nukidora(), 2);
+        assert_eq!(out.get_dora_count(None), 2);
+
+        // a West indicator makes North the dora, so every extracted North counts twice
+        let indicator: TileGroup = "Ww".to_string().try_into().unwrap();
+        assert_eq!(out.get_dora_count(Some(vec![indicator])), 4);
+    }
+
+    #[test]
+    fn nukidora_four_copy_limit() {
+        let out = Hand::new_from_strings(
+            vec![
+                "111m".to_string(),
+                "999m".to_string(),
+                "NNNw".to_string(),
+                "789s".to_string(),
+                "ggd".to_string(),
+                "Nwk".to_string(),
+            ],
+            "gd".to_string(),
+            "Ew".to_string(),
+            "Sw".to_string(),
+        );
+        assert_eq!(out.unwrap().nukidora(), 1);
+
+        let out = Hand::new_from_strings(
+            vec![
+                "111m".to_string(),
+                "999m".to_string(),
+                "NNNw".to_string(),
+                "789s".to_string(),
+                "ggd".to_string(),
+                "44zk".to_string(),
+            ],
+            "gd".to_string(),
+            "Ew".to_string(),
+            "Sw".to_string(),
+        );
+        assert_eq!(out.unwrap_err(), HandErr::TooManyNorths);
+
+        let out = Hand::new_from_strings(
+            vec![
+                "111m".to_string(),
+                "999m".to_string(),
+                "456p".to_string(),
+                "789s".to_string(),
+                "ggd".to_string(),
+                "Ewk".to_string(),
+            ],
+            "gd".to_string(),
+            "Ew".to_string(),
+            "Sw".to_string(),
+        );
+        assert_eq!(out.unwrap_err(), HandErr::InvalidGroup);
+    }
 }
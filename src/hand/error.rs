@@ -16,6 +16,37 @@ pub enum HandErr {
     RinshanIppatsu,
     NoHan,
     NoFu,
+    InvalidPayment,
+    InvalidRiichiTurn,
+    NotIishanten,
+    RonOwnDiscard,
+    GameOver,
+    NothingToUndo,
+    InvalidGameState,
+    GameStateFile,
+    InvalidUma,
+    GameNotOver,
+    SanmaManzu,
+    SanmaChi,
+    SanmaNorth,
+    TooManyNorths,
+    InvalidWinners,
+    InvalidHandEvent,
+    InvalidKyuushu,
+    NoAbortiveDraw,
+    InvalidTableFormat,
+    ClaimWinnerMismatch,
+    InvalidLang,
+    InvalidRenderFormat,
+    InvalidReplCommand,
+    InvalidRequest,
+    UnknownEndpoint,
+    InvalidMjaiEvent,
+    InvalidTrials,
+    NukidoraOutsideSanma,
+    InvalidHan,
+    ClaimMethodMismatch,
+    InvalidScore,
 }
 
 impl std::fmt::Display for HandErr {
@@ -41,6 +72,120 @@ impl std::fmt::Display for HandErr {
             }
             Self::NoHan => write!(f, "No Han provided!"),
             Self::NoFu => write!(f, "No Fu provided!"),
+            Self::InvalidPayment => write!(f, "Invalid Payment given"),
+            Self::NotIishanten => write!(f, "Hand is not Tenpai or Iishanten"),
+            Self::InvalidRiichiTurn => write!(f, "Riichi Turn is not in the Discard Pond"),
+            Self::RonOwnDiscard => write!(f, "Cant Ron your own Discard"),
+            Self::GameOver => write!(f, "Game is already Over"),
+            Self::NothingToUndo => write!(f, "No Hand to Undo"),
+            Self::InvalidGameState => write!(f, "Invalid Game State found"),
+            Self::GameStateFile => write!(f, "Unable to read or write the Game State File"),
+            Self::InvalidUma => write!(f, "Invalid Uma given, it needs four values adding up to 0"),
+            Self::GameNotOver => write!(f, "Game is not Over yet"),
+            Self::SanmaManzu => write!(f, "Cant have 2m to 8m in Sanma"),
+            Self::SanmaChi => write!(f, "Cant Chi in Sanma"),
+            Self::InvalidWinners => {
+                write!(
+                    f,
+                    "Cant have no Winners, the same Winner twice or over three Winners"
+                )
+            }
+            Self::InvalidHandEvent => write!(f, "Invalid Hand Event found"),
+            Self::InvalidKyuushu => {
+                write!(
+                    f,
+                    "Cant Kyuushu Kyuuhai without nine Terminals and Honors on the first Draw"
+                )
+            }
+            Self::NoAbortiveDraw => write!(f, "No Abortive Draw found"),
+            Self::InvalidTableFormat => {
+                write!(f, "Invalid Table Format given (md, csv, html or text)")
+            }
+            Self::ClaimWinnerMismatch => {
+                write!(f, "Claimed Tsumo Payment does not match the Winner's Seat")
+            }
+            Self::InvalidLang => write!(f, "Invalid Language given (romaji, en, ja or zh)"),
+            Self::InvalidRenderFormat => write!(f, "Invalid Render Format given (svg or ascii)"),
+            Self::InvalidReplCommand => write!(f, "Invalid Repl Command given (see :help)"),
+            Self::InvalidRequest => write!(f, "Invalid Request given"),
+            Self::UnknownEndpoint => write!(f, "Unknown Endpoint"),
+            Self::InvalidMjaiEvent => write!(f, "Invalid Mjai Event given"),
+            Self::InvalidTrials => write!(f, "Invalid Trials given (at least 1)"),
+            Self::NukidoraOutsideSanma => write!(
+                f,
+                "Cant have Nukidora outside of Sanma unless the rules allow it"
+            ),
+            Self::InvalidHan => write!(f, "Invalid Han given (at most 1000)"),
+            Self::ClaimMethodMismatch => {
+                write!(f, "Claimed Payment does not match the Tsumo or Ron")
+            }
+            Self::InvalidScore => write!(f, "Invalid Score given (han or fu do not add up)"),
+            Self::TooManyNorths => write!(f, "Cant have more than four North tiles"),
+            Self::SanmaNorth => write!(f, "Cant have a North Seat or Round in Sanma"),
+        }
+    }
+}
+
+impl HandErr {
+    /// Get the code of the error, which does not change between versions.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use mahc::hand::error::HandErr;
+    ///
+    /// assert_eq!(HandErr::NoYaku.code(), "no_yaku");
+    /// assert_eq!(HandErr::RinshanKanWithoutKan.code(), "rinshan_kan_without_kan");
+    /// ```
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::InvalidGroup => "invalid_group",
+            Self::InvalidSuit => "invalid_suit",
+            Self::InvalidShape => "invalid_shape",
+            Self::NoYaku => "no_yaku",
+            Self::NoHandTiles => "no_hand_tiles",
+            Self::NoWinTile => "no_win_tile",
+            Self::DuplicateRiichi => "duplicate_riichi",
+            Self::IppatsuWithoutRiichi => "ippatsu_without_riichi",
+            Self::DoubleRiichiHaiteiIppatsu => "double_riichi_haitei_ippatsu",
+            Self::DoubleRiichiHaiteiChankan => "double_riichi_haitei_chankan",
+            Self::ChankanTsumo => "chankan_tsumo",
+            Self::RinshanKanWithoutKan => "rinshan_kan_without_kan",
+            Self::RinshanWithoutTsumo => "rinshan_without_tsumo",
+            Self::RinshanIppatsu => "rinshan_ippatsu",
+            Self::NoHan => "no_han",
+            Self::NoFu => "no_fu",
+            Self::InvalidPayment => "invalid_payment",
+            Self::InvalidRiichiTurn => "invalid_riichi_turn",
+            Self::NotIishanten => "not_iishanten",
+            Self::RonOwnDiscard => "ron_own_discard",
+            Self::GameOver => "game_over",
+            Self::NothingToUndo => "nothing_to_undo",
+            Self::InvalidGameState => "invalid_game_state",
+            Self::GameStateFile => "game_state_file",
+            Self::InvalidUma => "invalid_uma",
+            Self::GameNotOver => "game_not_over",
+            Self::SanmaManzu => "sanma_manzu",
+            Self::SanmaChi => "sanma_chi",
+            Self::SanmaNorth => "sanma_north",
+            Self::TooManyNorths => "too_many_norths",
+            Self::InvalidWinners => "invalid_winners",
+            Self::InvalidHandEvent => "invalid_hand_event",
+            Self::InvalidKyuushu => "invalid_kyuushu",
+            Self::NoAbortiveDraw => "no_abortive_draw",
+            Self::InvalidTableFormat => "invalid_table_format",
+            Self::ClaimWinnerMismatch => "claim_winner_mismatch",
+            Self::InvalidLang => "invalid_lang",
+            Self::InvalidRenderFormat => "invalid_render_format",
+            Self::InvalidReplCommand => "invalid_repl_command",
+            Self::InvalidRequest => "invalid_request",
+            Self::UnknownEndpoint => "unknown_endpoint",
+            Self::InvalidMjaiEvent => "invalid_mjai_event",
+            Self::InvalidTrials => "invalid_trials",
+            Self::NukidoraOutsideSanma => "nukidora_outside_sanma",
+            Self::InvalidHan => "invalid_han",
+            Self::ClaimMethodMismatch => "claim_method_mismatch",
+            Self::InvalidScore => "invalid_score",
         }
     }
 }
@@ -0,0 +1,355 @@
+use crate::fu::Fu;
+use crate::hand::error::HandErr;
+use crate::yaku::Yaku;
+
+/// Language of yaku, fu and error names.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Lang {
+    /// Japanese names in romaji (ex: `Tanyao`), as used everywhere else.
+    #[default]
+    Romaji,
+    /// English names (ex: `All Simples`).
+    English,
+    /// Japanese names in kanji (ex: `断幺九`).
+    Japanese,
+    /// Chinese names (ex: `断幺九`).
+    Chinese,
+}
+
+impl Lang {
+    /// Every supported language.
+    pub const ALL: [Lang; 4] = [Lang::Romaji, Lang::English, Lang::Japanese, Lang::Chinese];
+}
+
+impl TryFrom<String> for Lang {
+    type Error = HandErr;
+
+    /// Parse the language from its name or code.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use mahc::lang::Lang;
+    ///
+    /// let actual: Lang = "ja".to_string().try_into().unwrap();
+    /// assert_eq!(actual, Lang::Japanese);
+    ///
+    /// assert!(Lang::try_from("klingon".to_string()).is_err());
+    /// ```
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        match value.to_lowercase().as_str() {
+            "romaji" => Ok(Self::Romaji),
+            "en" | "english" => Ok(Self::English),
+            "ja" | "jp" | "japanese" => Ok(Self::Japanese),
+            "zh" | "cn" | "chinese" => Ok(Self::Chinese),
+            _ => Err(HandErr::InvalidLang),
+        }
+    }
+}
+
+/// Get the name of the yaku in the language.
+///
+/// # Examples
+///
+/// ```rust
+/// use mahc::lang::{self, Lang};
+/// use mahc::yaku::Yaku;
+///
+/// assert_eq!(lang::yaku_name(&Yaku::Tanyao, Lang::English), "All Simples");
+/// assert_eq!(lang::yaku_name(&Yaku::Tanyao, Lang::Japanese), "断幺九");
+/// assert_eq!(lang::yaku_name(&Yaku::SanshokuDoujun, Lang::Romaji), "Sanshoku Doujun");
+/// ```
+pub fn yaku_name(yaku: &Yaku, lang: Lang) -> &'static str {
+    let (english, japanese, chinese) = match yaku {
+        Yaku::Tanyao => ("All Simples", "断幺九", "断幺九"),
+        Yaku::Iipeikou => ("Pure Double Sequence", "一盃口", "一般高"),
+        Yaku::Yakuhai => ("Value Tiles", "役牌", "役牌"),
+        Yaku::MenzenTsumo => ("Fully Concealed Hand", "門前清自摸和", "门前清自摸和"),
+        Yaku::Pinfu => ("No-points Hand", "平和", "平和"),
+        Yaku::Riichi => ("Riichi", "立直", "立直"),
+        Yaku::Ippatsu => ("One Shot", "一発", "一发"),
+        Yaku::Haitei => ("Last Tile", "海底摸月", "海底捞月"),
+        Yaku::RinshanKaihou => ("After a Kan", "嶺上開花", "岭上开花"),
+        Yaku::Chankan => ("Robbing a Kan", "槍槓", "抢杠"),
+
+        Yaku::DoubleRiichi => ("Double Riichi", "ダブル立直", "双立直"),
+        Yaku::Toitoi => ("All Triplets", "対々和", "对对和"),
+        Yaku::Ittsuu => ("Pure Straight", "一気通貫", "一气通贯"),
+        Yaku::SanshokuDoujun => ("Mixed Triple Sequence", "三色同順", "三色同顺"),
+        Yaku::Chantaiyao => ("Half Outside Hand", "混全帯幺九", "混全带幺九"),
+        Yaku::Sanankou => ("Three Concealed Triplets", "三暗刻", "三暗刻"),
+        Yaku::SanshokuDoukou => ("Triple Triplets", "三色同刻", "三色同刻"),
+        Yaku::Sankantsu => ("Three Kans", "三槓子", "三杠子"),
+        Yaku::Honroutou => ("All Terminals and Honors", "混老頭", "混老头"),
+        Yaku::Shousangen => ("Little Three Dragons", "小三元", "小三元"),
+        Yaku::Chiitoitsu => ("Seven Pairs", "七対子", "七对子"),
+
+        Yaku::Honitsu => ("Half Flush", "混一色", "混一色"),
+        Yaku::JunchanTaiyao => ("Fully Outside Hand", "純全帯幺九", "纯全带幺九"),
+        Yaku::Ryanpeikou => ("Twice Pure Double Sequence", "二盃口", "二般高"),
+
+        Yaku::Chinitsu => ("Full Flush", "清一色", "清一色"),
+
+        Yaku::KazoeYakuman => ("Counted Yakuman", "数え役満", "累计役满"),
+        Yaku::KokushiMusou => ("Thirteen Orphans", "国士無双", "国士无双"),
+        Yaku::KokushiMusou13SidedWait => (
+            "Thirteen Orphans 13-sided Wait",
+            "国士無双十三面待ち",
+            "国士无双十三面",
+        ),
+        Yaku::Suuankou => ("Four Concealed Triplets", "四暗刻", "四暗刻"),
+        Yaku::SuuankouTankiWait => (
+            "Four Concealed Triplets Single Wait",
+            "四暗刻単騎",
+            "四暗刻单骑",
+        ),
+        Yaku::Daisangen => ("Big Three Dragons", "大三元", "大三元"),
+        Yaku::Shousuushii => ("Little Four Winds", "小四喜", "小四喜"),
+        Yaku::Daisuushii => ("Big Four Winds", "大四喜", "大四喜"),
+        Yaku::Tsuuiisou => ("All Honors", "字一色", "字一色"),
+        Yaku::Daichiishin => ("Big Seven Stars", "大七星", "大七星"),
+        Yaku::Chinroutou => ("All Terminals", "清老頭", "清老头"),
+        Yaku::Ryuuiisou => ("All Green", "緑一色", "绿一色"),
+        Yaku::ChuurenPoutou => ("Nine Gates", "九蓮宝燈", "九莲宝灯"),
+        Yaku::ChuurenPoutou9SidedWait => ("True Nine Gates", "純正九蓮宝燈", "纯正九莲宝灯"),
+        Yaku::Suukantsu => ("Four Kans", "四槓子", "四杠子"),
+        Yaku::Tenhou => ("Blessing of Heaven", "天和", "天和"),
+        Yaku::Chiihou => ("Blessing of Earth", "地和", "地和"),
+    };
+
+    match lang {
+        Lang::Romaji => yaku.name(),
+        Lang::English => english,
+        Lang::Japanese => japanese,
+        Lang::Chinese => chinese,
+    }
+}
+
+/// Get the name of the fu in the language.
+///
+/// # Examples
+///
+/// ```rust
+/// use mahc::fu::Fu;
+/// use mahc::lang::{self, Lang};
+///
+/// assert_eq!(lang::fu_name(&Fu::SimpleClosedTriplet, Lang::Romaji), "Chunchan Ankou");
+/// assert_eq!(lang::fu_name(&Fu::SimpleClosedTriplet, Lang::Japanese), "中張牌暗刻");
+/// ```
+pub fn fu_name(fu: &Fu, lang: Lang) -> &'static str {
+    let (romaji, japanese, chinese) = match fu {
+        Fu::BasePoints => ("Fuutei", "副底", "底符"),
+        Fu::BasePointsChitoi => ("Chiitoitsu Fu", "七対子符", "七对子符"),
+        Fu::ClosedRon => ("Menzen Kafu", "門前加符", "门前加符"),
+        Fu::Tsumo => ("Tsumo Fu", "ツモ符", "自摸符"),
+        Fu::NonSimpleClosedTriplet => ("Yaochuu Ankou", "幺九牌暗刻", "幺九暗刻"),
+        Fu::SimpleClosedTriplet => ("Chunchan Ankou", "中張牌暗刻", "中张暗刻"),
+        Fu::NonSimpleOpenTriplet => ("Yaochuu Minkou", "幺九牌明刻", "幺九明刻"),
+        Fu::SimpleOpenTriplet => ("Chunchan Minkou", "中張牌明刻", "中张明刻"),
+        Fu::NonSimpleClosedKan => ("Yaochuu Ankan", "幺九牌暗槓", "幺九暗杠"),
+        Fu::SimpleClosedKan => ("Chunchan Ankan", "中張牌暗槓", "中张暗杠"),
+        Fu::NonSimpleOpenKan => ("Yaochuu Minkan", "幺九牌明槓", "幺九明杠"),
+        Fu::SimpleOpenKan => ("Chunchan Minkan", "中張牌明槓", "中张明杠"),
+        Fu::Toitsu => ("Yakuhai Jantou", "役牌雀頭", "役牌雀头"),
+        Fu::SingleWait => ("Machi Fu", "待ち符", "听牌符"),
+    };
+
+    match lang {
+        Lang::Romaji => romaji,
+        Lang::English => fu.name(),
+        Lang::Japanese => japanese,
+        Lang::Chinese => chinese,
+    }
+}
+
+/// Get the yaku as it is printed, with its han or as a yakuman.
+///
+/// Romaji keeps the labels of [`Yaku::to_string`].
+///
+/// # Examples
+///
+/// ```rust
+/// use mahc::lang::{self, Lang};
+/// use mahc::yaku::Yaku;
+///
+/// assert_eq!(lang::yaku_label(&Yaku::Honitsu, true, Lang::Romaji), "Honitsu: 2");
+/// assert_eq!(lang::yaku_label(&Yaku::Honitsu, true, Lang::Japanese), "混一色: 2");
+/// assert_eq!(lang::yaku_label(&Yaku::Daisangen, false, Lang::English), "Big Three Dragons Yakuman");
+/// ```
+pub fn yaku_label(yaku: &Yaku, is_open: bool, lang: Lang) -> String {
+    let yakuman = match lang {
+        Lang::Romaji => return yaku.to_string(is_open),
+        Lang::English => "Yakuman",
+        Lang::Japanese => "役満",
+        Lang::Chinese => "役满",
+    };
+    let name = yaku_name(yaku, lang);
+    if yaku.is_yakuman() {
+        format!("{name} {yakuman}")
+    } else {
+        format!("{name}: {}", yaku.get_han(is_open))
+    }
+}
+
+/// Get the fu as it is printed, with its minipoints.
+///
+/// # Examples
+///
+/// ```rust
+/// use mahc::fu::Fu;
+/// use mahc::lang::{self, Lang};
+///
+/// assert_eq!(lang::fu_label(&Fu::SimpleClosedTriplet, Lang::Romaji), "Chunchan Ankou: 4");
+/// assert_eq!(lang::fu_label(&Fu::SingleWait, Lang::English), "Single Wait: 2");
+/// ```
+pub fn fu_label(fu: &Fu, lang: Lang) -> String {
+    format!("{}: {}", fu_name(fu, lang), fu.value())
+}
+
+/// Get the message of the error in the language.
+///
+/// Romaji and English both use the English message.
+///
+/// # Examples
+///
+/// ```rust
+/// use mahc::hand::error::HandErr;
+/// use mahc::lang::{self, Lang};
+///
+/// assert_eq!(lang::error_message(&HandErr::NoYaku, Lang::English), "No Yaku");
+/// assert_eq!(lang::error_message(&HandErr::NoYaku, Lang::Japanese), "役がありません");
+/// ```
+pub fn error_message(err: &HandErr, lang: Lang) -> String {
+    let (japanese, chinese) = match err {
+        HandErr::InvalidGroup => ("無効な面子があります", "存在无效的面子"),
+        HandErr::InvalidSuit => ("無効な種類の牌があります", "存在无效的花色"),
+        HandErr::InvalidShape => ("無効な手牌の形です", "手牌形状无效"),
+        HandErr::NoYaku => ("役がありません", "没有役"),
+        HandErr::NoHandTiles => ("手牌が指定されていません", "未提供手牌"),
+        HandErr::NoWinTile => ("和了牌が指定されていません", "未提供和牌"),
+        HandErr::DuplicateRiichi => ("立直とダブル立直は同時にできません", "不能同时立直和双立直"),
+        HandErr::IppatsuWithoutRiichi => ("立直なしで一発はできません", "没有立直不能一发"),
+        HandErr::ChankanTsumo => ("ツモと槍槓は同時にできません", "不能同时自摸和抢杠"),
+        HandErr::RinshanKanWithoutKan => ("槓なしで嶺上開花はできません", "没有杠不能岭上开花"),
+        HandErr::RinshanWithoutTsumo => ("ツモなしで嶺上開花はできません", "没有自摸不能岭上开花"),
+        HandErr::RinshanIppatsu => ("嶺上開花と一発は同時にできません", "不能同时岭上开花和一发"),
+        HandErr::DoubleRiichiHaiteiIppatsu | HandErr::DoubleRiichiHaiteiChankan => (
+            "ダブル立直、一発、海底は同時にできません",
+            "不能同时双立直、一发和海底",
+        ),
+        HandErr::NoHan => ("翻が指定されていません", "未提供番数"),
+        HandErr::NoFu => ("符が指定されていません", "未提供符数"),
+        HandErr::InvalidPayment => ("無効な支払いです", "无效的支付"),
+        HandErr::NotIishanten => ("聴牌でも一向聴でもありません", "手牌不是听牌或一向听"),
+        HandErr::InvalidRiichiTurn => ("立直の巡目が河にありません", "立直巡目不在牌河中"),
+        HandErr::RonOwnDiscard => ("自分の捨て牌ではロンできません", "不能荣和自己的弃牌"),
+        HandErr::GameOver => ("対局はすでに終了しています", "对局已经结束"),
+        HandErr::NothingToUndo => ("取り消す局がありません", "没有可以撤销的局"),
+        HandErr::InvalidGameState => ("無効な対局状態です", "无效的对局状态"),
+        HandErr::GameStateFile => (
+            "対局状態ファイルを読み書きできません",
+            "无法读写对局状态文件",
+        ),
+        HandErr::InvalidUma => (
+            "無効なウマです。合計0になる4つの値が必要です",
+            "无效的顺位马，需要四个总和为0的值",
+        ),
+        HandErr::GameNotOver => ("対局はまだ終了していません", "对局尚未结束"),
+        HandErr::SanmaManzu => ("三麻では2萬から8萬は使えません", "三麻中不能有2万到8万"),
+        HandErr::SanmaChi => ("三麻ではチーできません", "三麻中不能吃"),
+        HandErr::SanmaNorth => ("三麻では北家や北場はありません", "三麻中不能有北家或北场"),
+        HandErr::TooManyNorths => ("北は4枚までです", "北不能超过四张"),
+        HandErr::InvalidWinners => (
+            "和了者がいない、同じ和了者が2回、または4人以上の和了者はできません",
+            "不能没有和牌者、重复的和牌者或超过三个和牌者",
+        ),
+        HandErr::InvalidHandEvent => ("無効な局のイベントです", "无效的对局事件"),
+        HandErr::InvalidKyuushu => (
+            "第一ツモで幺九牌が9種類なければ九種九牌はできません",
+            "第一巡摸牌时没有九种幺九牌不能九种九牌",
+        ),
+        HandErr::NoAbortiveDraw => ("途中流局がありません", "没有途中流局"),
+        HandErr::InvalidTableFormat => (
+            "無効な表の形式です (md, csv, html, text)",
+            "无效的表格格式 (md, csv, html, text)",
+        ),
+        HandErr::ClaimWinnerMismatch => (
+            "申告したツモの支払いが和了者の席と合いません",
+            "申报的自摸支付与和牌者的座位不符",
+        ),
+        HandErr::InvalidLang => (
+            "無効な言語です (romaji, en, ja, zh)",
+            "无效的语言 (romaji, en, ja, zh)",
+        ),
+        HandErr::InvalidRenderFormat => (
+            "無効な描画形式です (svg, ascii)",
+            "无效的绘制格式 (svg, ascii)",
+        ),
+        HandErr::InvalidReplCommand => (
+            "無効なコマンドです (:help を参照)",
+            "无效的命令 (参见 :help)",
+        ),
+        HandErr::InvalidRequest => ("無効なリクエストです", "无效的请求"),
+        HandErr::UnknownEndpoint => ("不明なエンドポイントです", "未知的接口"),
+        HandErr::InvalidMjaiEvent => ("無効な mjai イベントです", "无效的 mjai 事件"),
+        HandErr::InvalidTrials => ("無効な試行回数です (1 以上)", "无效的试验次数 (至少 1)"),
+        HandErr::NukidoraOutsideSanma => ("抜きドラは三麻だけです", "拔北宝牌只能在三麻中使用"),
+        HandErr::InvalidHan => ("無効な翻数です (1000 以下)", "无效的番数 (最多 1000)"),
+        HandErr::ClaimMethodMismatch => (
+            "申告した支払いがツモかロンかと合いません",
+            "申报的支付与自摸或荣和不符",
+        ),
+        HandErr::InvalidScore => (
+            "無効な点数です (翻か符が合いません)",
+            "无效的得分 (番或符不符)",
+        ),
+    };
+
+    match lang {
+        Lang::Romaji | Lang::English => err.to_string(),
+        Lang::Japanese => japanese.to_string(),
+        Lang::Chinese => chinese.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn labels_by_lang() {
+        let labels: Vec<String> = Lang::ALL
+            .iter()
+            .map(|lang| yaku_label(&Yaku::SanshokuDoujun, false, *lang))
+            .collect();
+        assert_eq!(
+            labels,
+            [
+                "Sanshoku Doujun: 2",
+                "Mixed Triple Sequence: 2",
+                "三色同順: 2",
+                "三色同顺: 2"
+            ]
+        );
+
+        assert_eq!(
+            yaku_label(&Yaku::Suuankou, false, Lang::Chinese),
+            "四暗刻 役满"
+        );
+        assert_eq!(
+            fu_label(&Fu::SimpleClosedTriplet, Lang::Romaji),
+            "Chunchan Ankou: 4"
+        );
+        assert_eq!(fu_label(&Fu::SingleWait, Lang::English), "Single Wait: 2");
+        assert_eq!(fu_label(&Fu::Toitsu, Lang::Japanese), "役牌雀頭: 2");
+    }
+
+    #[test]
+    fn error_messages_by_lang() {
+        let err = HandErr::RonOwnDiscard;
+        assert_eq!(error_message(&err, Lang::Romaji), err.to_string());
+        assert_eq!(error_message(&err, Lang::English), err.to_string());
+        assert_eq!(error_message(&err, Lang::Chinese), "不能荣和自己的弃牌");
+    }
+}
@@ -1,13 +1,33 @@
+pub mod abortive;
+pub mod advisor;
+pub mod all_last;
+pub mod aotenjou;
 pub mod calc;
+pub mod defence;
+pub mod explain;
+#[cfg(feature = "ffi")]
+pub mod ffi;
 pub mod fu;
+pub mod game;
 pub mod hand;
+pub mod lang;
 pub mod limit_hand;
+pub mod mjai;
 pub mod payment;
+pub mod placement;
+#[cfg(feature = "python")]
+pub mod python;
+pub mod render;
+pub mod report;
+pub mod sanma;
 pub mod score;
+pub mod score_table;
+pub mod seat;
+pub mod serve;
+pub mod settlement;
+pub mod simulation;
 pub mod suit;
+pub mod tenpai;
 pub mod tile_group;
+pub mod verify;
 pub mod yaku;
-
-/// Characters that represent terminal or honor tiles.
-// Using a fixed array gets stored on the stack rather than a `String` which gets stored on the heap.
-const TERMINAL_CHARS: [char; 9] = ['1', '9', 'E', 'S', 'W', 'N', 'r', 'g', 'w'];
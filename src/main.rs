@@ -1,18 +1,40 @@
 use std::ffi::OsString;
 use std::fs::{self, OpenOptions};
-use std::io::Write;
+use std::io::{BufRead, Write};
 
-use clap::Parser;
-use mahc::calc;
+use clap::{Args as ClapArgs, Parser, Subcommand};
+use mahc::abortive::HandEvent;
+use mahc::advisor::{self, Advice};
+use mahc::all_last::{AllLast, Requirement};
+use mahc::aotenjou::{self, AotenjouPayment, AotenjouScore};
+use mahc::calc::{self, HandValue, ReverseLookup, YonmaRules};
+use mahc::defence::{RiichiOpponent, Safety};
+use mahc::explain::Explanation;
+use mahc::game::{Game, GameLength, Rules, Table};
 use mahc::hand::error::HandErr;
 use mahc::hand::Hand;
-use mahc::payment::Payment;
-use mahc::score::{FuValue, HanValue, HonbaCounter, Score};
+use mahc::lang::{self, Lang};
+use mahc::limit_hand::LimitHands;
+use mahc::mjai::Referee;
+use mahc::payment::{ClaimedPayment, Payment, PlayerPoints, Points, SanmaTsumo};
+use mahc::placement::{placements, Placement, PlacementRules, TieRule};
+use mahc::render::{self, RenderFormat};
+use mahc::report::{AotenjouCalcReport, AotenjouHandReport, CalcReport, HandReport, VerifyReport};
+use mahc::sanma::SanmaRules;
+use mahc::score::{FuValue, HanValue, HonbaCounter, RiichiCounter, Score};
+use mahc::score_table::{ScoreTable, TableFormat, TableRules};
+use mahc::seat::Seat;
+use mahc::serve::{Request, Response, Server};
+use mahc::settlement::{MultiRon, MultiRonBonus, MultiRonRules, Settlement, WinMethod};
+use mahc::simulation::{Estimate, Simulation};
+use mahc::tenpai::{split_hand, Tenpai, TileCounts, WinContext};
 use mahc::tile_group::TileGroup;
-use serde_json::json;
+use mahc::verify::Verification;
+use mahc::yaku::Yaku;
+use serde_json::{json, Value};
 
 /// riichi mahjong calculator tool
-#[derive(Parser, Debug)]
+#[derive(Parser, Debug, Clone)]
 #[command(version, about, long_about = None)]
 pub struct Args {
     /// Hand tiles
@@ -67,41 +89,767 @@ pub struct Args {
     #[arg(long, default_value_t = false)]
     tenhou: bool,
 
+    /// no limit hands (aotenjou): yakuman are 13 han each and points have no upper limit
+    #[arg(long, default_value_t = false, conflicts_with_all = ["sanma", "kiriage"])]
+    aotenjou: bool,
+
+    /// three-player (sanma) game
+    #[arg(long, default_value_t = false, group = "nuki")]
+    sanma: bool,
+
+    /// split the missing player's share of a sanma tsumo (north bisection) instead of tsumo loss
+    #[arg(long, default_value_t = false, requires = "sanma")]
+    bisection: bool,
+
+    /// points each honba is worth in sanma (usually 200 or 1000)
+    #[arg(long, default_value_t = 200, requires = "sanma")]
+    sanma_honba: Points,
+
+    /// north tiles can be set aside as nukidora in a four-player game
+    #[arg(long, default_value_t = false, group = "nuki")]
+    nukidora: bool,
+
+    /// the draw after a nukidora counts for rinshan kaihou, in sanma or with --nukidora
+    #[arg(long, default_value_t = false, requires = "nuki")]
+    nuki_rinshan: bool,
+
     /// honba count
     #[arg(short, long, default_value_t = 0)]
     ba: HonbaCounter,
 
+    /// round 4 han 30 fu and 3 han 60 fu up to mangan (kiriage mangan)
+    #[arg(long, default_value_t = false)]
+    kiriage: bool,
+
     /// calculator mode
     #[arg(short, long, default_value = None, value_delimiter = ' ', num_args = 2)]
     manual: Option<Vec<u32>>,
 
+    /// reverse lookup of a payment (ex: 3900, 2600all, 1300-2600)
+    #[arg(short, long, default_value = None)]
+    lookup: Option<String>,
+
+    /// check the payment claimed for the hand (ex: 7700, 2600all, 2000-3900)
+    #[arg(long, default_value = None, conflicts_with = "aotenjou")]
+    verify: Option<String>,
+
+    /// all-last requirements from the East, South, West and North scores
+    #[arg(short, long, default_value = None, value_delimiter = ' ', num_args = 4)]
+    need: Option<Vec<PlayerPoints>>,
+
+    /// riichi sticks on the table
+    #[arg(long, default_value_t = 0)]
+    sticks: RiichiCounter,
+
+    /// target placement for all-last requirements
+    #[arg(long, default_value_t = 1)]
+    place: usize,
+
+    /// why each yaku and fu applied, and why common yaku did not
+    #[arg(long, default_value_t = false, conflicts_with_all = ["json", "aotenjou", "verify"])]
+    explain: bool,
+
+    /// draw the hand and dora indicators instead of scoring it (svg or ascii)
+    #[arg(long, default_value = None, conflicts_with = "json")]
+    render: Option<String>,
+
     /// file input
     #[arg(short, long, default_value = None)]
     file: Option<String>,
 
     /// stdout as json
-    #[arg(long, default_value_t = false)]
+    #[arg(long, global = true, default_value_t = false)]
     json: bool,
 
+    /// language of yaku, fu and errors (romaji, en, ja or zh)
+    #[arg(long, global = true, default_value = "romaji")]
+    lang: String,
+
+    /// json without a schema version, in the 1.x format and the old verify and aotenjou layout
+    #[arg(long, global = true, default_value_t = false, requires = "json")]
+    legacy_json: bool,
+
     /// file output
     #[arg(short, default_value = "mahc.txt")]
     output: Option<String>,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum Command {
+    /// safety of each tile in hand against a riichi player
+    Safety(SafetyArgs),
+    /// draws and discards that raise the value of a tenpai or iishanten hand
+    Advise(AdviseArgs),
+    /// win rate and expected value of a hand drawing from the rest of the wall
+    Simulate(SimulateArgs),
+    /// change in points of every seat after a win, for the score sheet
+    Settle(SettleArgs),
+    /// keep score of a whole game, hand by hand, in a local state file
+    Game(GameArgs),
+    /// placement results with uma and oka from final scores or a finished game
+    Placement(PlacementArgs),
+    /// dealer and non-dealer score tables under the active rules (--kiriage, --ba, --sanma)
+    Table(TableArgs),
+    /// score hands one after another, keeping the round context between them
+    Repl,
+    /// local HTTP server scoring hands in the same JSON as --json
+    Serve(ServeArgs),
+    /// referee for mjai events from stdin or a log, scoring every hora
+    Mjai(MjaiArgs),
+}
+
+#[derive(ClapArgs, Debug, Clone)]
+pub struct MjaiArgs {
+    /// mjai log to read instead of stdin, one event per line
+    #[arg(long, default_value = None)]
+    log: Option<String>,
+}
+
+#[derive(ClapArgs, Debug, Clone)]
+pub struct ServeArgs {
+    /// port to listen on, on localhost only
+    #[arg(long, default_value_t = 8080)]
+    port: u16,
+
+    /// origin of web pages allowed to call the API (ex: http://localhost:3000, or * for any)
+    #[arg(long, default_value = None)]
+    allow_origin: Option<String>,
+}
+
+#[derive(ClapArgs, Debug, Clone)]
+pub struct TableArgs {
+    /// output format: md, csv, html or text
+    #[arg(long, default_value = "text")]
+    format: String,
+
+    /// dealer table only
+    #[arg(long, default_value_t = false, conflicts_with = "non_dealer")]
+    dealer: bool,
+
+    /// non-dealer table only
+    #[arg(long, default_value_t = false)]
+    non_dealer: bool,
+}
+
+#[derive(ClapArgs, Debug, Clone)]
+pub struct PlacementArgs {
+    /// final scores in seating order, starting from the first dealer
+    #[arg(
+        long,
+        value_delimiter = ' ',
+        num_args = 4,
+        conflicts_with = "state",
+        required_unless_present = "state"
+    )]
+    scores: Option<Vec<PlayerPoints>>,
+
+    /// finished game state file to take the final scores from
+    #[arg(long, default_value = None)]
+    state: Option<String>,
+
+    /// uma in thousands, from first to last place
+    #[arg(long, default_value = "15/5/-5/-15")]
+    uma: String,
+
+    /// points each player started with (default: 25000, or the game's starting points)
+    #[arg(long, default_value = None)]
+    start: Option<PlayerPoints>,
+
+    /// return point taken off every final score
+    #[arg(long = "return", default_value_t = 30_000)]
+    return_points: PlayerPoints,
+
+    /// split the uma and oka between tied players instead of breaking ties by seat order
+    #[arg(long, default_value_t = false)]
+    split: bool,
+}
+
+#[derive(ClapArgs, Debug, Clone)]
+pub struct GameArgs {
+    /// file the game is kept in between hands
+    #[arg(long, default_value = "mahc-game.json")]
+    state: String,
+
+    #[command(subcommand)]
+    action: GameAction,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum GameAction {
+    /// start a new game, replacing the state file
+    New {
+        /// play the East round only
+        #[arg(long, default_value_t = false)]
+        tonpuusen: bool,
+
+        /// points each player starts with
+        #[arg(long, default_value_t = 25_000)]
+        points: PlayerPoints,
+
+        /// points needed to end the game after the last round
+        #[arg(long, default_value_t = 30_000)]
+        target: PlayerPoints,
+    },
+    /// score a win
+    Win {
+        /// Seat wind of the winner
+        #[arg(long)]
+        winner: String,
+
+        /// Seat wind of the discarder, leave out for tsumo
+        #[arg(long, default_value = None)]
+        from: Option<String>,
+
+        /// han and fu of the hand (ex: 3 30), leave out to score the hand given with --tiles
+        #[arg(long, value_delimiter = ' ', num_args = 2)]
+        hand: Option<Vec<u32>>,
+
+        /// Seat winds that declared riichi this hand
+        #[arg(long, value_delimiter = ' ', num_args = 1..)]
+        riichi: Option<Vec<String>>,
+    },
+    /// score an exhaustive draw
+    Draw {
+        /// Seat winds that are tenpai
+        #[arg(long, value_delimiter = ' ', num_args = 1..)]
+        tenpai: Option<Vec<String>>,
+
+        /// Seat winds that declared riichi this hand
+        #[arg(long, value_delimiter = ' ', num_args = 1..)]
+        riichi: Option<Vec<String>>,
+    },
+    /// score an abortive draw found in the events of the hand
+    Abort {
+        /// events in order as seat:event[:tiles] (ex: Ew:discard:Nw Sw:riichi Ww:kan Nw:ron
+        /// Ew:kyuushu:19m,19p,19s,ESWw,234p,55p)
+        #[arg(long, value_delimiter = ' ', num_args = 1..)]
+        events: Vec<String>,
+
+        /// three players winning off the same discard is not a draw
+        #[arg(long, default_value_t = false)]
+        triple_ron: bool,
+    },
+    /// score a chombo as a reverse mangan
+    Chombo {
+        /// Seat wind of the offender
+        #[arg(long)]
+        seat: String,
+    },
+    /// go back to before the last hand
+    Undo,
+    /// show the table
+    Show,
+}
+
+#[derive(ClapArgs, Debug, Clone)]
+pub struct SettleArgs {
+    /// Seat wind of the winner, or of every winner off the same discard
+    #[arg(long, value_delimiter = ' ', num_args = 1..=3)]
+    winner: Vec<String>,
+
+    /// Seat wind of the discarder, leave out for tsumo
+    #[arg(long, default_value = None)]
+    from: Option<String>,
+
+    /// han and fu of the hand, for each winner in order (ex: 3 30 2 40)
+    #[arg(long, value_delimiter = ' ', num_args = 2..=6)]
+    hand: Vec<u32>,
+
+    /// only the winner nearest to the discarder wins
+    #[arg(long, default_value_t = false)]
+    atamahane: bool,
+
+    /// every winner is paid the honba and the riichi sticks are split, instead of all going to
+    /// the nearest winner
+    #[arg(long, default_value_t = false)]
+    split_bonus: bool,
+
+    /// three players can win off the same discard instead of an abortive draw
+    #[arg(long, default_value_t = false)]
+    triple_ron: bool,
+}
+
+#[derive(ClapArgs, Debug, Clone)]
+pub struct AdviseArgs {
+    /// Hand tiles, with calls as open groups or kans
+    #[arg(long, value_delimiter = ' ', num_args = 1..)]
+    tiles: Vec<String>,
+}
+
+#[derive(ClapArgs, Debug, Clone)]
+pub struct SimulateArgs {
+    /// Hand tiles, with calls as open groups or kans
+    #[arg(long, value_delimiter = ' ', num_args = 1..)]
+    tiles: Vec<String>,
+
+    /// Other visible tiles (discards, called melds); dora indicators are counted already
+    #[arg(long, value_delimiter = ' ', num_args = 1..)]
+    visible: Option<Vec<String>>,
+
+    /// number of tiles we draw before the wall runs out
+    #[arg(long, default_value_t = 18)]
+    draws: usize,
+
+    /// number of simulated hands
+    #[arg(long, default_value_t = 1000)]
+    trials: u32,
+
+    /// random seed, the same seed always gives the same result
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+}
+
+#[derive(ClapArgs, Debug, Clone)]
+pub struct SafetyArgs {
+    /// Hand tiles
+    #[arg(long, value_delimiter = ' ', num_args = 1..)]
+    tiles: Vec<String>,
+
+    /// Discard pond of the riichi player
+    #[arg(long, value_delimiter = ' ', num_args = 1..)]
+    pond: Vec<String>,
+
+    /// turn of the riichi declaration, counted from 1 in the pond
+    #[arg(long)]
+    riichi_turn: usize,
+
+    /// Discard pond of the player after the riichi player
+    #[arg(long, value_delimiter = ' ', num_args = 1..)]
+    shimocha: Option<Vec<String>>,
+
+    /// Discard pond of the player across from the riichi player
+    #[arg(long, value_delimiter = ' ', num_args = 1..)]
+    toimen: Option<Vec<String>>,
+
+    /// Discard pond of the player before the riichi player
+    #[arg(long, value_delimiter = ' ', num_args = 1..)]
+    kamicha: Option<Vec<String>>,
+
+    /// Other visible tiles (called melds, dora indicators)
+    #[arg(long, value_delimiter = ' ', num_args = 1..)]
+    visible: Option<Vec<String>>,
 }
 
 pub fn parse_calculator(args: &Args) -> Result<String, HandErr> {
     let honba = args.ba;
     let han = args.manual.as_ref().unwrap()[0];
     let fu = args.manual.as_ref().unwrap()[1].into();
-    let payment = calc::calculate(han, fu)?;
+    if args.aotenjou {
+        let payment = AotenjouPayment::from_han_and_fu(han, fu)?;
+        return if args.legacy_json {
+            Ok(json_legacy_aotenjou_calc_out(&payment, honba, han, fu))
+        } else if args.json {
+            Ok(json_aotenjou_calc_out(&AotenjouCalcReport::new(
+                &payment, han, fu, honba,
+            )))
+        } else {
+            Ok(default_aotenjou_calc_out(&payment, honba, han, fu))
+        };
+    }
+    let mut payment = args_payment(args, han, fu)?;
+    if let Some(sanma) = parse_sanma(args) {
+        payment.set_sanma(sanma.tsumo, sanma.tsumibou);
+    }
 
-    if args.json {
-        Ok(json_calc_out(&payment, honba, han, fu))
+    if args.legacy_json {
+        Ok(json_legacy_calc_out(&payment, honba, han, fu))
+    } else if args.json {
+        Ok(json_calc_out(&CalcReport::new(&payment, han, fu, honba)))
     } else {
         Ok(default_calc_out(&payment, honba, han, fu))
     }
 }
 
-pub fn parse_hand(args: &Args) -> Result<String, HandErr> {
+pub fn parse_lookup(args: &Args) -> Result<String, HandErr> {
+    let honba = args.ba;
+    let claim: ClaimedPayment = args.lookup.clone().unwrap().try_into()?;
+    let lookup = calc::reverse_lookup(&claim, &parse_table_rules(args));
+
+    if args.json {
+        Ok(json_lookup_out(
+            &lookup,
+            args.lookup.as_ref().unwrap(),
+            honba,
+        ))
+    } else {
+        Ok(default_lookup_out(
+            &lookup,
+            args.lookup.as_ref().unwrap(),
+            honba,
+        ))
+    }
+}
+
+pub fn parse_all_last(args: &Args) -> Result<String, HandErr> {
+    let scores = args.need.as_ref().unwrap();
+    let table = AllLast::new(
+        [scores[0], scores[1], scores[2], scores[3]],
+        args.ba,
+        args.sticks,
+    );
+    let seat: Seat = args.seat.clone().try_into()?;
+    let requirements = table.requirements(seat, args.place);
+
+    if args.json {
+        Ok(json_all_last_out(&requirements, seat, args.place))
+    } else {
+        Ok(default_all_last_out(&requirements, seat, args.place))
+    }
+}
+
+pub fn parse_command(args: &Args, command: &Command) -> Result<String, HandErr> {
+    match command {
+        Command::Safety(safety) => parse_safety(args, safety),
+        Command::Advise(advise) => parse_advise(args, advise),
+        Command::Simulate(simulate) => parse_simulate(args, simulate),
+        Command::Settle(settle) => parse_settle(args, settle),
+        Command::Game(game) => parse_game(args, game),
+        Command::Placement(placement) => parse_placement(args, placement),
+        Command::Table(table) => parse_table(args, table),
+        Command::Repl => {
+            parse_repl(args);
+            Ok(String::new())
+        }
+        Command::Serve(serve) => {
+            parse_serve(serve);
+            Ok(String::new())
+        }
+        Command::Mjai(mjai) => {
+            parse_mjai(mjai);
+            Ok(String::new())
+        }
+    }
+}
+
+pub fn parse_safety(args: &Args, safety: &SafetyArgs) -> Result<String, HandErr> {
+    let to_tiles = |tiles: &[String]| -> Result<Vec<TileGroup>, HandErr> {
+        tiles.iter().map(|tile| tile.clone().try_into()).collect()
+    };
+    let hand = to_tiles(&safety.tiles)?;
+    let visible = to_tiles(safety.visible.as_deref().unwrap_or_default())?;
+    let [shimocha, toimen, kamicha] = [&safety.shimocha, &safety.toimen, &safety.kamicha]
+        .map(|pond| to_tiles(pond.as_deref().unwrap_or_default()));
+    let opponent = RiichiOpponent::new(to_tiles(&safety.pond)?, safety.riichi_turn)?
+        .with_other_ponds([shimocha?, toimen?, kamicha?]);
+    let tiles = opponent.analyse(&hand, &visible);
+
+    if args.json {
+        Ok(json_safety_out(&tiles))
+    } else {
+        Ok(default_safety_out(&tiles))
+    }
+}
+
+pub fn parse_advise(args: &Args, advise: &AdviseArgs) -> Result<String, HandErr> {
+    let (concealed, melds) = split_hand(&advise.tiles)?;
+    let context = WinContext {
+        seat: args.seat.clone().try_into()?,
+        prev: args.prev.clone().try_into()?,
+        dora: parse_doras(args),
+        tsumo: args.tsumo,
+        riichi: args.riichi,
+        honba: args.ba,
+    };
+    let advice = advisor::advise(&TileCounts::new(&concealed), &melds, &context)?;
+    let lang = Lang::try_from(args.lang.clone())?;
+
+    if args.json {
+        Ok(json_advice_out(&advice, &context))
+    } else {
+        Ok(default_advice_out(&advice, &context, lang))
+    }
+}
+
+pub fn parse_simulate(args: &Args, simulate: &SimulateArgs) -> Result<String, HandErr> {
+    let (concealed, melds) = split_hand(&simulate.tiles)?;
+    let visible = simulate
+        .visible
+        .iter()
+        .flatten()
+        .map(|tile| tile.clone().try_into())
+        .collect::<Result<Vec<TileGroup>, HandErr>>()?;
+    let context = WinContext {
+        seat: args.seat.clone().try_into()?,
+        prev: args.prev.clone().try_into()?,
+        dora: parse_doras(args),
+        tsumo: true,
+        riichi: args.riichi,
+        honba: args.ba,
+    };
+    let mut simulation = Simulation::new(&TileCounts::new(&concealed), &melds, &visible, &context)?;
+    let estimate = simulation.run(simulate.trials, simulate.draws, simulate.seed)?;
+
+    if args.json {
+        Ok(json_simulate_out(&estimate, simulate.seed))
+    } else {
+        Ok(default_simulate_out(&estimate, simulate.seed))
+    }
+}
+
+pub fn parse_settle(args: &Args, settle: &SettleArgs) -> Result<String, HandErr> {
+    if settle.hand.len() != settle.winner.len() * 2 {
+        return Err(HandErr::InvalidWinners);
+    }
+    let winners = settle
+        .winner
+        .iter()
+        .zip(settle.hand.chunks(2))
+        .map(|(winner, hand)| -> Result<(Seat, Payment), HandErr> {
+            Ok((
+                winner.clone().try_into()?,
+                args_payment(args, hand[0], hand[1].into())?,
+            ))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let deltas = match (&settle.from, winners.as_slice()) {
+        (None, [(winner, payment)]) => {
+            Settlement::new(*winner, WinMethod::Tsumo, args.ba, args.sticks)?.deltas(payment)
+        }
+        (None, _) => return Err(HandErr::InvalidWinners),
+        (Some(from), _) => {
+            let rules = MultiRonRules {
+                bonus: if settle.split_bonus {
+                    MultiRonBonus::All
+                } else {
+                    MultiRonBonus::Nearest
+                },
+                triple_ron_draw: !settle.triple_ron,
+                atamahane: settle.atamahane,
+            };
+            MultiRon::new(
+                from.clone().try_into()?,
+                winners,
+                args.ba,
+                args.sticks,
+                rules,
+            )?
+            .deltas()
+        }
+    };
+
+    if args.json {
+        Ok(json_settle_out(&deltas))
+    } else {
+        Ok(default_settle_out(&deltas))
+    }
+}
+
+/// The wind tile of a seat, as the hand flags take it.
+fn wind_tile(seat: Seat) -> String {
+    match seat {
+        Seat::East => "Ew",
+        Seat::South => "Sw",
+        Seat::West => "Ww",
+        Seat::North => "Nw",
+    }
+    .to_string()
+}
+
+pub fn parse_game(args: &Args, game_args: &GameArgs) -> Result<String, HandErr> {
+    let to_seats = |seats: &Option<Vec<String>>| -> Result<Vec<Seat>, HandErr> {
+        seats
+            .iter()
+            .flatten()
+            .map(|seat| seat.clone().try_into())
+            .collect()
+    };
+
+    let game = if let GameAction::New {
+        tonpuusen,
+        points,
+        target,
+    } = &game_args.action
+    {
+        Game::new(Rules {
+            length: if *tonpuusen {
+                GameLength::Tonpuusen
+            } else {
+                GameLength::Hanchan
+            },
+            starting_points: *points,
+            target: *target,
+        })
+    } else {
+        let state = fs::read_to_string(&game_args.state).map_err(|_| HandErr::GameStateFile)?;
+        let mut game: Game = state.try_into()?;
+
+        match &game_args.action {
+            GameAction::Win {
+                winner,
+                from,
+                hand,
+                riichi,
+            } => {
+                let winner: Seat = winner.clone().try_into()?;
+                let method = match from {
+                    Some(from) => WinMethod::Ron(from.clone().try_into()?),
+                    None => WinMethod::Tsumo,
+                };
+                let riichi = to_seats(riichi)?;
+                match hand {
+                    Some(hand) => {
+                        let payment = args_payment(args, hand[0], hand[1].into())?;
+                        game.agari(winner, method, &payment, &riichi)?;
+                    }
+                    None => {
+                        // the winds, tsumo, riichi and honba of the hand come from the game
+                        let table = game.table();
+                        let hand_args = Args {
+                            seat: wind_tile(winner),
+                            prev: wind_tile(table.round_wind),
+                            tsumo: method == WinMethod::Tsumo,
+                            riichi: riichi.contains(&winner) && !args.doubleriichi,
+                            ba: table.honba,
+                            ..args.clone()
+                        };
+                        let hand = args_hand(&hand_args)?;
+                        let score = args_score(&hand_args, hand, parse_doras(&hand_args))?;
+                        game.agari_score(winner, method, &score, &riichi)?;
+                    }
+                }
+            }
+            GameAction::Draw { tenpai, riichi } => {
+                game.draw(&to_seats(tenpai)?, &to_seats(riichi)?)?
+            }
+            GameAction::Abort { events, triple_ron } => {
+                let events: Vec<HandEvent> = events
+                    .iter()
+                    .map(|event| event.clone().try_into())
+                    .collect::<Result<_, _>>()?;
+                let rules = MultiRonRules {
+                    triple_ron_draw: !triple_ron,
+                    ..MultiRonRules::default()
+                };
+                game.abort(&events, &rules)?;
+            }
+            GameAction::Chombo { seat } => game.chombo(seat.clone().try_into()?)?,
+            GameAction::Undo => game.undo()?,
+            GameAction::New { .. } | GameAction::Show => {}
+        }
+        game
+    };
+
+    fs::write(&game_args.state, game.to_json()).map_err(|_| HandErr::GameStateFile)?;
+
+    if args.json {
+        Ok(json_game_out(game.table()))
+    } else {
+        Ok(default_game_out(game.table()))
+    }
+}
+
+pub fn parse_placement(args: &Args, placement: &PlacementArgs) -> Result<String, HandErr> {
+    let mut rules = PlacementRules {
+        return_points: placement.return_points,
+        uma: placement.uma.clone().try_into()?,
+        ties: if placement.split {
+            TieRule::Split
+        } else {
+            TieRule::SeatOrder
+        },
+        ..PlacementRules::default()
+    };
+
+    let scores = if let Some(scores) = &placement.scores {
+        [scores[0], scores[1], scores[2], scores[3]]
+    } else {
+        let state = fs::read_to_string(placement.state.as_ref().unwrap())
+            .map_err(|_| HandErr::GameStateFile)?;
+        let game: Game = state.try_into()?;
+        if !game.table().finished {
+            return Err(HandErr::GameNotOver);
+        }
+        rules.starting_points = game.rules().starting_points;
+        game.table().scores
+    };
+    if let Some(start) = placement.start {
+        rules.starting_points = start;
+    }
+
+    let results = placements(&scores, &rules);
+
+    if args.json {
+        Ok(json_placement_out(&results))
+    } else {
+        Ok(default_placement_out(&results))
+    }
+}
+
+pub fn parse_table(args: &Args, table_args: &TableArgs) -> Result<String, HandErr> {
+    let format: TableFormat = table_args.format.clone().try_into()?;
+    let rules = parse_table_rules(args);
+
+    let mut tables = vec![];
+    if !table_args.non_dealer {
+        tables.push(ScoreTable::new(&rules, true)?);
+    }
+    if !table_args.dealer {
+        tables.push(ScoreTable::new(&rules, false)?);
+    }
+
+    if args.json {
+        Ok(json_table_out(&tables))
+    } else {
+        Ok(default_table_out(&tables, format))
+    }
+}
+
+/// The rules payments are built under, for score tables and reverse lookups.
+fn parse_table_rules(args: &Args) -> TableRules {
+    TableRules {
+        kiriage: args.kiriage,
+        honba: args.ba,
+        sanma: parse_sanma(args),
+    }
+}
+
+/// Pay out a han and fu under the rules of the flags, the same for every command.
+fn args_payment(args: &Args, han: HanValue, fu: FuValue) -> Result<Payment, HandErr> {
+    if parse_table_rules(args).kiriage {
+        calc::calculate_kiriage(han, fu)
+    } else {
+        calc::calculate(han, fu)
+    }
+}
+
+fn parse_sanma(args: &Args) -> Option<SanmaRules> {
+    if !args.sanma {
+        return None;
+    }
+
+    Some(SanmaRules {
+        tsumo: if args.bisection {
+            SanmaTsumo::NorthBisection
+        } else {
+            SanmaTsumo::TsumoLoss
+        },
+        tsumibou: args.sanma_honba,
+        nuki_rinshan: args.nuki_rinshan,
+    })
+}
+
+fn parse_yonma(args: &Args) -> YonmaRules {
+    YonmaRules {
+        nukidora: args.nukidora,
+        nuki_rinshan: args.nuki_rinshan,
+    }
+}
+
+fn parse_doras(args: &Args) -> Option<Vec<TileGroup>> {
+    args.dora.clone().map(|dora_tiles| {
+        dora_tiles
+            .into_iter()
+            .filter_map(|tile| tile.try_into().ok())
+            .collect()
+    })
+}
+
+/// Check the flags of a hand and build it from its tiles.
+fn args_hand(args: &Args) -> Result<Hand, HandErr> {
     if args.tiles.is_none() {
         return Err(HandErr::NoHandTiles);
     }
@@ -129,86 +877,305 @@ pub fn parse_hand(args: &Args) -> Result<String, HandErr> {
     if args.doubleriichi && args.haitei && args.chankan {
         return Err(HandErr::DoubleRiichiHaiteiChankan);
     }
-    let hand = Hand::new_from_strings(
+    Hand::new_from_strings(
         args.tiles.clone().unwrap(),
         args.win.clone().unwrap(),
         args.prev.clone(),
         args.seat.clone(),
-    )?;
-    let doras: Option<Vec<TileGroup>> = args.dora.clone().map(|dora_tiles| {
-        dora_tiles
-            .into_iter()
-            .filter_map(|tile| tile.try_into().ok())
-            .collect()
-    });
-    let score = calc::get_hand_score(
-        hand,
-        doras,
-        args.tsumo,
-        args.riichi,
-        args.doubleriichi,
-        args.ippatsu,
-        args.haitei,
-        args.rinshan,
-        args.chankan,
-        args.tenhou,
-        args.ba,
-    )?;
+    )
+}
+
+/// Score a hand under the rules of the flags, leaving out aotenjou.
+fn args_score(args: &Args, hand: Hand, doras: Option<Vec<TileGroup>>) -> Result<Score, HandErr> {
+    let mut score = match parse_sanma(args) {
+        Some(sanma) => sanma.get_hand_score(
+            hand,
+            doras,
+            args.tsumo,
+            args.riichi,
+            args.doubleriichi,
+            args.ippatsu,
+            args.haitei,
+            args.rinshan,
+            args.chankan,
+            args.tenhou,
+            args.ba,
+        )?,
+        None => parse_yonma(args).get_hand_score(
+            hand,
+            doras,
+            args.tsumo,
+            args.riichi,
+            args.doubleriichi,
+            args.ippatsu,
+            args.haitei,
+            args.rinshan,
+            args.chankan,
+            args.tenhou,
+            args.ba,
+        )?,
+    };
+    if args.kiriage {
+        calc::apply_kiriage(&mut score)?;
+    }
+    Ok(score)
+}
+
+pub fn parse_hand(args: &Args) -> Result<String, HandErr> {
+    let hand = args_hand(args)?;
+    let doras = parse_doras(args);
+    if let Some(format) = &args.render {
+        let format = RenderFormat::try_from(format.clone())?;
+        let doras = doras.unwrap_or_default();
+        return Ok(format!("\n{}", render::render(&hand, &doras, format)));
+    }
+    let lang = Lang::try_from(args.lang.clone())?;
+    if args.aotenjou {
+        let score = aotenjou::get_hand_score(
+            &parse_yonma(args),
+            hand.clone(),
+            doras,
+            args.tsumo,
+            args.riichi,
+            args.doubleriichi,
+            args.ippatsu,
+            args.haitei,
+            args.rinshan,
+            args.chankan,
+            args.tenhou,
+            args.ba,
+        )?;
+        return if args.legacy_json {
+            Ok(json_legacy_aotenjou_hand_out(&score))
+        } else if args.json {
+            Ok(json_aotenjou_hand_out(&AotenjouHandReport::new(
+                &hand, &score,
+            )))
+        } else {
+            Ok(default_aotenjou_hand_out(&score, lang))
+        };
+    }
+    let score = args_score(args, hand.clone(), doras)?;
 
     //TODO VALIDATION (i dont care enough yet)
 
-    let printout = if args.json {
-        json_hand_out(&score)
+    if let Some(claim) = &args.verify {
+        let dealer = Seat::try_from(args.seat.clone())?.is_dealer();
+        let verification = Verification::new(
+            &score,
+            dealer,
+            args.tsumo,
+            claim.clone().try_into()?,
+            &parse_table_rules(args),
+        )?;
+        return if args.legacy_json {
+            Ok(json_legacy_verify_out(&verification, &score))
+        } else if args.json {
+            Ok(json_verify_out(&VerifyReport::new(
+                &verification,
+                &hand,
+                &score,
+            )))
+        } else {
+            Ok(default_verify_out(&verification, &score, lang))
+        };
+    }
+
+    let printout = if args.legacy_json {
+        json_legacy_hand_out(&score)
+    } else if args.json {
+        json_hand_out(&HandReport::new(&hand, &score))
+    } else if args.explain {
+        let explanation = Explanation::new(&hand, &score, args.tsumo);
+        default_hand_out(&score, lang) + &default_explain_out(&explanation, score.is_open(), lang)
     } else {
-        default_hand_out(&score)
+        default_hand_out(&score, lang)
     };
     Ok(printout)
 }
 
-pub fn json_calc_out(payment: &Payment, honba: HonbaCounter, han: HanValue, fu: FuValue) -> String {
-    let out = json!({
-    "han" : han,
-    "fu" : fu,
-    "honba" : honba,
-        "scores" : {
-            "dealer" : {
-                "ron" : payment.dealer_ron(honba),
-                "tsumo" : payment.dealer_tsumo(honba)
-            },
-            "non-dealer" : {
-                "ron" : payment.non_dealer_ron(honba),
-                "tsumo" : {
-                    "dealer" : payment.non_dealer_tsumo_to_dealer(honba),
-                    "non-dealer" : payment.non_dealer_tsumo_to_non_dealer(honba)
-                }
-            }
-        }
-    });
-    out.to_string()
+/// The han and fu of the hand, or its limit.
+fn hand_value(score: &Score) -> String {
+    if score.yaku().iter().any(Yaku::is_yakuman) {
+        return "Yakuman".to_string();
+    }
+    let value = format!("{} Han/ {} Fu", score.han(), score.fu_score());
+    match LimitHands::get_limit_hand(score.han(), score.fu_score()) {
+        Some(limit) => format!("{limit} ({value})"),
+        None => value,
+    }
 }
 
-pub fn default_calc_out(
-    payment: &Payment,
-    honba: HonbaCounter,
-    han: HanValue,
-    fu: FuValue,
-) -> String {
-    let honba_str = if honba != 0 {
-        format!("/ {honba} Honba")
-    } else {
-        "".to_string()
+/// How the han and fu a claim is for differ from the hand.
+fn value_difference(value: &HandValue, score: &Score) -> String {
+    let HandValue::HanFu(han, fu) = *value else {
+        return "".to_string();
     };
+    if score.yaku().iter().any(Yaku::is_yakuman) {
+        return "".to_string();
+    }
 
-    format!(
-        "\n{han} Han\
-        / {fu} Fu\
-        {honba}\
-        \nDealer: {dealer_ron} ({dealer_each})\
-        \nnon-dealer: {non_dealer_ron} ({non_dealer_payment}/{dealer_payment})",
-        han = han,
-        fu = fu,
-        honba = honba_str,
-        dealer_ron = payment.dealer_ron(honba),
+    let mut differences = vec![];
+    let han_difference = han as i64 - score.han() as i64;
+    if han_difference != 0 {
+        differences.push(format!("{han_difference:+} Han"));
+    }
+    let fu_difference = fu as i64 - score.fu_score() as i64;
+    if fu_difference != 0 {
+        differences.push(format!("{fu_difference:+} Fu"));
+    }
+    if differences.is_empty() {
+        return "".to_string();
+    }
+    format!(" ({})", differences.join(", "))
+}
+
+pub fn json_verify_out(report: &VerifyReport) -> String {
+    serde_json::to_string(report).unwrap_or_default()
+}
+
+pub fn json_legacy_verify_out(verification: &Verification, score: &Score) -> String {
+    let mut out = json_score(score);
+    let verify = json!({
+        "claim" : verification.claim().to_string(),
+        "actual" : verification.actual().to_string(),
+        "correct" : verification.is_correct(),
+        "components" : verification.components().iter().map(|component| json!({
+            "payer" : component.payer,
+            "claimed" : component.claimed,
+            "actual" : component.actual,
+            "difference" : component.difference()
+        })).collect::<Vec<_>>(),
+        "claimedValues" : verification.claimed_values().iter().map(|value| value.to_string()).collect::<Vec<String>>()
+    });
+    if let (Some(out), Value::Object(verify)) = (out.as_object_mut(), verify) {
+        out.extend(verify);
+    }
+    out.to_string()
+}
+
+pub fn default_verify_out(verification: &Verification, score: &Score, lang: Lang) -> String {
+    let claim = verification.claim();
+    if verification.is_correct() {
+        return format!("\nClaim: {claim} is correct\nHand: {}", hand_value(score));
+    }
+
+    let mut out = format!(
+        "\nClaim: {claim} is incorrect, the hand is worth {}",
+        verification.actual()
+    );
+    for component in verification.components() {
+        out.push_str(&format!(
+            "\n{}: {} claimed, {} actual ({:+})",
+            component.payer,
+            component.claimed,
+            component.actual,
+            component.difference()
+        ));
+    }
+
+    out.push_str(&format!("\nHand: {}", hand_value(score)));
+    if verification.claimed_values().is_empty() {
+        out.push_str("\nClaim matches no hand");
+    } else {
+        out.push_str("\nClaim matches: ");
+        for value in verification.claimed_values() {
+            out.push_str(&format!("\n  {value}{}", value_difference(value, score)));
+        }
+    }
+
+    if score.dora_count() != 0 {
+        out.push_str(&format!("\nDora: {}", score.dora_count()));
+    }
+    out.push_str("\nYaku: ");
+    for yaku in score.yaku() {
+        out.push_str(&format!(
+            "\n  {}",
+            lang::yaku_label(yaku, score.is_open(), lang)
+        ));
+    }
+    out.push_str("\nFu: ");
+    for fu in score.fu() {
+        out.push_str(&format!("\n  {}", lang::fu_label(fu, lang)));
+    }
+
+    out
+}
+
+/// Points each player pays, for a win by the dealer or by a non-dealer.
+fn json_scores(payment: &Payment, honba: HonbaCounter) -> Value {
+    json!({
+        "dealer" : {
+            "ron" : payment.dealer_ron(honba),
+            "tsumo" : payment.dealer_tsumo(honba)
+        },
+        "non-dealer" : {
+            "ron" : payment.non_dealer_ron(honba),
+            "tsumo" : {
+                "dealer" : payment.non_dealer_tsumo_to_dealer(honba),
+                "non-dealer" : payment.non_dealer_tsumo_to_non_dealer(honba)
+            }
+        }
+    })
+}
+
+/// The serialized score, with the yaku and fu display strings alongside.
+fn json_score(score: &Score) -> Value {
+    let mut out = serde_json::to_value(score).unwrap_or_default();
+    out["fuString"] = json!(score
+        .fu()
+        .iter()
+        .map(|x| x.to_string())
+        .collect::<Vec<String>>());
+    out["yakuString"] = json!(score
+        .yaku()
+        .iter()
+        .map(|x| x.to_string(score.is_open()))
+        .collect::<Vec<String>>());
+    out
+}
+
+pub fn json_calc_out(report: &CalcReport) -> String {
+    serde_json::to_string(report).unwrap_or_default()
+}
+
+pub fn json_legacy_calc_out(
+    payment: &Payment,
+    honba: HonbaCounter,
+    han: HanValue,
+    fu: FuValue,
+) -> String {
+    let out = json!({
+        "han" : han,
+        "fu" : fu,
+        "honba" : honba,
+        "scores" : json_scores(payment, honba)
+    });
+    out.to_string()
+}
+
+pub fn default_calc_out(
+    payment: &Payment,
+    honba: HonbaCounter,
+    han: HanValue,
+    fu: FuValue,
+) -> String {
+    let honba_str = if honba != 0 {
+        format!("/ {honba} Honba")
+    } else {
+        "".to_string()
+    };
+
+    format!(
+        "\n{han} Han\
+        / {fu} Fu\
+        {honba}\
+        \nDealer: {dealer_ron} ({dealer_each})\
+        \nnon-dealer: {non_dealer_ron} ({non_dealer_payment}/{dealer_payment})",
+        han = han,
+        fu = fu,
+        honba = honba_str,
+        dealer_ron = payment.dealer_ron(honba),
         dealer_each = payment.dealer_tsumo(honba),
         non_dealer_ron = payment.non_dealer_ron(honba),
         non_dealer_payment = payment.non_dealer_tsumo_to_non_dealer(honba),
@@ -216,31 +1183,314 @@ pub fn default_calc_out(
     )
 }
 
-pub fn json_hand_out(score: &Score) -> String {
+pub fn json_lookup_out(lookup: &ReverseLookup, payment: &str, honba: HonbaCounter) -> String {
+    let to_json = |values: &[HandValue]| {
+        values
+            .iter()
+            .map(|value| match value {
+                HandValue::HanFu(han, fu) => json!({ "han" : han, "fu" : fu }),
+                HandValue::Limit(limit) => json!({ "limit" : limit.to_string() }),
+            })
+            .collect::<Vec<_>>()
+    };
+    let out = json!({
+        "payment" : payment,
+        "honba" : honba,
+        "dealer" : to_json(&lookup.dealer),
+        "non-dealer" : to_json(&lookup.non_dealer)
+    });
+    out.to_string()
+}
+
+pub fn default_lookup_out(lookup: &ReverseLookup, payment: &str, honba: HonbaCounter) -> String {
+    let mut out = format!("\n{payment}");
+    if honba != 0 {
+        out.push_str(&format!("/ {honba} Honba"));
+    }
+
+    for (winner, values) in [
+        ("Dealer", &lookup.dealer),
+        ("Non-dealer", &lookup.non_dealer),
+    ] {
+        if values.is_empty() {
+            continue;
+        }
+        out.push_str(&format!("\n{winner}: "));
+        for value in values {
+            out.push_str(&format!("\n  {value}"));
+        }
+    }
+
+    if lookup.dealer.is_empty() && lookup.non_dealer.is_empty() {
+        out.push_str("\nNo matching hands");
+    }
+
+    out
+}
+
+pub fn json_all_last_out(requirements: &[Requirement], seat: Seat, place: usize) -> String {
+    let out = json!({
+        "seat" : seat.to_string(),
+        "place" : place,
+        "requirements" : requirements.iter().map(|requirement| json!({
+            "method" : requirement.method.to_string(),
+            "options" : requirement.options.iter().map(|x| x.to_string()).collect::<Vec<String>>()
+        })).collect::<Vec<_>>()
+    });
+    out.to_string()
+}
+
+pub fn default_all_last_out(requirements: &[Requirement], seat: Seat, place: usize) -> String {
+    let mut out = format!("\n{seat} needs placement {place}");
+    for requirement in requirements {
+        out.push_str(&format!("\n{}: ", requirement.method));
+        if requirement.options.is_empty() {
+            out.push_str("\n  Not possible");
+        }
+        for option in &requirement.options {
+            out.push_str(&format!("\n  {option}"));
+        }
+    }
+
+    out
+}
+
+pub fn json_safety_out(tiles: &[(TileGroup, Safety)]) -> String {
+    let out = json!({
+        "tiles" : tiles.iter().map(|(tile, safety)| json!({
+            "tile" : tile.to_string(),
+            "safety" : safety.to_string()
+        })).collect::<Vec<_>>()
+    });
+    out.to_string()
+}
+
+pub fn default_safety_out(tiles: &[(TileGroup, Safety)]) -> String {
+    let mut out = String::new();
+    for (tile, safety) in tiles {
+        out.push_str(&format!("\n{tile}: {safety}"));
+    }
+
+    out
+}
+
+pub fn json_advice_out(advice: &Advice, context: &WinContext) -> String {
+    let tenpai_json = |tenpai: &Tenpai| {
+        json!({
+            "waits" : tenpai.waits.iter().map(|x| x.to_string()).collect::<Vec<String>>(),
+            "points" : tenpai.best.as_ref().map(|score| context.points(score)),
+            "yakuString" : tenpai.best.as_ref().map(|score| score.yaku().iter().map(|x| x.to_string(score.is_open())).collect::<Vec<String>>())
+        })
+    };
+    let out = json!({
+        "tenpai" : advice.current.as_ref().map(tenpai_json),
+        "improvements" : advice.improvements.iter().map(|improvement| json!({
+            "draw" : improvement.draw.to_string(),
+            "discard" : improvement.discard.to_string(),
+            "gain" : improvement.gain,
+            "tenpai" : tenpai_json(&improvement.tenpai)
+        })).collect::<Vec<_>>()
+    });
+    out.to_string()
+}
+
+pub fn default_advice_out(advice: &Advice, context: &WinContext, lang: Lang) -> String {
+    let waits = |tenpai: &Tenpai| {
+        tenpai
+            .waits
+            .iter()
+            .map(|x| x.to_string())
+            .collect::<Vec<String>>()
+            .join(" ")
+    };
+
+    let mut out = match &advice.current {
+        Some(tenpai) => match &tenpai.best {
+            Some(score) => format!(
+                "\nTenpai: {} (waits {})",
+                context.points(score),
+                waits(tenpai)
+            ),
+            None => format!("\nTenpai: No Yaku (waits {})", waits(tenpai)),
+        },
+        None => "\nIishanten".to_string(),
+    };
+
+    for improvement in &advice.improvements {
+        // improvements always have a yaku
+        let score = improvement.tenpai.best.as_ref().unwrap();
+        out.push_str(&format!(
+            "\nDraw {}, discard {}: {} (+{})\n  Waits: {}\n  Yaku: {}",
+            improvement.draw,
+            improvement.discard,
+            context.points(score),
+            improvement.gain,
+            waits(&improvement.tenpai),
+            score
+                .yaku()
+                .iter()
+                .map(|x| lang::yaku_label(x, score.is_open(), lang))
+                .collect::<Vec<String>>()
+                .join(", ")
+        ));
+    }
+
+    out
+}
+
+pub fn json_simulate_out(estimate: &Estimate, seed: u64) -> String {
+    let out = json!({
+        "trials" : estimate.trials,
+        "seed" : seed,
+        "tenpaiRate" : estimate.tenpai_rate(),
+        "winRate" : estimate.win_rate(),
+        "expectedValue" : estimate.expected_value(),
+        "averageWin" : estimate.average_win()
+    });
+    out.to_string()
+}
+
+pub fn default_simulate_out(estimate: &Estimate, seed: u64) -> String {
+    format!(
+        "\nTrials: {} (seed {})\nTenpai: {:.1}%\nTsumo: {:.1}%\nExpected Value: {:.0}\nAverage Win: {:.0}",
+        estimate.trials,
+        seed,
+        estimate.tenpai_rate() * 100.0,
+        estimate.win_rate() * 100.0,
+        estimate.expected_value(),
+        estimate.average_win()
+    )
+}
+
+pub fn json_settle_out(deltas: &[PlayerPoints; 4]) -> String {
+    let out = json!({
+        "east" : deltas[0],
+        "south" : deltas[1],
+        "west" : deltas[2],
+        "north" : deltas[3]
+    });
+    out.to_string()
+}
+
+pub fn default_settle_out(deltas: &[PlayerPoints; 4]) -> String {
+    // tab separated so the rows can be pasted straight into a spreadsheet
+    let seats = Seat::ALL.map(|seat| seat.to_string()).join("\t");
+    let deltas = deltas.map(|delta| format!("{delta:+}")).join("\t");
+    format!("\n{seats}\n{deltas}")
+}
+
+pub fn json_game_out(table: &Table) -> String {
+    let out = json!({
+        "roundWind" : table.round_wind.to_string(),
+        "round" : table.dealer + 1,
+        "honba" : table.honba,
+        "riichiSticks" : table.riichi_sticks,
+        "scores" : table.scores,
+        "seats" : (0..4).map(|player| table.seat(player).to_string()).collect::<Vec<String>>(),
+        "standings" : table.standings().map(|player| player + 1),
+        "finished" : table.finished
+    });
+    out.to_string()
+}
+
+pub fn default_game_out(table: &Table) -> String {
+    if table.finished {
+        let mut out = "\nGame Over".to_string();
+        for (place, player) in table.standings().iter().enumerate() {
+            out.push_str(&format!(
+                "\n{}. Player {}: {}",
+                place + 1,
+                player + 1,
+                table.scores[*player]
+            ));
+        }
+        return out;
+    }
+
+    let mut out = format!(
+        "\n{} {}, {} Honba, {} Riichi Sticks",
+        table.round_wind,
+        table.dealer + 1,
+        table.honba,
+        table.riichi_sticks
+    );
+    for (player, score) in table.scores.iter().enumerate() {
+        out.push_str(&format!(
+            "\nPlayer {} ({}): {}",
+            player + 1,
+            table.seat(player),
+            score
+        ));
+    }
+    out
+}
+
+pub fn json_placement_out(results: &[Placement]) -> String {
+    let out = json!(results
+        .iter()
+        .map(|result| json!({
+            "player" : result.player + 1,
+            "place" : result.place,
+            "score" : result.score,
+            "result" : result.result
+        }))
+        .collect::<Vec<_>>());
+    out.to_string()
+}
+
+pub fn default_placement_out(results: &[Placement]) -> String {
+    let mut out = String::new();
+    for result in results {
+        out.push_str(&format!(
+            "\n{}. Player {}: {} ({:+.1})",
+            result.place,
+            result.player + 1,
+            result.score,
+            result.result
+        ));
+    }
+    out
+}
+
+pub fn json_table_out(tables: &[ScoreTable]) -> String {
+    let out = json!(tables
+        .iter()
+        .flat_map(ScoreTable::grids)
+        .map(|grid| json!({
+            "title" : grid.title,
+            "header" : grid.header,
+            "rows" : grid.rows
+        }))
+        .collect::<Vec<_>>());
+    out.to_string()
+}
+
+pub fn default_table_out(tables: &[ScoreTable], format: TableFormat) -> String {
+    let tables: Vec<String> = tables.iter().map(|table| table.render(format)).collect();
+    format!("\n{}", tables.join("\n\n"))
+}
+
+pub fn json_hand_out(report: &HandReport) -> String {
+    serde_json::to_string(report).unwrap_or_default()
+}
+
+pub fn json_legacy_hand_out(score: &Score) -> String {
     let out = json!({
         "han" : score.han(),
         "fu" : score.fu_score(),
         "honba" : score.honba(),
         "dora" : score.dora_count(),
         "fuString" : score.fu().iter().map(|x| x.to_string()).collect::<Vec<String>>(),
-        "yakuString" : score.yaku().iter().map(|x| x.to_string(score.is_open())).collect::<Vec<String>>(),
-        "scores" : {
-            "dealer" : {
-                "ron" : score.payment().dealer_ron(score.honba()),
-                "tsumo" : score.payment().dealer_tsumo(score.honba())
-            },
-            "non-dealer" : {
-                "ron" : score.payment().non_dealer_ron(score.honba()),
-                "tsumo" : {
-                "dealer" : score.payment().non_dealer_tsumo_to_dealer(score.honba()),
-                "non-dealer" : score.payment().non_dealer_tsumo_to_non_dealer(score.honba())
-                }
-            }
-        }
+        "yakuString" : score
+            .yaku()
+            .iter()
+            .map(|x| x.to_string(score.is_open()))
+            .collect::<Vec<String>>(),
+        "scores" : json_scores(score.payment(), score.honba())
     });
     out.to_string()
 }
-pub fn default_hand_out(score: &Score) -> String {
+pub fn default_hand_out(score: &Score, lang: Lang) -> String {
     let mut out: String = String::new();
     if !score.yaku()[0].is_yakuman() {
         if score.honba() != 0 {
@@ -258,37 +1508,589 @@ pub fn default_hand_out(score: &Score) -> String {
         }
     }
 
-    out.push_str(
-        format!(
-            "\nDealer: {} ({})\nNon-dealer: {} ({}/{})",
-            score.payment().dealer_ron(score.honba()),
-            score.payment().dealer_tsumo(score.honba()),
-            score.payment().non_dealer_ron(score.honba()),
-            score
-                .payment()
-                .non_dealer_tsumo_to_non_dealer(score.honba()),
-            score.payment().non_dealer_tsumo_to_dealer(score.honba())
-        )
-        .as_str(),
-    );
+    out.push_str(
+        format!(
+            "\nDealer: {} ({})\nNon-dealer: {} ({}/{})",
+            score.payment().dealer_ron(score.honba()),
+            score.payment().dealer_tsumo(score.honba()),
+            score.payment().non_dealer_ron(score.honba()),
+            score
+                .payment()
+                .non_dealer_tsumo_to_non_dealer(score.honba()),
+            score.payment().non_dealer_tsumo_to_dealer(score.honba())
+        )
+        .as_str(),
+    );
+
+    if !score.yaku()[0].is_yakuman() && score.dora_count() != 0 {
+        out.push_str(format!("\nDora: {}", score.dora_count()).as_str());
+    }
+
+    out.push_str("\nYaku: ");
+    for yaku in score.yaku() {
+        out.push_str(format!("\n  {}", lang::yaku_label(yaku, score.is_open(), lang)).as_str());
+    }
+
+    if !score.yaku()[0].is_yakuman() {
+        out.push_str("\nFu: ");
+        for fu in score.fu() {
+            out.push_str(format!("\n  {}", lang::fu_label(fu, lang)).as_str());
+        }
+    }
+
+    out
+}
+
+pub fn default_explain_out(explanation: &Explanation, is_open: bool, lang: Lang) -> String {
+    let mut out = String::from("\nWhy: ");
+    for yaku in &explanation.yaku {
+        out.push_str(&format!(
+            "\n  {} ({})",
+            lang::yaku_label(&yaku.item, is_open, lang),
+            yaku.reason
+        ));
+    }
+    for fu in &explanation.fu {
+        out.push_str(&format!(
+            "\n  {} ({})",
+            lang::fu_label(&fu.item, lang),
+            fu.reason
+        ));
+    }
+
+    if !explanation.denied.is_empty() {
+        out.push_str("\nNot awarded: ");
+        for yaku in &explanation.denied {
+            out.push_str(&format!(
+                "\n  {}: denied, {}",
+                lang::yaku_name(&yaku.item, lang),
+                yaku.reason
+            ));
+        }
+    }
+
+    out
+}
+
+/// Points are given as strings, as they can be too large for JSON numbers.
+fn json_aotenjou_scores(payment: &AotenjouPayment, honba: HonbaCounter) -> Value {
+    json!({
+        "dealer" : {
+            "ron" : payment.dealer_ron(honba).to_string(),
+            "tsumo" : payment.dealer_tsumo(honba).to_string()
+        },
+        "non-dealer" : {
+            "ron" : payment.non_dealer_ron(honba).to_string(),
+            "tsumo" : {
+                "dealer" : payment.non_dealer_tsumo_to_dealer(honba).to_string(),
+                "non-dealer" : payment.non_dealer_tsumo_to_non_dealer(honba).to_string()
+            }
+        }
+    })
+}
+
+pub fn json_aotenjou_calc_out(report: &AotenjouCalcReport) -> String {
+    serde_json::to_string(report).unwrap_or_default()
+}
+
+pub fn json_legacy_aotenjou_calc_out(
+    payment: &AotenjouPayment,
+    honba: HonbaCounter,
+    han: HanValue,
+    fu: FuValue,
+) -> String {
+    let out = json!({
+        "han" : han,
+        "fu" : fu,
+        "honba" : honba,
+        "scores" : json_aotenjou_scores(payment, honba)
+    });
+    out.to_string()
+}
+
+pub fn default_aotenjou_calc_out(
+    payment: &AotenjouPayment,
+    honba: HonbaCounter,
+    han: HanValue,
+    fu: FuValue,
+) -> String {
+    let honba_str = if honba != 0 {
+        format!("/ {honba} Honba")
+    } else {
+        "".to_string()
+    };
+
+    format!(
+        "\n{han} Han/ {fu} Fu{honba_str}\nDealer: {} ({})\nNon-dealer: {} ({}/{})",
+        payment.dealer_ron(honba),
+        payment.dealer_tsumo(honba),
+        payment.non_dealer_ron(honba),
+        payment.non_dealer_tsumo_to_non_dealer(honba),
+        payment.non_dealer_tsumo_to_dealer(honba)
+    )
+}
+
+pub fn json_aotenjou_hand_out(report: &AotenjouHandReport) -> String {
+    serde_json::to_string(report).unwrap_or_default()
+}
+
+pub fn json_legacy_aotenjou_hand_out(aotenjou: &AotenjouScore) -> String {
+    let score = aotenjou.score();
+    let mut out = json_score(score);
+    out["han"] = json!(aotenjou.han());
+    // the serialized payment is the one with limits
+    if let Some(out) = out.as_object_mut() {
+        out.remove("payment");
+    }
+    out["scores"] = json_aotenjou_scores(aotenjou.payment(), score.honba());
+    out.to_string()
+}
+
+pub fn default_aotenjou_hand_out(aotenjou: &AotenjouScore, lang: Lang) -> String {
+    let score = aotenjou.score();
+    let mut out = default_aotenjou_calc_out(
+        aotenjou.payment(),
+        score.honba(),
+        aotenjou.han(),
+        score.fu_score(),
+    );
+
+    if score.dora_count() != 0 {
+        out.push_str(&format!("\nDora: {}", score.dora_count()));
+    }
+    out.push_str("\nYaku: ");
+    for yaku in score.yaku() {
+        out.push_str(&format!(
+            "\n  {}",
+            lang::yaku_label(yaku, score.is_open(), lang)
+        ));
+    }
+    out.push_str("\nFu: ");
+    for fu in score.fu() {
+        out.push_str(&format!("\n  {}", lang::fu_label(fu, lang)));
+    }
+
+    out
+}
+
+/// Round context the repl keeps between hands.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReplContext {
+    seat: String,
+    prev: String,
+    honba: HonbaCounter,
+    dora: Vec<String>,
+    kiriage: bool,
+    sanma: bool,
+    aotenjou: bool,
+    lang: String,
+    /// Hands entered so far, without the context they were scored in.
+    history: Vec<String>,
+}
+
+const REPL_HELP: &str = "\
+Enter a hand as tiles with the winning tile last (ex: 123p 456p 99s 234s 678m 6m -r -t).
+Any flag of a normal hand can follow the tiles.
+  :seat S           seat wind
+  :round E          round wind
+  :honba 2          honba count
+  :dora 3p 5m       dora indicators (none to clear)
+  :rule kiriage on  turn kiriage, sanma or aotenjou on or off
+  :lang ja          language of yaku, fu and errors
+  :context          show the round context
+  :history          show the hands entered so far
+  :redo [n]         score hand n (default: the last) again in the current context
+  :quit             leave the repl";
+
+impl ReplContext {
+    /// Start from the round context given on the command line.
+    pub fn new(args: &Args) -> Self {
+        Self {
+            seat: args.seat.clone(),
+            prev: args.prev.clone(),
+            honba: args.ba,
+            dora: args.dora.clone().unwrap_or_default(),
+            kiriage: args.kiriage,
+            sanma: args.sanma,
+            aotenjou: args.aotenjou,
+            lang: args.lang.clone(),
+            history: vec![],
+        }
+    }
+
+    fn lang(&self) -> Lang {
+        Lang::try_from(self.lang.clone()).unwrap_or_default()
+    }
+
+    /// Run one line of input, giving what to print.
+    pub fn eval(&mut self, line: &str) -> String {
+        let line = line.trim();
+        let result = if line.is_empty() {
+            Ok(String::new())
+        } else if let Some(command) = line.strip_prefix(':') {
+            self.command(command)
+        } else {
+            self.history.push(line.to_string());
+            self.hand(line)
+        };
+
+        match result {
+            Ok(out) => out.trim_start_matches('\n').to_string(),
+            Err(e) => format!("Error: {}", lang::error_message(&e, self.lang())),
+        }
+    }
+
+    fn command(&mut self, command: &str) -> Result<String, HandErr> {
+        let mut words = command.split_whitespace();
+        let name = words.next().unwrap_or_default();
+        let values: Vec<&str> = words.collect();
+        let value = || values.first().copied().ok_or(HandErr::InvalidReplCommand);
+        // winds can be given without their suit (ex: `S`)
+        let wind = |value: &str| -> Result<String, HandErr> {
+            let wind = if value.len() == 1 {
+                format!("{value}w")
+            } else {
+                value.to_string()
+            };
+            Seat::try_from(wind.clone())?;
+            Ok(wind)
+        };
+
+        match name {
+            "seat" => self.seat = wind(value()?)?,
+            "round" | "prev" => self.prev = wind(value()?)?,
+            "honba" | "ba" => {
+                self.honba = value()?.parse().map_err(|_| HandErr::InvalidReplCommand)?
+            }
+            "dora" => {
+                let dora: Vec<&str> = values.into_iter().filter(|d| *d != "none").collect();
+                for tile in &dora {
+                    TileGroup::try_from(tile.to_string())?;
+                }
+                self.dora = dora.into_iter().map(String::from).collect();
+            }
+            "rule" => {
+                let on = match values.get(1).copied() {
+                    None | Some("on") => true,
+                    Some("off") => false,
+                    _ => return Err(HandErr::InvalidReplCommand),
+                };
+                match value()? {
+                    "kiriage" => self.kiriage = on,
+                    "sanma" => self.sanma = on,
+                    "aotenjou" => self.aotenjou = on,
+                    _ => return Err(HandErr::InvalidReplCommand),
+                }
+            }
+            "lang" => {
+                Lang::try_from(value()?.to_string())?;
+                self.lang = value()?.to_string();
+            }
+            "context" => {}
+            "history" => {
+                let lines: Vec<String> = self
+                    .history
+                    .iter()
+                    .enumerate()
+                    .map(|(i, hand)| format!("{}: {hand}", i + 1))
+                    .collect();
+                return Ok(lines.join("\n"));
+            }
+            "redo" => {
+                let index = match values.first() {
+                    Some(n) => n.parse().map_err(|_| HandErr::InvalidReplCommand)?,
+                    None => self.history.len(),
+                };
+                let hand = index
+                    .checked_sub(1)
+                    .and_then(|i| self.history.get(i))
+                    .cloned()
+                    .ok_or(HandErr::InvalidReplCommand)?;
+                self.history.push(hand.clone());
+                return self.hand(&hand);
+            }
+            "help" => return Ok(REPL_HELP.to_string()),
+            _ => return Err(HandErr::InvalidReplCommand),
+        }
+
+        Ok(self.to_string())
+    }
+
+    /// Score a hand in the current context.
+    fn hand(&self, line: &str) -> Result<String, HandErr> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let flags_at = words
+            .iter()
+            .position(|word| word.starts_with('-'))
+            .unwrap_or(words.len());
+        let (tiles, flags) = words.split_at(flags_at);
+        let has_win = flags.iter().any(|f| *f == "-w" || *f == "--win");
+
+        if tiles.len() < if has_win { 1 } else { 2 } {
+            return Err(HandErr::NoHandTiles);
+        }
+
+        let mut argv: Vec<String> = vec!["mahc".into(), "--tiles".into()];
+        match (has_win, tiles.split_last()) {
+            (false, Some((win, tiles))) => {
+                argv.extend(tiles.iter().map(|t| t.to_string()));
+                argv.extend(["-w".into(), win.to_string()]);
+            }
+            _ => argv.extend(tiles.iter().map(|t| t.to_string())),
+        }
+        argv.extend([
+            "-s".into(),
+            self.seat.clone(),
+            "-p".into(),
+            self.prev.clone(),
+            "--ba".into(),
+            self.honba.to_string(),
+            "--lang".into(),
+            self.lang.clone(),
+        ]);
+        if !self.dora.is_empty() {
+            argv.push("-d".into());
+            argv.extend(self.dora.iter().cloned());
+        }
+        for (rule, on) in [
+            ("--kiriage", self.kiriage),
+            ("--sanma", self.sanma),
+            ("--aotenjou", self.aotenjou),
+        ] {
+            if on {
+                argv.push(rule.into());
+            }
+        }
+        argv.extend(flags.iter().map(|f| f.to_string()));
+
+        let args = Args::try_parse_from(&argv).map_err(|_| HandErr::InvalidReplCommand)?;
+        parse_hand(&args)
+    }
+}
+
+impl std::fmt::Display for ReplContext {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let dora = if self.dora.is_empty() {
+            "none".to_string()
+        } else {
+            self.dora.join(" ")
+        };
+        let rules: Vec<&str> = [
+            ("kiriage", self.kiriage),
+            ("sanma", self.sanma),
+            ("aotenjou", self.aotenjou),
+        ]
+        .into_iter()
+        .filter(|(_, on)| *on)
+        .map(|(rule, _)| rule)
+        .collect();
+
+        write!(
+            f,
+            "Seat: {}/ Round: {}/ Honba: {}/ Dora: {dora}",
+            self.seat, self.prev, self.honba
+        )?;
+        if !rules.is_empty() {
+            write!(f, "/ Rules: {}", rules.join(" "))?;
+        }
+        Ok(())
+    }
+}
+
+/// Read hands and commands line by line until `:quit` or the end of the input.
+pub fn run_repl(context: &mut ReplContext, input: impl BufRead, output: &mut impl Write) {
+    let prompt = |output: &mut dyn Write| {
+        let _ = write!(output, "mahc> ");
+        let _ = output.flush();
+    };
+
+    prompt(output);
+    for line in input.lines().map_while(Result::ok) {
+        if matches!(line.trim(), ":quit" | ":q" | ":exit") {
+            break;
+        }
+        let out = context.eval(&line);
+        if !out.is_empty() {
+            let _ = writeln!(output, "{out}");
+        }
+        prompt(output);
+    }
+    let _ = writeln!(output);
+}
+
+pub fn parse_repl(args: &Args) {
+    let mut context = ReplContext::new(args);
+    println!("{context}\nType :help for commands.");
+    run_repl(
+        &mut context,
+        std::io::stdin().lock(),
+        &mut std::io::stdout(),
+    );
+}
+
+/// Fields of a JSON request to the HTTP API and the flags they stand for. Any other field is
+/// rejected, so only these flags can be reached.
+const REQUEST_FLAGS: [(&str, &str); 23] = [
+    ("tiles", "--tiles"),
+    ("win", "--win"),
+    ("dora", "--dora"),
+    ("seat", "--seat"),
+    ("prev", "--prev"),
+    ("tsumo", "--tsumo"),
+    ("riichi", "--riichi"),
+    ("doubleriichi", "--doubleriichi"),
+    ("ippatsu", "--ippatsu"),
+    ("haitei", "--haitei"),
+    ("rinshan", "--rinshan"),
+    ("chankan", "--chankan"),
+    ("tenhou", "--tenhou"),
+    ("aotenjou", "--aotenjou"),
+    ("sanma", "--sanma"),
+    ("bisection", "--bisection"),
+    ("sanma_honba", "--sanma-honba"),
+    ("nukidora", "--nukidora"),
+    ("nuki_rinshan", "--nuki-rinshan"),
+    ("honba", "--ba"),
+    ("kiriage", "--kiriage"),
+    ("verify", "--verify"),
+    ("lang", "--lang"),
+];
+
+/// Turn the fields of a JSON request into the flags they stand for (ex: `{"tsumo": true,
+/// "dora": ["3p"]}` to `--tsumo --dora 3p`), as listed in `allowed`.
+fn json_flags(
+    fields: &serde_json::Map<String, Value>,
+    allowed: &[(&str, &str)],
+) -> Result<Vec<String>, HandErr> {
+    let mut flags = vec![];
+    for (key, value) in fields {
+        let Some((_, flag)) = allowed.iter().find(|(field, _)| field == key) else {
+            return Err(HandErr::InvalidRequest);
+        };
+        let values = match value {
+            Value::Bool(false) | Value::Null => continue,
+            Value::Bool(true) => vec![],
+            Value::String(s) => vec![s.clone()],
+            Value::Number(n) => vec![n.to_string()],
+            Value::Array(items) => items
+                .iter()
+                .map(|item| match item {
+                    Value::String(s) => Ok(s.clone()),
+                    Value::Number(n) => Ok(n.to_string()),
+                    _ => Err(HandErr::InvalidRequest),
+                })
+                .collect::<Result<_, _>>()?,
+            Value::Object(_) => return Err(HandErr::InvalidRequest),
+        };
+        flags.push(flag.to_string());
+        flags.extend(values);
+    }
+    Ok(flags)
+}
+
+/// Parse the JSON request into args, with the subcommand and its own fields last.
+fn request_args(body: &str, command: Option<(&str, &[(&str, &str)])>) -> Result<Args, HandErr> {
+    let body = if body.trim().is_empty() { "{}" } else { body };
+    let mut fields = match serde_json::from_str(body) {
+        Ok(Value::Object(fields)) => fields,
+        _ => return Err(HandErr::InvalidRequest),
+    };
 
-    if !score.yaku()[0].is_yakuman() && score.dora_count() != 0 {
-        out.push_str(format!("\nDora: {}", score.dora_count()).as_str());
+    let mut argv: Vec<String> = vec!["mahc".into(), "--json".into()];
+    if let Some(han) = fields.remove("han") {
+        let fu = fields.remove("fu").ok_or(HandErr::NoFu)?;
+        argv.extend(["--manual".into(), han.to_string(), fu.to_string()]);
+    }
+    let mut command_fields = serde_json::Map::new();
+    if let Some((_, command_flags)) = command {
+        for (key, _) in command_flags {
+            if let Some(value) = fields.remove(*key) {
+                command_fields.insert(key.to_string(), value);
+            }
+        }
+    }
+    argv.extend(json_flags(&fields, &REQUEST_FLAGS)?);
+    if let Some((name, command_flags)) = command {
+        argv.push(name.into());
+        argv.extend(json_flags(&command_fields, command_flags)?);
     }
 
-    out.push_str("\nYaku: ");
-    for yaku in score.yaku() {
-        out.push_str(format!("\n  {}", yaku.to_string(score.is_open())).as_str());
+    Args::try_parse_from(&argv).map_err(|_| HandErr::InvalidRequest)
+}
+
+/// Answer a request to the HTTP API, in the same JSON as `--json`.
+pub fn serve_route(request: &Request) -> Response {
+    let result = match (request.method.as_str(), request.path.as_str()) {
+        ("POST", "/hand") => request_args(&request.body, None).and_then(|args| parse_hand(&args)),
+        ("POST", "/calc") => request_args(&request.body, None).and_then(|args| match args.manual {
+            Some(_) => parse_calculator(&args),
+            None => Err(HandErr::NoHan),
+        }),
+        ("GET" | "POST", "/table") => {
+            let table_flags = [("dealer", "--dealer"), ("non_dealer", "--non-dealer")];
+            let table = ("table", table_flags.as_slice());
+            request_args(&request.body, Some(table)).and_then(|args| match &args.command {
+                Some(command) => parse_command(&args, command),
+                None => Err(HandErr::InvalidRequest),
+            })
+        }
+        ("POST", "/validate") => {
+            let valid = request_args(&request.body, None).and_then(|args| parse_hand(&args));
+            return Response::ok(match valid {
+                Ok(_) => json!({ "valid": true }).to_string(),
+                Err(e) => {
+                    json!({ "valid": false, "error": { "code": e.code(), "message": e.to_string() } })
+                        .to_string()
+                }
+            });
+        }
+        _ => Err(HandErr::UnknownEndpoint),
+    };
+
+    match result {
+        Ok(out) => Response::ok(out),
+        Err(e) => Response::error(&e),
     }
+}
 
-    if !score.yaku()[0].is_yakuman() {
-        out.push_str("\nFu: ");
-        for fu in score.fu() {
-            out.push_str(format!("\n  {}", fu).as_str());
+pub fn parse_serve(serve: &ServeArgs) {
+    let server = match Server::bind(serve.port) {
+        Ok(server) => server,
+        Err(e) => {
+            eprintln!("Error: Unable to listen on port {}: {e}", serve.port);
+            return;
         }
+    };
+    let server = match &serve.allow_origin {
+        Some(origin) => server.allow_origin(origin.clone()),
+        None => server,
+    };
+    println!("Listening on http://127.0.0.1:{}", server.port());
+    server.run(serve_route);
+}
+
+/// Open the mjai log, or stdin if there is none.
+fn mjai_input(mjai: &MjaiArgs) -> std::io::Result<Box<dyn BufRead>> {
+    match &mjai.log {
+        Some(log) => Ok(Box::new(std::io::BufReader::new(fs::File::open(log)?))),
+        None => Ok(Box::new(std::io::stdin().lock())),
     }
+}
 
-    out
+pub fn parse_mjai(mjai: &MjaiArgs) {
+    let input = match mjai_input(mjai) {
+        Ok(input) => input,
+        Err(_) => {
+            eprintln!(
+                "Error: Unable to read file {}",
+                mjai.log.as_deref().unwrap_or_default()
+            );
+            return;
+        }
+    };
+    if let Err(e) = Referee::new().run(input, &mut std::io::stdout()) {
+        eprintln!("Error: {e}");
+    }
 }
 
 pub fn parse_file(args: &Args) {
@@ -308,35 +2110,42 @@ pub fn parse_file(args: &Args) {
                 current_line_args.push(arg.into());
             }
             let args = Args::parse_from(&current_line_args);
-            if let Some(_) = args.file {
+            if let Some(command) = &args.command {
+                parse_command(&args, command)
+            } else if args.file.is_some() {
                 parse_file(&args);
                 Ok("".to_string())
             } else if args.manual.is_some() {
                 parse_calculator(&args)
+            } else if args.lookup.is_some() {
+                parse_lookup(&args)
+            } else if args.need.is_some() {
+                parse_all_last(&args)
             } else {
                 parse_hand(&args)
             }
         })
         .collect();
 
+    let lang = Lang::try_from(args.lang.clone()).unwrap_or_default();
     for result in results {
-        writeout(&result, args.output.as_ref().unwrap());
-        printout(&result)
+        writeout(&result, args.output.as_ref().unwrap(), lang);
+        printout(&result, lang)
     }
 }
 
-pub fn printout(result: &Result<String, HandErr>) {
+pub fn printout(result: &Result<String, HandErr>, lang: Lang) {
     match result {
         Ok(o) => {
             println!("{}", o);
         }
         Err(e) => {
-            eprintln!("Error: {}", e);
+            eprintln!("Error: {}", lang::error_message(e, lang));
         }
     }
 }
 
-pub fn writeout(result: &Result<String, HandErr>, output: &str) {
+pub fn writeout(result: &Result<String, HandErr>, output: &str, lang: Lang) {
     let mut file = OpenOptions::new()
         .create(true)
         .append(true)
@@ -345,9 +2154,9 @@ pub fn writeout(result: &Result<String, HandErr>, output: &str) {
 
     let mut content = match result {
         Ok(o) => o.clone(),
-        Err(e) => e.to_string(),
+        Err(e) => lang::error_message(e, lang),
     };
-    content.push_str("\n");
+    content.push('\n');
 
     file.write_all(content.as_bytes())
         .expect("unable to write to file");
@@ -356,20 +2165,36 @@ pub fn writeout(result: &Result<String, HandErr>, output: &str) {
 fn main() {
     let args = Args::parse();
 
-    let result = if let Some(_) = args.file {
+    let result = if let Some(Command::Repl) = &args.command {
+        parse_repl(&args);
+        return;
+    } else if let Some(Command::Serve(serve)) = &args.command {
+        parse_serve(serve);
+        return;
+    } else if let Some(Command::Mjai(mjai)) = &args.command {
+        parse_mjai(mjai);
+        return;
+    } else if let Some(command) = &args.command {
+        parse_command(&args, command)
+    } else if args.file.is_some() {
         parse_file(&args);
         return;
     } else if args.manual.is_some() {
         parse_calculator(&args)
+    } else if args.lookup.is_some() {
+        parse_lookup(&args)
+    } else if args.need.is_some() {
+        parse_all_last(&args)
     } else {
         parse_hand(&args)
     };
 
+    let lang = Lang::try_from(args.lang.clone()).unwrap_or_default();
     if let Some(output) = &args.output {
-        writeout(&result, output);
+        writeout(&result, output, lang);
     }
 
-    printout(&result);
+    printout(&result, lang);
 }
 
 #[cfg(test)]
@@ -518,4 +2343,1029 @@ mod test {
                 .to_string())
         );
     }
+
+    #[test]
+    fn lookup_ron() {
+        let args = Args::parse_from(["", "--lookup", "3900"]);
+        let out = parse_lookup(&args);
+        assert_eq!(
+            out.unwrap(),
+            ("\n3900\nDealer: \n  1 Han/ 80 Fu\n  2 Han/ 40 Fu\nNon-dealer: \n  2 Han/ 60 Fu\n  3 Han/ 30 Fu"
+                .to_string())
+        );
+    }
+    #[test]
+    fn lookup_non_dealer_tsumo() {
+        let args = Args::parse_from(["", "--lookup", "1300-2600"]);
+        let out = parse_lookup(&args);
+        assert_eq!(
+            out.unwrap(),
+            ("\n1300-2600\nNon-dealer: \n  2 Han/ 80 Fu\n  3 Han/ 40 Fu\n  4 Han/ 20 Fu"
+                .to_string())
+        );
+    }
+    #[test]
+    fn lookup_dealer_tsumo_honba() {
+        let args = Args::parse_from(["", "--lookup", "4300all", "--ba", "3"]);
+        let out = parse_lookup(&args);
+        assert_eq!(
+            out.unwrap(),
+            ("\n4300all/ 3 Honba\nDealer: \n  Mangan".to_string())
+        );
+    }
+    #[test]
+    fn lookup_no_match() {
+        let args = Args::parse_from(["", "--lookup", "1234"]);
+        let out = parse_lookup(&args);
+        assert_eq!(out.unwrap(), ("\n1234\nNo matching hands".to_string()));
+    }
+    #[test]
+    fn lookup_invalid_payment() {
+        let args = Args::parse_from(["", "--lookup", "lots"]);
+        let out = parse_lookup(&args);
+        assert_eq!(out.unwrap_err(), HandErr::InvalidPayment);
+    }
+    #[test]
+    fn all_last_requirements() {
+        let args = Args::parse_from([
+            "", "--need", "30000", "24000", "26000", "20000", "--seat", "Sw",
+        ]);
+        let out = parse_all_last(&args);
+        assert_eq!(
+            out.unwrap(),
+            ("\nSouth needs placement 1\
+            \nRon from East: \n  1 Han/ 100 Fu\n  2 Han/ 50 Fu\n  3 Han/ 30 Fu\
+            \nRon from West: \n  2 Han/ 100 Fu\n  3 Han/ 50 Fu\n  4 Han/ 30 Fu\
+            \nRon from North: \n  2 Han/ 100 Fu\n  3 Han/ 50 Fu\n  4 Han/ 30 Fu\
+            \nTsumo: \n  2 Han/ 60 Fu\n  3 Han/ 30 Fu\n  4 Han/ 20 Fu"
+                .to_string())
+        );
+    }
+    #[test]
+    fn all_last_not_possible() {
+        let args = Args::parse_from([
+            "", "--need", "90000", "5000", "3000", "2000", "--seat", "Nw", "--place", "1",
+        ]);
+        let out = parse_all_last(&args);
+        assert_eq!(
+            out.unwrap(),
+            ("\nNorth needs placement 1\
+            \nRon from East: \n  Not possible\
+            \nRon from South: \n  Not possible\
+            \nRon from West: \n  Not possible\
+            \nTsumo: \n  Not possible"
+                .to_string())
+        );
+    }
+    #[test]
+    fn safety_against_riichi() {
+        let args = Args::parse_from([
+            "",
+            "safety",
+            "--tiles",
+            "9m",
+            "1p",
+            "7p",
+            "Nw",
+            "5s",
+            "--pond",
+            "Nw",
+            "9m",
+            "4p",
+            "1s",
+            "--riichi-turn",
+            "2",
+            "--toimen",
+            "Ww",
+            "5s",
+        ]);
+        let Some(command) = &args.command else {
+            panic!("no command parsed");
+        };
+        let out = parse_command(&args, command);
+        assert_eq!(
+            out.unwrap(),
+            ("\n9m: Genbutsu\n1p: Suji\n7p: Suji\nNw: Genbutsu\n5s: Genbutsu".to_string())
+        );
+    }
+    #[test]
+    fn safety_invalid_riichi_turn() {
+        let args = Args::parse_from([
+            "",
+            "safety",
+            "--tiles",
+            "9m",
+            "--pond",
+            "Nw",
+            "--riichi-turn",
+            "2",
+        ]);
+        let Some(command) = &args.command else {
+            panic!("no command parsed");
+        };
+        let out = parse_command(&args, command);
+        assert_eq!(out.unwrap_err(), HandErr::InvalidRiichiTurn);
+    }
+    #[test]
+    fn advise_kanchan_to_iipeikou() {
+        let args = Args::parse_from([
+            "", "-s", "Sw", "-r", "advise", "--tiles", "234m", "567p", "678p", "99m", "24s",
+        ]);
+        let Some(command) = &args.command else {
+            panic!("no command parsed");
+        };
+        let out = parse_command(&args, command).unwrap();
+        assert!(out.starts_with(
+            "\nTenpai: 1300 (waits 3s)\
+            \nDraw 5p, discard 8p: 2600 (+1300)\n  Waits: 3s\n  Yaku: Riichi: 1, Iipeikou: 1"
+        ));
+    }
+    #[test]
+    fn advise_not_iishanten() {
+        let args = Args::parse_from([
+            "", "advise", "--tiles", "159m", "159p", "159s", "ESWw", "rd",
+        ]);
+        let Some(command) = &args.command else {
+            panic!("no command parsed");
+        };
+        let out = parse_command(&args, command);
+        assert_eq!(out.unwrap_err(), HandErr::NotIishanten);
+    }
+    #[test]
+    fn simulate_seeded() {
+        let args = Args::parse_from([
+            "", "simulate", "--tiles", "234m", "567p", "345s", "99p", "67s", "--trials", "50",
+            "--seed", "3",
+        ]);
+        let Some(command) = &args.command else {
+            panic!("no command parsed");
+        };
+        let out = parse_command(&args, command).unwrap();
+        assert!(out.starts_with("\nTrials: 50 (seed 3)\nTenpai: 100.0%\nTsumo: "));
+        assert_eq!(out, parse_command(&args, command).unwrap());
+    }
+    #[test]
+    fn simulate_too_many_tiles() {
+        let args = Args::parse_from([
+            "", "simulate", "--tiles", "234m", "567p", "345s", "99p", "678s",
+        ]);
+        let Some(command) = &args.command else {
+            panic!("no command parsed");
+        };
+        let out = parse_command(&args, command);
+        assert_eq!(out.unwrap_err(), HandErr::InvalidShape);
+    }
+    #[test]
+    fn settle_ron_with_sticks() {
+        let args = Args::parse_from([
+            "", "--ba", "1", "--sticks", "2", "settle", "--winner", "Sw", "--from", "Ew", "--hand",
+            "3", "30",
+        ]);
+        let Some(command) = &args.command else {
+            panic!("no command parsed");
+        };
+        let out = parse_command(&args, command).unwrap();
+        assert_eq!(out, "\nEast\tSouth\tWest\tNorth\n-4200\t+6200\t+0\t+0");
+    }
+    #[test]
+    fn settle_tsumo_json() {
+        let args = Args::parse_from([
+            "", "--json", "settle", "--winner", "Ew", "--hand", "4", "30",
+        ]);
+        let Some(command) = &args.command else {
+            panic!("no command parsed");
+        };
+        let out = parse_command(&args, command).unwrap();
+        assert_eq!(
+            out,
+            "{\"east\":11700,\"north\":-3900,\"south\":-3900,\"west\":-3900}"
+        );
+    }
+    #[test]
+    fn settle_double_ron() {
+        let run = |cli: &[&str]| {
+            let args = Args::parse_from(cli);
+            let Some(command) = &args.command else {
+                panic!("no command parsed");
+            };
+            parse_command(&args, command)
+        };
+        let double_ron = [
+            "", "--ba", "1", "--sticks", "1", "settle", "--winner", "Sw", "Ew", "--from", "Ww",
+            "--hand", "2", "30", "2", "40",
+        ];
+
+        // East is nearer to West than South, so takes the honba and the riichi stick
+        let out = run(&double_ron).unwrap();
+        assert_eq!(out, "\nEast\tSouth\tWest\tNorth\n+5200\t+2000\t-6200\t+0");
+
+        let out = run(&[&double_ron[..], &["--atamahane"]].concat()).unwrap();
+        assert_eq!(out, "\nEast\tSouth\tWest\tNorth\n+5200\t+0\t-4200\t+0");
+
+        let out = run(&[
+            "", "settle", "--winner", "Sw", "Ew", "--hand", "2", "30", "2", "40",
+        ]);
+        assert_eq!(out.unwrap_err(), HandErr::InvalidWinners);
+        let out = run(&[
+            "", "settle", "--winner", "Sw", "Ew", "--from", "Ww", "--hand", "2", "30",
+        ]);
+        assert_eq!(out.unwrap_err(), HandErr::InvalidWinners);
+    }
+    #[test]
+    fn settle_kiriage_like_game() {
+        let args = Args::parse_from([
+            "",
+            "--kiriage",
+            "settle",
+            "--winner",
+            "Sw",
+            "--from",
+            "Ww",
+            "--hand",
+            "4",
+            "30",
+        ]);
+        let Some(command) = &args.command else {
+            panic!("no command parsed");
+        };
+        let out = parse_command(&args, command);
+        assert_eq!(
+            out.unwrap(),
+            "\nEast\tSouth\tWest\tNorth\n+0\t+8000\t-8000\t+0"
+        );
+    }
+    #[test]
+    fn settle_ron_own_discard() {
+        let args = Args::parse_from([
+            "", "settle", "--winner", "Ww", "--from", "Ww", "--hand", "1", "30",
+        ]);
+        let Some(command) = &args.command else {
+            panic!("no command parsed");
+        };
+        let out = parse_command(&args, command);
+        assert_eq!(out.unwrap_err(), HandErr::RonOwnDiscard);
+    }
+    #[test]
+    fn game_hand_by_hand() {
+        let state = std::env::temp_dir().join("mahc-test-game-hand-by-hand.json");
+        let state = state.to_str().unwrap();
+        let run = |action: &[&str]| {
+            let mut cli = vec!["", "game", "--state", state];
+            cli.extend(action);
+            let args = Args::parse_from(cli);
+            let Some(command) = &args.command else {
+                panic!("no command parsed");
+            };
+            parse_command(&args, command)
+        };
+
+        run(&["new", "--tonpuusen"]).unwrap();
+        run(&[
+            "win", "--winner", "Sw", "--from", "Ew", "--hand", "3", "30", "--riichi", "Sw",
+        ])
+        .unwrap();
+        let out = run(&["draw", "--tenpai", "Ew"]).unwrap();
+        assert_eq!(
+            out,
+            "\nEast 2, 1 Honba, 0 Riichi Sticks\
+            \nPlayer 1 (North): 20100\nPlayer 2 (East): 31900\
+            \nPlayer 3 (South): 24000\nPlayer 4 (West): 24000"
+        );
+
+        let out = run(&["undo"]).unwrap();
+        assert!(out.starts_with("\nEast 2, 0 Honba, 0 Riichi Sticks"));
+        run(&["undo"]).unwrap();
+        assert_eq!(run(&["undo"]).unwrap_err(), HandErr::NothingToUndo);
+        assert_eq!(
+            run(&["win", "--winner", "Ew", "--from", "Ew", "--hand", "1", "30"]).unwrap_err(),
+            HandErr::RonOwnDiscard
+        );
+
+        fs::remove_file(state).unwrap();
+        assert_eq!(run(&["show"]).unwrap_err(), HandErr::GameStateFile);
+    }
+    #[test]
+    fn game_win_from_hand() {
+        let state = std::env::temp_dir().join("mahc-test-game-win-from-hand.json");
+        let state = state.to_str().unwrap();
+        let run = |cli: &[&str]| {
+            let args = Args::parse_from(cli);
+            let Some(command) = &args.command else {
+                panic!("no command parsed");
+            };
+            parse_command(&args, command)
+        };
+
+        run(&["", "game", "--state", state, "new"]).unwrap();
+        // riichi, pinfu, tanyao and dora 1 at 30 fu, rounded up to mangan
+        let out = run(&[
+            "",
+            "--tiles",
+            "22m",
+            "678p",
+            "345s",
+            "567m",
+            "234p",
+            "-w",
+            "2p",
+            "-d",
+            "1p",
+            "--kiriage",
+            "game",
+            "--state",
+            state,
+            "win",
+            "--winner",
+            "Sw",
+            "--from",
+            "Ew",
+            "--riichi",
+            "Sw",
+        ])
+        .unwrap();
+        assert_eq!(
+            out,
+            "\nEast 2, 0 Honba, 0 Riichi Sticks\
+            \nPlayer 1 (North): 17000\nPlayer 2 (East): 33000\
+            \nPlayer 3 (South): 25000\nPlayer 4 (West): 25000"
+        );
+
+        fs::remove_file(state).unwrap();
+    }
+    #[test]
+    fn game_abortive_draw() {
+        let state = std::env::temp_dir().join("mahc-test-game-abortive-draw.json");
+        let state = state.to_str().unwrap();
+        let run = |action: &[&str]| {
+            let mut cli = vec!["", "game", "--state", state];
+            cli.extend(action);
+            let args = Args::parse_from(cli);
+            let Some(command) = &args.command else {
+                panic!("no command parsed");
+            };
+            parse_command(&args, command)
+        };
+
+        run(&["new"]).unwrap();
+        let out = run(&[
+            "abort",
+            "--events",
+            "Ew:riichi",
+            "Sw:riichi",
+            "Ww:riichi",
+            "Nw:riichi",
+        ])
+        .unwrap();
+        assert!(out.starts_with("\nEast 1, 1 Honba, 4 Riichi Sticks"));
+
+        let rons = [
+            "abort",
+            "--events",
+            "Ew:discard:5p",
+            "Sw:ron",
+            "Ww:ron",
+            "Nw:ron",
+        ];
+        run(&rons).unwrap();
+        let mut allowed = rons.to_vec();
+        allowed.push("--triple-ron");
+        assert_eq!(run(&allowed).unwrap_err(), HandErr::NoAbortiveDraw);
+        assert_eq!(
+            run(&["abort", "--events", "Ew:skip"]).unwrap_err(),
+            HandErr::InvalidHandEvent
+        );
+
+        fs::remove_file(state).unwrap();
+    }
+    #[test]
+    fn placement_from_scores() {
+        let args = Args::parse_from([
+            "",
+            "placement",
+            "--scores",
+            "42300",
+            "23900",
+            "30600",
+            "3200",
+            "--uma",
+            "30/10/-10/-30",
+        ]);
+        let Some(command) = &args.command else {
+            panic!("no command parsed");
+        };
+        let out = parse_command(&args, command).unwrap();
+        assert_eq!(
+            out,
+            "\n1. Player 1: 42300 (+62.3)\n2. Player 3: 30600 (+10.6)\
+            \n3. Player 2: 23900 (-16.1)\n4. Player 4: 3200 (-56.8)"
+        );
+    }
+    #[test]
+    fn placement_from_unfinished_game() {
+        let state = std::env::temp_dir().join("mahc-test-placement-unfinished.json");
+        let state = state.to_str().unwrap();
+        fs::write(state, Game::new(Rules::default()).to_json()).unwrap();
+
+        let args = Args::parse_from(["", "placement", "--state", state]);
+        let Some(command) = &args.command else {
+            panic!("no command parsed");
+        };
+        let out = parse_command(&args, command);
+        fs::remove_file(state).unwrap();
+        assert_eq!(out.unwrap_err(), HandErr::GameNotOver);
+    }
+    #[test]
+    fn placement_invalid_uma() {
+        let args = Args::parse_from([
+            "",
+            "placement",
+            "--scores",
+            "25000",
+            "25000",
+            "25000",
+            "25000",
+            "--uma",
+            "20/10/-10/-10",
+        ]);
+        let Some(command) = &args.command else {
+            panic!("no command parsed");
+        };
+        let out = parse_command(&args, command);
+        assert_eq!(out.unwrap_err(), HandErr::InvalidUma);
+    }
+    #[test]
+    fn sanma_calc_tsumo_loss() {
+        let args = Args::parse_from(["", "--manual", "5", "30", "--ba", "1", "--sanma"]);
+        let out = parse_calculator(&args).unwrap();
+        assert_eq!(
+            out,
+            "\n5 Han/ 30 Fu/ 1 Honba\nDealer: 12200 (4100)\nnon-dealer: 8200 (2100/4100)"
+        );
+    }
+    #[test]
+    fn sanma_hand_bisection() {
+        let args = Args::parse_from([
+            "",
+            "--tiles",
+            "111m",
+            "999m",
+            "456p",
+            "789s",
+            "ggd",
+            "-w",
+            "gd",
+            "-s",
+            "Sw",
+            "-t",
+            "-d",
+            "1m",
+            "--sanma",
+            "--bisection",
+            "--sanma-honba",
+            "1000",
+        ]);
+        let out = parse_hand(&args).unwrap();
+        assert!(out.contains("Non-dealer: 8000 (3000/5000)"));
+        assert!(out.contains("Dora: 3"));
+    }
+    #[test]
+    fn sanma_north_seat() {
+        let args = Args::parse_from([
+            "", "--tiles", "111m", "999m", "456p", "789s", "ggd", "-w", "gd", "-s", "Nw", "--sanma",
+        ]);
+        let out = parse_hand(&args);
+        assert_eq!(out.unwrap_err(), HandErr::SanmaNorth);
+    }
+    #[test]
+    fn sanma_nukidora_rinshan() {
+        let args = Args::parse_from([
+            "",
+            "--tiles",
+            "111m",
+            "999m",
+            "456p",
+            "789s",
+            "ggd",
+            "NNwk",
+            "-w",
+            "gd",
+            "-s",
+            "Sw",
+            "-t",
+            "--rinshan",
+            "-d",
+            "Ww",
+            "--sanma",
+            "--nuki-rinshan",
+        ]);
+        let out = parse_hand(&args).unwrap();
+        assert!(out.contains("Dora: 4"));
+    }
+    #[test]
+    fn four_player_nukidora() {
+        let flags = [
+            "", "--tiles", "123p", "456p", "789s", "rrrd", "EEw", "Nwk", "-w", "3p",
+        ];
+        let args = Args::parse_from(flags);
+        assert_eq!(
+            parse_hand(&args).unwrap_err(),
+            HandErr::NukidoraOutsideSanma
+        );
+
+        let args = Args::parse_from(flags.iter().chain(&["--nukidora"]));
+        let out = parse_hand(&args).unwrap();
+        assert!(out.contains("Dora: 1"));
+
+        let conflict = flags.iter().chain(&["--nukidora", "--sanma"]);
+        assert!(Args::try_parse_from(conflict).is_err());
+        assert!(Args::try_parse_from(flags.iter().chain(&["--nuki-rinshan"])).is_err());
+    }
+    #[test]
+    fn table_house_rules() {
+        let args = Args::parse_from([
+            "",
+            "--kiriage",
+            "--ba",
+            "1",
+            "table",
+            "--non-dealer",
+            "--format",
+            "md",
+        ]);
+        let Some(command) = &args.command else {
+            panic!("no command parsed");
+        };
+        let out = parse_command(&args, command).unwrap();
+        assert!(out.starts_with("\n### Non-dealer\n"));
+        assert!(!out.contains("### Dealer"));
+        assert!(out.contains(
+            "| 30 | 1300 (400/600) | 2300 (600/1100) | 4200 (1100/2100) | 8300 (2100/4100) |"
+        ));
+
+        let args = Args::parse_from(["", "table", "--format", "pdf"]);
+        let Some(command) = &args.command else {
+            panic!("no command parsed");
+        };
+        assert_eq!(
+            parse_command(&args, command).unwrap_err(),
+            HandErr::InvalidTableFormat
+        );
+    }
+    #[test]
+    fn calc_kiriage() {
+        let args = Args::parse_from(["", "--manual", "4", "30", "--kiriage"]);
+        let out = parse_calculator(&args).unwrap();
+        assert!(out.contains("Dealer: 12000 (4000)"));
+    }
+    #[test]
+    fn hand_kiriage() {
+        let args = Args::parse_from([
+            "",
+            "--tiles",
+            "22m",
+            "678p",
+            "345s",
+            "567m",
+            "234p",
+            "-w",
+            "2p",
+            "-s",
+            "Sw",
+            "-r",
+            "-d",
+            "1p",
+            "--kiriage",
+        ]);
+        let out = parse_hand(&args).unwrap();
+        assert!(out.contains("\nNon-dealer: 8000 (2000/4000)"));
+
+        // sanma hands are rounded up as well
+        let args = Args::parse_from([
+            "",
+            "--tiles",
+            "111m",
+            "999m",
+            "456p",
+            "789s",
+            "ggd",
+            "Nwk",
+            "-w",
+            "gd",
+            "-s",
+            "Sw",
+            "-t",
+            "--sanma",
+            "--kiriage",
+            "-d",
+            "Ww",
+        ]);
+        let out = parse_hand(&args).unwrap();
+        assert!(out.contains("\nNon-dealer: 8000 (2000/4000)"), "{out}");
+    }
+    #[test]
+    fn aotenjou_calc() {
+        let args = Args::parse_from(["", "--manual", "100", "30", "--aotenjou"]);
+        let out = parse_calculator(&args).unwrap();
+        assert!(out.starts_with("\n100 Han/ 30 Fu\nDealer: 912708432164325169077626307870800 ("));
+
+        let args = Args::parse_from(["", "--manual", "5", "30", "--aotenjou", "--json"]);
+        let out = parse_calculator(&args).unwrap();
+        assert!(out.contains("\"ron\":\"15400\""));
+    }
+    #[test]
+    fn aotenjou_hand() {
+        let args = Args::parse_from([
+            "",
+            "--tiles",
+            "555z",
+            "666z",
+            "777z",
+            "222zo",
+            "11z",
+            "-w",
+            "1z",
+            "-s",
+            "3z",
+            "--aotenjou",
+        ]);
+        let out = parse_hand(&args).unwrap();
+        assert!(out.starts_with("\n26 Han/ 60 Fu\nDealer: 96636764200 (32212254800)"));
+        assert!(out.contains("Daisangen"));
+    }
+    #[test]
+    fn verify_claim() {
+        let hand = [
+            "", "--tiles", "123p", "456p", "99s", "234s", "678m", "-w", "6m", "-s", "Sw",
+        ];
+
+        let mut args = hand.to_vec();
+        args.extend(["-r", "--verify", "2000"]);
+        let out = parse_hand(&Args::parse_from(args)).unwrap();
+        assert_eq!(out, "\nClaim: 2000 is correct\nHand: 2 Han/ 30 Fu");
+
+        let mut args = hand.to_vec();
+        args.extend(["-r", "-t", "--verify", "1000-2000"]);
+        let out = parse_hand(&Args::parse_from(args)).unwrap();
+        assert!(out.starts_with(
+            "\nClaim: 1000-2000 is incorrect, the hand is worth 700-1300\
+            \nNon-dealer: 1000 claimed, 700 actual (+300)\
+            \nDealer: 2000 claimed, 1300 actual (+700)\
+            \nHand: 3 Han/ 20 Fu\
+            \nClaim matches: \n  2 Han/ 60 Fu (-1 Han, +40 Fu)\n  3 Han/ 30 Fu (+10 Fu)"
+        ));
+        assert!(out.contains("\nYaku: \n  Riichi: 1"));
+
+        let mut args = hand.to_vec();
+        args.extend(["-t", "--verify", "700all"]);
+        let out = parse_hand(&Args::parse_from(args));
+        assert_eq!(out.unwrap_err(), HandErr::ClaimWinnerMismatch);
+
+        // a tsumo claim needs -t, and a ron claim cannot have it
+        let mut args = hand.to_vec();
+        args.extend(["-r", "--verify", "700-1300"]);
+        let out = parse_hand(&Args::parse_from(args));
+        assert_eq!(out.unwrap_err(), HandErr::ClaimMethodMismatch);
+        let mut args = hand.to_vec();
+        args.extend(["-r", "-t", "--verify", "2000"]);
+        let out = parse_hand(&Args::parse_from(args));
+        assert_eq!(out.unwrap_err(), HandErr::ClaimMethodMismatch);
+    }
+
+    #[test]
+    fn json_verify_and_aotenjou_schema() {
+        let hand = [
+            "", "--tiles", "123p", "456p", "99s", "234s", "678m", "-w", "6m", "-s", "Sw", "-r",
+            "--json",
+        ];
+        let run = |extra: &[&str]| -> Value {
+            let args = Args::parse_from(hand.iter().chain(extra));
+            serde_json::from_str(&parse_hand(&args).unwrap()).unwrap()
+        };
+
+        let out = run(&["--verify", "2900"]);
+        assert_eq!(out["schema_version"], 2);
+        assert_eq!(out["correct"], false);
+        assert_eq!(out["components"][0]["difference"], 900);
+        assert_eq!(
+            out["claimed_values"][0],
+            json!({"han": 1, "fu": 90, "limit": null})
+        );
+        assert_eq!(out["hand"]["yaku"][1]["id"], "pinfu");
+        assert!(out.get("fuString").is_none());
+
+        let out = run(&["--aotenjou"]);
+        assert_eq!(out["schema_version"], 2);
+        assert_eq!(out["points"]["non_dealer"]["ron"], "2000");
+        assert!(out.get("yakuString").is_none());
+
+        // the old layout is only behind --legacy-json
+        let out = run(&["--aotenjou", "--legacy-json"]);
+        assert!(out.get("schema_version").is_none());
+        assert!(out.get("yakuString").is_some());
+        let out = run(&["--verify", "2900", "--legacy-json"]);
+        assert_eq!(out["claim"], "2900");
+        assert!(out.get("fuString").is_some());
+    }
+
+    #[test]
+    fn json_hand_schema() {
+        let hand = [
+            "", "--tiles", "123p", "456p", "789p", "rrrdo", "99p", "-w", "9p", "-d", "9p", "--json",
+        ];
+        let out = parse_hand(&Args::parse_from(hand)).unwrap();
+        let out: Value = serde_json::from_str(&out).unwrap();
+
+        assert_eq!(out["schema_version"], 2);
+        assert_eq!(out["limit"], "mangan");
+        assert_eq!(out["wait"], "tanki");
+        assert_eq!(out["dora"]["dora"], 1);
+        assert_eq!(
+            out["yaku"][0],
+            json!({ "id" : "honitsu", "name" : "Honitsu", "han" : 2, "yakuman" : false, "open" : true })
+        );
+        assert_eq!(out["fu_breakdown"][1]["open"], true);
+        assert_eq!(out["points"]["non_dealer"]["ron"], 8000);
+
+        let mut args = hand.to_vec();
+        args.push("--legacy-json");
+        let out = parse_hand(&Args::parse_from(args)).unwrap();
+        let out: Value = serde_json::from_str(&out).unwrap();
+        assert_eq!(out["schema_version"], Value::Null);
+        assert_eq!(out["yakuString"][0], "Honitsu: 2");
+        assert_eq!(out["scores"]["non-dealer"]["ron"], 8000);
+    }
+
+    #[test]
+    fn json_calc_schema() {
+        let args = Args::parse_from(["", "-m", "4", "30", "--kiriage", "--json"]);
+        let out: Value = serde_json::from_str(&parse_calculator(&args).unwrap()).unwrap();
+        assert_eq!(out["limit"], "mangan");
+        assert_eq!(out["points"]["dealer"]["ron"], 12000);
+
+        let args = Args::parse_from(["", "-m", "4", "30", "--json", "--legacy-json"]);
+        let out: Value = serde_json::from_str(&parse_calculator(&args).unwrap()).unwrap();
+        assert_eq!(out["scores"]["non-dealer"]["ron"], 7700);
+    }
+
+    #[test]
+    fn legacy_json_matches_1x() {
+        // output of mahc 1.x for the same hand and calculator input
+        let args = Args::parse_from([
+            "",
+            "--tiles",
+            "123p",
+            "456p",
+            "99s",
+            "234s",
+            "678m",
+            "-w",
+            "6m",
+            "-s",
+            "Sw",
+            "-r",
+            "-t",
+            "--ba",
+            "1",
+            "-d",
+            "1p",
+            "--json",
+            "--legacy-json",
+        ]);
+        assert_eq!(
+            parse_hand(&args).unwrap(),
+            "{\"dora\":1,\"fu\":20,\"fuString\":[\"BasePoints: 20\"],\"han\":4,\"honba\":1,\
+            \"scores\":{\"dealer\":{\"ron\":8000,\"tsumo\":2700},\"non-dealer\":{\"ron\":5500,\
+            \"tsumo\":{\"dealer\":2700,\"non-dealer\":1400}}},\
+            \"yakuString\":[\"Riichi: 1\",\"MenzenTsumo: 1\",\"Pinfu: 1\"]}"
+        );
+
+        let args = Args::parse_from(["", "-m", "3", "30", "--ba", "2", "--json", "--legacy-json"]);
+        assert_eq!(
+            parse_calculator(&args).unwrap(),
+            "{\"fu\":30,\"han\":3,\"honba\":2,\"scores\":{\"dealer\":{\"ron\":6400,\
+            \"tsumo\":2200},\"non-dealer\":{\"ron\":4500,\"tsumo\":{\"dealer\":2200,\
+            \"non-dealer\":1200}}}}"
+        );
+    }
+
+    #[test]
+    fn hand_in_japanese() {
+        let args = Args::parse_from([
+            "", "--tiles", "123p", "456p", "99s", "234s", "678m", "-w", "6m", "-s", "Sw", "-r",
+            "--lang", "ja",
+        ]);
+        let out = parse_hand(&args).unwrap();
+        assert!(out.ends_with("\nYaku: \n  立直: 1\n  平和: 1\nFu: \n  副底: 20\n  門前加符: 10"));
+
+        let args = Args::parse_from(["", "--tiles", "123p", "-w", "3p", "--lang", "tlh"]);
+        assert_eq!(parse_hand(&args).unwrap_err(), HandErr::InvalidShape);
+        let args = Args::parse_from([
+            "", "--tiles", "123p", "456p", "99s", "234s", "678m", "-w", "6m", "--lang", "tlh",
+        ]);
+        assert_eq!(parse_hand(&args).unwrap_err(), HandErr::InvalidLang);
+    }
+
+    #[test]
+    fn explain_hand() {
+        let args = Args::parse_from([
+            "",
+            "--tiles",
+            "123p",
+            "456p",
+            "99s",
+            "234s",
+            "789m",
+            "-w",
+            "7m",
+            "-s",
+            "Sw",
+            "-r",
+            "--explain",
+        ]);
+        let out = parse_hand(&args).unwrap();
+        assert!(out
+            .contains("\nWhy: \n  Riichi: 1 (riichi declared)\n  Fuutei: 20 (every winning hand)"));
+        assert!(out.contains("\n  Machi Fu: 2 (penchan wait on 7m)"));
+        assert!(out.contains(
+            "\nNot awarded: \n  Tanyao: denied, 1p in 123p\n  Pinfu: denied, penchan wait"
+        ));
+
+        let args = Args::try_parse_from([
+            "",
+            "--tiles",
+            "123p",
+            "456p",
+            "99s",
+            "234s",
+            "789m",
+            "-w",
+            "7m",
+            "--explain",
+            "--json",
+        ]);
+        assert!(args.is_err());
+    }
+
+    #[test]
+    fn render_hand() {
+        let args = Args::parse_from([
+            "", "--tiles", "123p", "456p", "789m", "555so", "99s", "-w", "9s", "-d", "4p",
+            "--render", "ascii",
+        ]);
+        let out = parse_hand(&args).unwrap();
+        assert!(out.starts_with("\n+--+--+"));
+        assert!(out.ends_with("\nDora\n+--+\n|4p|\n|  |\n+--+"));
+
+        let args = Args::parse_from([
+            "", "--tiles", "123p", "456p", "789m", "555so", "99s", "-w", "9s", "--render", "svg",
+        ]);
+        assert!(parse_hand(&args).unwrap().ends_with("</svg>"));
+
+        let args = Args::parse_from([
+            "", "--tiles", "123p", "456p", "789m", "555so", "99s", "-w", "9s", "--render", "png",
+        ]);
+        assert_eq!(parse_hand(&args).unwrap_err(), HandErr::InvalidRenderFormat);
+    }
+
+    #[test]
+    fn repl_keeps_context() {
+        let args = Args::parse_from(["", "--ba", "1", "repl"]);
+        let mut context = ReplContext::new(&args);
+        let input = "\
+123p 456p 99s 234s 678m 6m -r
+:seat S
+:dora 5p
+:redo
+:history
+:seat X
+rrrd
+:quit
+123p 456p 99s 234s 678m 6m
+";
+        let mut output = vec![];
+        run_repl(&mut context, input.as_bytes(), &mut output);
+        let output = String::from_utf8(output).unwrap();
+
+        assert!(output.starts_with("mahc> 2 Han/ 30 Fu/ 1 Honba\nDealer: 3200 (1100)"));
+        assert!(output.contains("mahc> Seat: Sw/ Round: Ew/ Honba: 1/ Dora: 5p\n"));
+        assert!(output.contains("mahc> 3 Han/ 30 Fu/ 1 Honba\nDealer: 6100 (2100)"));
+        assert!(output.contains(
+            "mahc> 1: 123p 456p 99s 234s 678m 6m -r\n2: 123p 456p 99s 234s 678m 6m -r\n"
+        ));
+        assert!(output.contains("mahc> Error: Invalid Group found\n"));
+        assert!(output.contains("mahc> Error: No Hand Tiles given\n"));
+        assert_eq!(output.matches("Han/").count(), 2);
+    }
+
+    #[test]
+    fn serve_routes() {
+        let request = |method: &str, path: &str, body: &str| Request {
+            method: method.into(),
+            path: path.into(),
+            body: body.into(),
+        };
+
+        let hand = r#"{"tiles": ["123p", "456p", "99s", "234s", "678m"], "win": "6m", "riichi": true, "honba": 1}"#;
+        let response = serve_route(&request("POST", "/hand", hand));
+        let args = Args::parse_from([
+            "", "--tiles", "123p", "456p", "99s", "234s", "678m", "-w", "6m", "-r", "--ba", "1",
+            "--json",
+        ]);
+        assert_eq!(response, Response::ok(parse_hand(&args).unwrap()));
+        let verify = r#"{"tiles": ["123p", "456p", "99s", "234s", "678m"], "win": "6m", "riichi": true, "verify": "2000"}"#;
+        let report: serde_json::Value =
+            serde_json::from_str(&serve_route(&request("POST", "/hand", verify)).body).unwrap();
+        assert_eq!(report["schema_version"], mahc::report::SCHEMA_VERSION);
+        assert_eq!(
+            report["hand"]["schema_version"],
+            mahc::report::SCHEMA_VERSION
+        );
+
+        let response = serve_route(&request("POST", "/calc", r#"{"han": 3, "fu": 30}"#));
+        let args = Args::parse_from(["", "--manual", "3", "30", "--json"]);
+        assert_eq!(response, Response::ok(parse_calculator(&args).unwrap()));
+
+        let response = serve_route(&request("POST", "/table", r#"{"dealer": true}"#));
+        let args = Args::parse_from(["", "--json", "table", "--dealer"]);
+        let table = parse_command(&args, args.command.as_ref().unwrap()).unwrap();
+        assert_eq!(response, Response::ok(table));
+
+        let no_yaku = r#"{"tiles": ["123p", "456p", "99s", "234s", "678mo"], "win": "6m"}"#;
+        let response = serve_route(&request("POST", "/validate", no_yaku));
+        assert_eq!(
+            response.body,
+            r#"{"error":{"code":"no_yaku","message":"No Yaku"},"valid":false}"#
+        );
+
+        let response = serve_route(&request("POST", "/hand", no_yaku));
+        assert_eq!(response, Response::error(&HandErr::NoYaku));
+        let response = serve_route(&request("POST", "/hand", r#"{"bogus": 1}"#));
+        assert_eq!(response, Response::error(&HandErr::InvalidRequest));
+        // flags outside the allowlist cannot be reached
+        let response = serve_route(&request("POST", "/hand", r#"{"file": "hands.txt"}"#));
+        assert_eq!(response, Response::error(&HandErr::InvalidRequest));
+        let response = serve_route(&request("POST", "/table", r#"{"format": "html"}"#));
+        assert_eq!(response, Response::error(&HandErr::InvalidRequest));
+        let response = serve_route(&request("GET", "/hand", ""));
+        assert_eq!(response.status, 404);
+    }
+
+    #[test]
+    fn serve_on_localhost() {
+        use std::io::Read;
+        use std::net::TcpStream;
+
+        let server = Server::bind(0).unwrap();
+        let port = server.port();
+        let client = std::thread::spawn(move || {
+            let mut stream = TcpStream::connect(("127.0.0.1", port)).unwrap();
+            let body = r#"{"han": 1, "fu": 30}"#;
+            let request = format!(
+                "POST /calc HTTP/1.1\r\nContent-Length: {}\r\n\r\n{body}",
+                body.len()
+            );
+            stream.write_all(request.as_bytes()).unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            response
+        });
+
+        server.handle_one(serve_route).unwrap();
+        let response = client.join().unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.contains(r#""han":1,"fu":30"#));
+    }
+
+    #[test]
+    fn mjai_log() {
+        let log = std::env::temp_dir().join("mahc-mjai-test.jsonl");
+        fs::write(
+            &log,
+            r#"{"type":"start_game","names":["a","b","c","d"]}
+{"type":"start_kyoku","bakaze":"E","kyoku":1,"honba":0,"kyotaku":0,"oya":0,"dora_marker":"4s","tehais":[["2m","3m","4m","5p","6p","7p","3s","4s","5s","6s","7s","8s","5m"],["?","?","?","?","?","?","?","?","?","?","?","?","?"],["?","?","?","?","?","?","?","?","?","?","?","?","?"],["?","?","?","?","?","?","?","?","?","?","?","?","?"]]}
+{"type":"tsumo","actor":0,"pai":"9p"}
+{"type":"dahai","actor":0,"pai":"9p","tsumogiri":true}
+{"type":"tsumo","actor":1,"pai":"?"}
+{"type":"dahai","actor":1,"pai":"5m","tsumogiri":false}
+{"type":"hora","actor":0,"target":1,"pai":"5m"}
+{"type":"end_kyoku"}
+"#,
+        )
+        .unwrap();
+        let args = Args::parse_from(["", "mjai", "--log", log.to_str().unwrap()]);
+        let Some(Command::Mjai(mjai)) = &args.command else {
+            panic!("expected the mjai command");
+        };
+
+        let mut output = vec![];
+        Referee::new()
+            .run(mjai_input(mjai).unwrap(), &mut output)
+            .unwrap();
+        fs::remove_file(&log).unwrap();
+        let output = String::from_utf8(output).unwrap();
+
+        assert_eq!(output.lines().count(), 1);
+        assert!(output.contains(r#""deltas":[3900,-3900,0,0]"#));
+        assert!(output.contains(r#""yakus":[["tanyaochu",1],["dora",1]]"#));
+    }
 }
@@ -0,0 +1,1013 @@
+//! Scoring referee for the [mjai](https://github.com/gimite/mjai) protocol, where bots and
+//! simulators send events as JSON lines (ex: `{"type":"dahai","actor":0,"pai":"5mr"}`).
+//!
+//! The referee tracks the hands and table from the events, and scores every `hora` event with
+//! [`calc::get_hand_score`], filling in its `yakus`, `fu`, `fan`, `hora_points`, `deltas` and
+//! `scores` the way an mjai server does.
+
+use std::io::{BufRead, Write};
+
+use serde_json::{json, Value};
+
+use crate::calc;
+use crate::hand::error::HandErr;
+use crate::hand::Hand;
+use crate::payment::{PlayerPoints, RIICHI_STICK};
+use crate::score::{HonbaCounter, RiichiCounter, Score};
+use crate::seat::Seat;
+use crate::settlement::{Settlement, WinMethod};
+use crate::suit::Suit;
+use crate::tenpai::{TileCounts, WinContext};
+use crate::tile_group::{GroupType, TileGroup};
+use crate::yaku::Yaku;
+
+/// Points each player starts the game with.
+const START_POINTS: PlayerPoints = 25_000;
+/// Number of tiles left to draw after the deal, in a four player game.
+const LIVE_WALL: usize = 70;
+/// Fan mjai gives each yakuman.
+const YAKUMAN_FAN: u32 = 100;
+/// Tile mjai shows in place of a tile the viewer cannot see.
+const HIDDEN: &str = "?";
+
+/// What a player has in hand, and what they can still win with.
+#[derive(Debug, Clone, Default)]
+struct Player {
+    /// Concealed tiles, in mjai notation.
+    tehai: Vec<String>,
+    /// Called and concealed kan groups.
+    melds: Vec<TileGroup>,
+    riichi: bool,
+    double_riichi: bool,
+    /// Riichi declared and waiting to be accepted, and whether it is a double riichi.
+    declared: Option<bool>,
+    ippatsu: bool,
+    /// Whether the player has discarded this hand.
+    discarded: bool,
+    /// Whether the next draw is the replacement tile of a kan.
+    kan_pending: bool,
+    /// Whether the last draw is the replacement tile of a kan.
+    rinshan: bool,
+}
+
+/// Keeps track of a game from its mjai events, scoring every win.
+///
+/// # Examples
+///
+/// ```rust
+/// use mahc::mjai::Referee;
+///
+/// let events = [
+///     r#"{"type":"start_game","names":["a","b","c","d"]}"#,
+///     r#"{"type":"start_kyoku","bakaze":"E","kyoku":1,"honba":0,"kyotaku":0,"oya":0,
+///        "dora_marker":"4s","tehais":[
+///        ["2m","3m","4m","5p","6p","7p","3s","4s","5s","6s","7s","8s","5m"],
+///        ["?","?","?","?","?","?","?","?","?","?","?","?","?"],
+///        ["?","?","?","?","?","?","?","?","?","?","?","?","?"],
+///        ["?","?","?","?","?","?","?","?","?","?","?","?","?"]]}"#,
+///     r#"{"type":"tsumo","actor":0,"pai":"9p"}"#,
+///     r#"{"type":"dahai","actor":0,"pai":"9p","tsumogiri":true}"#,
+///     r#"{"type":"tsumo","actor":1,"pai":"?"}"#,
+///     r#"{"type":"dahai","actor":1,"pai":"5m","tsumogiri":false}"#,
+/// ];
+/// let mut referee = Referee::new();
+/// for event in events {
+///     referee.event(&serde_json::from_str(event).unwrap()).unwrap();
+/// }
+///
+/// let hora = serde_json::from_str(r#"{"type":"hora","actor":0,"target":1,"pai":"5m"}"#);
+/// let scored = referee.event(&hora.unwrap()).unwrap().unwrap();
+///
+/// // tanyao and a dora, for the dealer
+/// assert_eq!(scored["fan"], 2);
+/// assert_eq!(scored["fu"], 40);
+/// assert_eq!(scored["hora_points"], 3_900);
+/// assert_eq!(referee.scores(), [28_900, 21_100, 25_000, 25_000]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Referee {
+    scores: [PlayerPoints; 4],
+    /// Prevalent wind, in mjai notation.
+    bakaze: String,
+    /// Player who is the dealer.
+    oya: usize,
+    honba: HonbaCounter,
+    kyotaku: RiichiCounter,
+    dora_markers: Vec<String>,
+    players: [Player; 4],
+    /// Number of tiles left to draw.
+    wall: usize,
+    /// Whether any tile was called this hand, which ends the first go-around.
+    called: bool,
+    /// Last discarded tile.
+    discard: Option<String>,
+    /// Whether the last discard followed the replacement tile of a kan, which is not houtei.
+    rinshan_discard: bool,
+    /// Player who collected the honba and riichi sticks of a ron, and what they were paid.
+    ron_bonus: Option<(usize, [PlayerPoints; 4])>,
+    /// Tile added to a called triplet, which can be robbed.
+    kakan: Option<String>,
+}
+
+impl Default for Referee {
+    fn default() -> Self {
+        Self {
+            scores: [START_POINTS; 4],
+            bakaze: "E".to_string(),
+            oya: 0,
+            honba: 0,
+            kyotaku: 0,
+            dora_markers: vec![],
+            players: Default::default(),
+            wall: LIVE_WALL,
+            called: false,
+            discard: None,
+            rinshan_discard: false,
+            ron_bonus: None,
+            kakan: None,
+        }
+    }
+}
+
+impl Referee {
+    /// Create a new [`Referee`] for a game that has not started.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the points of every player.
+    pub fn scores(&self) -> [PlayerPoints; 4] {
+        self.scores
+    }
+
+    /// Apply an event, returning the scored event if it is a `hora`.
+    ///
+    /// Events the referee does not need (ex: `end_kyoku`) are ignored.
+    ///
+    /// With more than one ron on a discard, the honba and riichi sticks go to the winner nearest
+    /// the discarder in turn order. When a nearer winner comes after another, its `deltas` also
+    /// take them back from the winner that collected them first.
+    pub fn event(&mut self, event: &Value) -> Result<Option<Value>, HandErr> {
+        let kind = event["type"].as_str().ok_or(HandErr::InvalidMjaiEvent)?;
+        // only the tile of the last added kan can be robbed
+        if !matches!(kind, "kakan" | "hora") {
+            self.kakan = None;
+        }
+
+        match kind {
+            "start_game" => self.scores = [START_POINTS; 4],
+            "start_kyoku" => self.start_kyoku(event)?,
+            "tsumo" => {
+                let player = &mut self.players[actor(event, "actor")?];
+                player.tehai.push(pai(event, "pai")?);
+                player.rinshan = std::mem::take(&mut player.kan_pending);
+                self.wall = self.wall.checked_sub(1).ok_or(HandErr::InvalidMjaiEvent)?;
+            }
+            "dahai" => {
+                let tile = pai(event, "pai")?;
+                let player = &mut self.players[actor(event, "actor")?];
+                take(&mut player.tehai, &tile)?;
+                // the discard that declares riichi still keeps ippatsu
+                player.ippatsu = false;
+                player.discarded = true;
+                self.rinshan_discard = std::mem::take(&mut player.rinshan);
+                self.discard = Some(tile);
+            }
+            "reach" => {
+                let called = self.called;
+                let player = &mut self.players[actor(event, "actor")?];
+                player.declared = Some(!called && !player.discarded);
+            }
+            "reach_accepted" => {
+                let actor = actor(event, "actor")?;
+                let player = &mut self.players[actor];
+                let double = player.declared.take().ok_or(HandErr::InvalidMjaiEvent)?;
+                player.riichi = true;
+                player.double_riichi = double;
+                player.ippatsu = true;
+                self.kyotaku += 1;
+                self.scores[actor] -= RIICHI_STICK as PlayerPoints;
+                self.set_scores(event)?;
+            }
+            "chi" | "pon" | "daiminkan" => {
+                let mut tiles = pais(event, "consumed")?;
+                let player = &mut self.players[actor(event, "actor")?];
+                for tile in &tiles {
+                    take(&mut player.tehai, tile)?;
+                }
+                tiles.push(pai(event, "pai")?);
+                player.melds.push(group(&tiles, true)?);
+                player.kan_pending = kind == "daiminkan";
+                self.interrupt();
+            }
+            "ankan" => {
+                let tiles = pais(event, "consumed")?;
+                let player = &mut self.players[actor(event, "actor")?];
+                for tile in &tiles {
+                    take(&mut player.tehai, tile)?;
+                }
+                player.melds.push(group(&tiles, false)?);
+                player.kan_pending = true;
+                self.interrupt();
+            }
+            "kakan" => {
+                let added = pai(event, "pai")?;
+                let mut tiles = pais(event, "consumed")?;
+                let player = &mut self.players[actor(event, "actor")?];
+                take(&mut player.tehai, &added)?;
+                let pon = group(&tiles, true)?;
+                let meld = player
+                    .melds
+                    .iter_mut()
+                    .find(|meld| meld.group_type == GroupType::Triplet && same_kind(meld, &pon))
+                    .ok_or(HandErr::InvalidMjaiEvent)?;
+                tiles.push(added.clone());
+                *meld = group(&tiles, true)?;
+                player.kan_pending = true;
+                self.interrupt();
+                self.kakan = Some(added);
+            }
+            "dora" => self.dora_markers.push(pai(event, "dora_marker")?),
+            "hora" => return self.hora(event).map(Some),
+            "ryukyoku" => self.ryukyoku(event)?,
+            _ => {}
+        }
+
+        Ok(None)
+    }
+
+    /// Read events line by line, writing every scored `hora`, and an mjai `error` event for
+    /// every line that cannot be applied.
+    pub fn run(&mut self, input: impl BufRead, output: &mut impl Write) -> std::io::Result<()> {
+        for line in input.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let result = serde_json::from_str(&line)
+                .map_err(|_| HandErr::InvalidMjaiEvent)
+                .and_then(|event| self.event(&event));
+            match result {
+                Ok(Some(hora)) => writeln!(output, "{hora}")?,
+                Ok(None) => continue,
+                Err(e) => writeln!(
+                    output,
+                    "{}",
+                    json!({"type": "error", "message": e.to_string()})
+                )?,
+            }
+            output.flush()?;
+        }
+
+        Ok(())
+    }
+
+    fn start_kyoku(&mut self, event: &Value) -> Result<(), HandErr> {
+        let tehais = event["tehais"]
+            .as_array()
+            .ok_or(HandErr::InvalidMjaiEvent)?;
+        if tehais.len() != 4 {
+            return Err(HandErr::InvalidMjaiEvent);
+        }
+        let mut players: [Player; 4] = Default::default();
+        for (player, tehai) in players.iter_mut().zip(tehais) {
+            player.tehai = strings(tehai)?;
+        }
+
+        *self = Self {
+            scores: self.scores,
+            bakaze: pai(event, "bakaze")?,
+            oya: actor(event, "oya")?,
+            honba: event["honba"].as_u64().unwrap_or_default(),
+            kyotaku: event["kyotaku"].as_u64().unwrap_or_default(),
+            dora_markers: vec![pai(event, "dora_marker")?],
+            players,
+            ..Self::default()
+        };
+        self.set_scores(event)
+    }
+
+    /// Take the scores of the event, when it has them.
+    fn set_scores(&mut self, event: &Value) -> Result<(), HandErr> {
+        if !event["scores"].is_null() {
+            self.scores = points(&event["scores"])?;
+        }
+        Ok(())
+    }
+
+    /// Pay out a draw, from its scores or else from its deltas.
+    fn ryukyoku(&mut self, event: &Value) -> Result<(), HandErr> {
+        if event["scores"].is_null() && !event["deltas"].is_null() {
+            for (score, delta) in self.scores.iter_mut().zip(points(&event["deltas"])?) {
+                *score += delta;
+            }
+        }
+        self.set_scores(event)
+    }
+
+    /// A call ends the first go-around and every ippatsu.
+    fn interrupt(&mut self) {
+        self.called = true;
+        for player in &mut self.players {
+            player.ippatsu = false;
+        }
+    }
+
+    fn seat(&self, player: usize) -> Seat {
+        Seat::ALL[(player + 4 - self.oya) % 4]
+    }
+
+    /// Score the win of the event and pay it out.
+    fn hora(&mut self, event: &Value) -> Result<Value, HandErr> {
+        let actor = actor(event, "actor")?;
+        let target = actor_or(event, "target", actor)?;
+        let tsumo = actor == target;
+        let player = &self.players[actor];
+        let chankan = !tsumo && self.kakan.is_some();
+
+        let win = match event["pai"].as_str() {
+            Some(win) => win.to_string(),
+            None if tsumo => player
+                .tehai
+                .last()
+                .cloned()
+                .ok_or(HandErr::InvalidMjaiEvent)?,
+            None => self
+                .kakan
+                .clone()
+                .or_else(|| self.discard.clone())
+                .ok_or(HandErr::InvalidMjaiEvent)?,
+        };
+        let mut tehai = match event.get("hora_tehais") {
+            Some(tehai) => strings(tehai)?,
+            None => player.tehai.clone(),
+        };
+        let hora_tehais = if tsumo {
+            let mut tehai = tehai.clone();
+            take(&mut tehai, &win)?;
+            tehai
+        } else {
+            tehai.clone()
+        };
+        if tehai.len() + 3 * player.melds.len() == 13 {
+            tehai.push(win.clone());
+        }
+
+        let concealed: Vec<TileGroup> = tehai.iter().map(|t| tile(t)).collect::<Result<_, _>>()?;
+        let seat = self.seat(actor);
+        let context = WinContext {
+            seat: wind(["E", "S", "W", "N"][seat.index()])?,
+            prev: wind(&self.bakaze)?,
+            dora: None,
+            tsumo,
+            riichi: false,
+            honba: self.honba,
+        };
+        let mut markers = self.dora_markers.clone();
+        if player.riichi {
+            markers.extend(strings(&event["uradora_markers"]).unwrap_or_default());
+        }
+        let markers: Vec<TileGroup> = markers.iter().map(|t| tile(t)).collect::<Result<_, _>>()?;
+        let first_turn = !self.called && !player.discarded;
+        // the last draw or discard does not count when it came after a kan
+        let haitei = self.wall == 0
+            && match tsumo {
+                true => !player.rinshan,
+                false => !chankan && !self.rinshan_discard,
+            };
+
+        let win_tile = tile(&win)?;
+        let mut best: Option<(Hand, Score)> = None;
+        let mut error = HandErr::InvalidMjaiEvent;
+        let hands = TileCounts::new(&concealed).winning_hands(
+            &player.melds,
+            TileCounts::index(&win_tile),
+            &context,
+        );
+        for hand in hands {
+            let score = calc::get_hand_score(
+                hand.clone(),
+                Some(markers.clone()),
+                tsumo,
+                player.riichi && !player.double_riichi,
+                player.double_riichi,
+                player.ippatsu,
+                haitei,
+                tsumo && player.rinshan,
+                chankan,
+                tsumo && first_turn,
+                self.honba,
+            );
+            match score {
+                Ok(score) => {
+                    let value = (score.payment().base_points(), score.han());
+                    let best_value = best
+                        .as_ref()
+                        .map(|(_, b)| (b.payment().base_points(), b.han()));
+                    if best_value.is_none_or(|best_value| value > best_value) {
+                        best = Some((hand, score));
+                    }
+                }
+                Err(e) => error = e,
+            }
+        }
+        let (hand, score) = best.ok_or(error)?;
+
+        let method = if tsumo {
+            WinMethod::Tsumo
+        } else {
+            WinMethod::Ron(self.seat(target))
+        };
+        let with_bonus = Settlement::new(seat, method, self.honba, self.kyotaku)?;
+        let with_bonus = with_bonus.deltas(score.payment());
+        let without_bonus = Settlement::new(seat, method, 0, 0)?.deltas(score.payment());
+        let mut deltas: [PlayerPoints; 4] = [0; 4];
+        let mut bonus: [PlayerPoints; 4] = [0; 4];
+        for player in 0..4 {
+            let index = self.seat(player).index();
+            deltas[player] = without_bonus[index];
+            bonus[player] = with_bonus[index] - without_bonus[index];
+        }
+        // the winner nearest the discarder in turn order collects the honba and riichi sticks
+        let distance = |winner: usize| (winner + 4 - target) % 4;
+        let collects = match self.ron_bonus {
+            Some((first, _)) => !tsumo && distance(actor) < distance(first),
+            None => true,
+        };
+        if collects {
+            if let Some((_, paid)) = self.ron_bonus.take() {
+                for (delta, paid) in deltas.iter_mut().zip(paid) {
+                    *delta -= paid;
+                }
+            }
+            for (delta, bonus) in deltas.iter_mut().zip(bonus) {
+                *delta += bonus;
+            }
+            self.ron_bonus = Some((actor, bonus));
+        }
+        for (score, delta) in self.scores.iter_mut().zip(deltas) {
+            *score += delta;
+        }
+
+        let (yakus, fan) = self.yakus(&hand, &score, tsumo, &markers);
+        let mut scored = event.clone();
+        let fields = scored.as_object_mut().ok_or(HandErr::InvalidMjaiEvent)?;
+        fields.insert("target".to_string(), json!(target));
+        fields.insert("pai".to_string(), json!(win));
+        fields.insert("hora_tehais".to_string(), json!(hora_tehais));
+        fields.insert("yakus".to_string(), json!(yakus));
+        fields.insert("fu".to_string(), json!(score.fu_score()));
+        fields.insert("fan".to_string(), json!(fan));
+        fields.insert(
+            "hora_points".to_string(),
+            json!(score.payment().total(seat.is_dealer(), tsumo, 0)),
+        );
+        fields.insert("deltas".to_string(), json!(deltas));
+        fields.insert("scores".to_string(), json!(self.scores));
+
+        Ok(scored)
+    }
+
+    /// Get the yaku of the win as mjai names and fan, and the total fan.
+    fn yakus(
+        &self,
+        hand: &Hand,
+        score: &Score,
+        tsumo: bool,
+        markers: &[TileGroup],
+    ) -> (Vec<(String, u32)>, u32) {
+        let mut yakus: Vec<(String, u32)> = vec![];
+
+        let is_yakuman = score.yaku().iter().any(|yaku| yaku.is_yakuman());
+        for yaku in score.yaku() {
+            match yaku {
+                Yaku::Yakuhai => continue,
+                _ if is_yakuman => add(&mut yakus, yaku_name(yaku, tsumo), YAKUMAN_FAN),
+                _ => add(
+                    &mut yakus,
+                    yaku_name(yaku, tsumo),
+                    yaku.get_han(hand.is_open()),
+                ),
+            }
+        }
+        if is_yakuman {
+            let fan = yakus.iter().map(|(_, fan)| fan).sum();
+            return (yakus, fan);
+        }
+
+        if score.yaku().contains(&Yaku::Yakuhai) {
+            for group in hand.triplets().iter().chain(&hand.kans()) {
+                if group.suit == Suit::Dragon {
+                    add(&mut yakus, "sangenpai", 1);
+                }
+                if same_kind(group, &hand.seat_tile()) {
+                    add(&mut yakus, "jikaze", 1);
+                }
+                if same_kind(group, &hand.prev_tile()) {
+                    add(&mut yakus, "bakaze", 1);
+                }
+            }
+        }
+
+        let aka = hand.count_dora(&[]);
+        let dora_tiles: Vec<TileGroup> = markers
+            .iter()
+            .take(self.dora_markers.len())
+            .filter_map(|marker| marker.next_tile().ok())
+            .collect();
+        let dora = hand.count_dora(&dora_tiles) - aka;
+        add(&mut yakus, "dora", dora);
+        add(&mut yakus, "uradora", score.dora_count() - dora - aka);
+        add(&mut yakus, "akadora", aka);
+
+        (yakus, score.han())
+    }
+}
+
+/// Add the fan to the yaku of the name, leaving out yaku with no fan.
+fn add(yakus: &mut Vec<(String, u32)>, name: &str, fan: u32) {
+    if fan == 0 {
+        return;
+    }
+    match yakus.iter_mut().find(|(n, _)| n == name) {
+        Some((_, total)) => *total += fan,
+        None => yakus.push((name.to_string(), fan)),
+    }
+}
+
+/// Get the mjai name of the yaku.
+fn yaku_name(yaku: &Yaku, tsumo: bool) -> &'static str {
+    match yaku {
+        Yaku::Tanyao => "tanyaochu",
+        Yaku::Iipeikou => "ipeko",
+        Yaku::Yakuhai => "yakuhai",
+        Yaku::MenzenTsumo => "menzenchin_tsumoho",
+        Yaku::Pinfu => "pinfu",
+        Yaku::Riichi => "reach",
+        Yaku::Ippatsu => "ippatsu",
+        Yaku::Haitei if tsumo => "haiteiraoyue",
+        Yaku::Haitei => "houteiraoyui",
+        Yaku::RinshanKaihou => "rinshankaiho",
+        Yaku::Chankan => "chankan",
+        Yaku::DoubleRiichi => "double_reach",
+        Yaku::Toitoi => "toitoiho",
+        Yaku::Ittsuu => "ikkitsukan",
+        Yaku::SanshokuDoujun => "sanshokudojun",
+        Yaku::Chantaiyao => "honchantaiyao",
+        Yaku::Sanankou => "sananko",
+        Yaku::SanshokuDoukou => "sanshokudoko",
+        Yaku::Sankantsu => "sankantsu",
+        Yaku::Honroutou => "honroto",
+        Yaku::Shousangen => "shosangen",
+        Yaku::Chiitoitsu => "chitoitsu",
+        Yaku::Honitsu => "honiso",
+        Yaku::JunchanTaiyao => "junchantaiyao",
+        Yaku::Ryanpeikou => "ryanpeko",
+        Yaku::Chinitsu => "chiniso",
+        Yaku::KazoeYakuman => "kazoeyakuman",
+        Yaku::KokushiMusou | Yaku::KokushiMusou13SidedWait => "kokushimuso",
+        Yaku::Suuankou | Yaku::SuuankouTankiWait => "suanko",
+        Yaku::Daisangen => "daisangen",
+        Yaku::Shousuushii => "shosushi",
+        Yaku::Daisuushii => "daisushi",
+        Yaku::Tsuuiisou => "tsuiso",
+        Yaku::Daichiishin => "daichishin",
+        Yaku::Chinroutou => "chinroto",
+        Yaku::Ryuuiisou => "ryuiso",
+        Yaku::ChuurenPoutou | Yaku::ChuurenPoutou9SidedWait => "churenpoton",
+        Yaku::Suukantsu => "sukantsu",
+        Yaku::Tenhou => "tenho",
+        Yaku::Chiihou => "chiho",
+    }
+}
+
+/// Get the value and suit characters of an mjai tile, and whether it is akadora (ex: `5mr` is
+/// `('5', 'm', true)`).
+fn notation(pai: &str) -> Result<(char, char, bool), HandErr> {
+    let (pai, aka) = match pai.strip_suffix('r') {
+        Some(five) if five.starts_with('5') => (five, true),
+        _ => (pai, false),
+    };
+    let mut chars = pai.chars();
+    let tile = match (chars.next(), chars.next(), chars.next()) {
+        (Some(value @ '1'..='9'), Some(suit @ ('m' | 'p' | 's')), None) => (value, suit, aka),
+        (Some(wind @ ('E' | 'S' | 'W' | 'N')), None, None) => (wind, 'w', false),
+        (Some('P'), None, None) => ('w', 'd', false),
+        (Some('F'), None, None) => ('g', 'd', false),
+        (Some('C'), None, None) => ('r', 'd', false),
+        _ => return Err(HandErr::InvalidMjaiEvent),
+    };
+
+    Ok(tile)
+}
+
+/// Parse a single mjai tile (ex: `5mr` to `0m`, `P` to `wd`).
+fn tile(pai: &str) -> Result<TileGroup, HandErr> {
+    group(&[pai.to_string()], false)
+}
+
+/// Parse a wind tile from its mjai notation.
+fn wind(pai: &str) -> Result<TileGroup, HandErr> {
+    match notation(pai)? {
+        (value, 'w', _) => format!("{value}w").try_into(),
+        _ => Err(HandErr::InvalidMjaiEvent),
+    }
+}
+
+/// Join mjai tiles into a group, sorting the tiles of a sequence.
+fn group(pais: &[String], open: bool) -> Result<TileGroup, HandErr> {
+    let tiles: Vec<(char, char, bool)> = pais
+        .iter()
+        .map(|pai| notation(pai))
+        .collect::<Result<_, _>>()?;
+    let Some(&(_, suit, _)) = tiles.first() else {
+        return Err(HandErr::InvalidMjaiEvent);
+    };
+    if tiles.iter().any(|&(_, s, _)| s != suit) {
+        return Err(HandErr::InvalidMjaiEvent);
+    }
+
+    let mut values: Vec<char> = tiles.iter().map(|&(value, _, _)| value).collect();
+    values.sort_unstable();
+    let mut values: String = values.into_iter().collect();
+    let aka = tiles.iter().filter(|&&(_, _, aka)| aka).count();
+    values = values.replacen('5', "0", aka);
+
+    let open = if open { "o" } else { "" };
+    format!("{values}{suit}{open}")
+        .try_into()
+        .map_err(|_| HandErr::InvalidMjaiEvent)
+}
+
+/// Check if two groups are made of the same kind of tile.
+fn same_kind(a: &TileGroup, b: &TileGroup) -> bool {
+    a.value == b.value && a.suit == b.suit
+}
+
+/// Take a tile out of the concealed tiles, or a hidden tile if the tile is not there.
+fn take(tehai: &mut Vec<String>, pai: &str) -> Result<(), HandErr> {
+    let index = tehai
+        .iter()
+        .position(|t| t == pai)
+        .or_else(|| tehai.iter().position(|t| t == HIDDEN))
+        .ok_or(HandErr::InvalidMjaiEvent)?;
+    tehai.remove(index);
+    Ok(())
+}
+
+/// Get the tile of the field, which can be hidden.
+fn pai(event: &Value, key: &str) -> Result<String, HandErr> {
+    let pai = event[key].as_str().ok_or(HandErr::InvalidMjaiEvent)?;
+    if pai != HIDDEN {
+        notation(pai)?;
+    }
+    Ok(pai.to_string())
+}
+
+fn strings(value: &Value) -> Result<Vec<String>, HandErr> {
+    value
+        .as_array()
+        .ok_or(HandErr::InvalidMjaiEvent)?
+        .iter()
+        .map(|pai| {
+            pai.as_str()
+                .map(str::to_string)
+                .ok_or(HandErr::InvalidMjaiEvent)
+        })
+        .collect()
+}
+
+fn pais(event: &Value, key: &str) -> Result<Vec<String>, HandErr> {
+    strings(&event[key])
+}
+
+/// Get the points of every player, from an array of four.
+fn points(value: &Value) -> Result<[PlayerPoints; 4], HandErr> {
+    let points: Vec<PlayerPoints> = value
+        .as_array()
+        .ok_or(HandErr::InvalidMjaiEvent)?
+        .iter()
+        .map(|points| points.as_i64().ok_or(HandErr::InvalidMjaiEvent))
+        .collect::<Result<_, _>>()?;
+    points.try_into().map_err(|_| HandErr::InvalidMjaiEvent)
+}
+
+/// Get the player of the field, from `0` to `3`.
+fn actor(event: &Value, key: &str) -> Result<usize, HandErr> {
+    match event[key].as_u64() {
+        Some(player @ 0..=3) => Ok(player as usize),
+        _ => Err(HandErr::InvalidMjaiEvent),
+    }
+}
+
+fn actor_or(event: &Value, key: &str, default: usize) -> Result<usize, HandErr> {
+    match event.get(key) {
+        Some(_) => actor(event, key),
+        None => Ok(default),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HIDDEN_TEHAI: &str = r#"["?","?","?","?","?","?","?","?","?","?","?","?","?"]"#;
+
+    fn referee(tehais: [&str; 2], events: &[&str]) -> Referee {
+        let mut referee = Referee::new();
+        let start = format!(
+            r#"{{"type":"start_kyoku","bakaze":"E","kyoku":1,"honba":1,"kyotaku":0,"oya":0,
+            "dora_marker":"1p","scores":[25000,25000,25000,25000],
+            "tehais":[{},{},{HIDDEN_TEHAI},{HIDDEN_TEHAI}]}}"#,
+            tehais[0], tehais[1]
+        );
+        referee
+            .event(&serde_json::from_str(&start).unwrap())
+            .unwrap();
+        for event in events {
+            referee
+                .event(&serde_json::from_str(event).unwrap())
+                .unwrap();
+        }
+        referee
+    }
+
+    fn hora(referee: &mut Referee, event: &str) -> Value {
+        referee
+            .event(&serde_json::from_str(event).unwrap())
+            .unwrap()
+            .unwrap()
+    }
+
+    #[test]
+    fn parse_tiles() {
+        assert_eq!(tile("5mr").unwrap().to_string(), "0m");
+        assert_eq!(tile("P").unwrap().to_string(), "wd");
+        assert_eq!(tile("C").unwrap().to_string(), "rd");
+        assert_eq!(tile("N").unwrap().to_string(), "Nw");
+        assert_eq!(tile("0m").unwrap_err(), HandErr::InvalidMjaiEvent);
+        assert_eq!(tile("?").unwrap_err(), HandErr::InvalidMjaiEvent);
+
+        let chi = ["6s".to_string(), "5sr".to_string(), "4s".to_string()];
+        assert_eq!(group(&chi, true).unwrap().to_string(), "406so");
+    }
+
+    #[test]
+    fn riichi_ippatsu_tsumo() {
+        let mut referee = referee(
+            [
+                r#"["1m","1m","1m","?","?","?","?","?","?","?","?","?","?"]"#,
+                r#"["2m","3m","4m","5pr","6p","7p","3s","4s","5s","7s","8s","9s","9p"]"#,
+            ],
+            &[
+                r#"{"type":"tsumo","actor":0,"pai":"?"}"#,
+                r#"{"type":"dahai","actor":0,"pai":"1m","tsumogiri":false}"#,
+                r#"{"type":"tsumo","actor":1,"pai":"N"}"#,
+                r#"{"type":"reach","actor":1}"#,
+                r#"{"type":"dahai","actor":1,"pai":"N","tsumogiri":true}"#,
+                r#"{"type":"reach_accepted","actor":1}"#,
+                r#"{"type":"tsumo","actor":2,"pai":"?"}"#,
+                r#"{"type":"dahai","actor":2,"pai":"E","tsumogiri":true}"#,
+                r#"{"type":"tsumo","actor":3,"pai":"?"}"#,
+                r#"{"type":"dahai","actor":3,"pai":"E","tsumogiri":true}"#,
+                r#"{"type":"tsumo","actor":0,"pai":"?"}"#,
+                r#"{"type":"dahai","actor":0,"pai":"1m","tsumogiri":false}"#,
+                r#"{"type":"tsumo","actor":1,"pai":"9p"}"#,
+            ],
+        );
+        let scored = hora(
+            &mut referee,
+            r#"{"type":"hora","actor":1,"target":1,"pai":"9p","uradora_markers":["8p"]}"#,
+        );
+
+        // double riichi, as no one had called and it is the first discard
+        assert_eq!(
+            scored["yakus"],
+            json!([
+                ["double_reach", 2],
+                ["ippatsu", 1],
+                ["menzenchin_tsumoho", 1],
+                ["uradora", 2],
+                ["akadora", 1]
+            ])
+        );
+        // haneman, with the honba and the riichi stick on top
+        assert_eq!(scored["fan"], 7);
+        assert_eq!(scored["hora_points"], 12_000);
+        assert_eq!(scored["deltas"], json!([-6_100, 13_300, -3_100, -3_100]));
+        assert_eq!(referee.scores(), [18_900, 37_300, 21_900, 21_900]);
+        assert_eq!(scored["hora_tehais"].as_array().unwrap().len(), 13);
+    }
+
+    #[test]
+    fn rinshan_and_chankan() {
+        let tehais = [
+            r#"["2m","3m","4m","6p","7p","8p","5s","5s","5s","7s","7s","E","E"]"#,
+            r#"["2p","3p","4p","5p","6p","7p","5s","6s","2m","3m","4m","8m","8m"]"#,
+        ];
+        let calls = [
+            r#"{"type":"tsumo","actor":0,"pai":"E"}"#,
+            r#"{"type":"dahai","actor":0,"pai":"E","tsumogiri":true}"#,
+            r#"{"type":"tsumo","actor":1,"pai":"N"}"#,
+            r#"{"type":"dahai","actor":1,"pai":"N","tsumogiri":true}"#,
+            r#"{"type":"tsumo","actor":2,"pai":"?"}"#,
+            r#"{"type":"dahai","actor":2,"pai":"7s","tsumogiri":true}"#,
+            r#"{"type":"pon","actor":0,"target":2,"pai":"7s","consumed":["7s","7s"]}"#,
+            r#"{"type":"dahai","actor":0,"pai":"E","tsumogiri":false}"#,
+            r#"{"type":"tsumo","actor":1,"pai":"N"}"#,
+            r#"{"type":"dahai","actor":1,"pai":"N","tsumogiri":true}"#,
+            r#"{"type":"tsumo","actor":2,"pai":"?"}"#,
+            r#"{"type":"dahai","actor":2,"pai":"W","tsumogiri":true}"#,
+            r#"{"type":"tsumo","actor":3,"pai":"?"}"#,
+            r#"{"type":"dahai","actor":3,"pai":"W","tsumogiri":true}"#,
+            r#"{"type":"tsumo","actor":0,"pai":"7s"}"#,
+            r#"{"type":"kakan","actor":0,"pai":"7s","consumed":["7s","7s","7s"]}"#,
+        ];
+
+        let mut robbed = referee(tehais, &calls);
+        let scored = hora(&mut robbed, r#"{"type":"hora","actor":1,"target":0}"#);
+        assert_eq!(
+            scored["yakus"],
+            json!([["chankan", 1], ["tanyaochu", 1], ["pinfu", 1], ["dora", 1]])
+        );
+        assert_eq!(scored["pai"], "7s");
+
+        let mut kept = referee(tehais, &calls);
+        for event in [
+            r#"{"type":"dora","dora_marker":"9s"}"#,
+            r#"{"type":"tsumo","actor":0,"pai":"E"}"#,
+        ] {
+            kept.event(&serde_json::from_str(event).unwrap()).unwrap();
+        }
+        let scored = hora(
+            &mut kept,
+            r#"{"type":"hora","actor":0,"target":0,"pai":"E"}"#,
+        );
+        assert_eq!(scored["yakus"], json!([["rinshankaiho", 1]]));
+        assert_eq!(scored["hora_points"], 2_100);
+    }
+
+    #[test]
+    fn yakuhai_by_wind() {
+        let mut referee = referee(
+            [
+                HIDDEN_TEHAI,
+                r#"["E","E","E","S","S","S","2p","3p","4p","6s","7s","8s","9m"]"#,
+            ],
+            &[
+                r#"{"type":"tsumo","actor":0,"pai":"?"}"#,
+                r#"{"type":"dahai","actor":0,"pai":"9m","tsumogiri":false}"#,
+            ],
+        );
+        let scored = hora(&mut referee, r#"{"type":"hora","actor":1,"target":0}"#);
+
+        assert_eq!(
+            scored["yakus"],
+            json!([["bakaze", 1], ["jikaze", 1], ["dora", 1]])
+        );
+        assert_eq!(scored["deltas"], json!([-6_700, 6_700, 0, 0]));
+    }
+
+    #[test]
+    fn houtei_from_the_discarder() {
+        let tehais = [
+            HIDDEN_TEHAI,
+            r#"["E","E","E","S","S","S","2p","3p","4p","6s","7s","8s","9m"]"#,
+        ];
+        let ron = r#"{"type":"hora","actor":1,"target":0}"#;
+
+        let mut last = referee(tehais, &[]);
+        last.wall = 1;
+        for event in [
+            r#"{"type":"tsumo","actor":0,"pai":"?"}"#,
+            r#"{"type":"dahai","actor":0,"pai":"9m","tsumogiri":true}"#,
+        ] {
+            last.event(&serde_json::from_str(event).unwrap()).unwrap();
+        }
+        assert_eq!(
+            hora(&mut last, ron)["yakus"],
+            json!([
+                ["houteiraoyui", 1],
+                ["bakaze", 1],
+                ["jikaze", 1],
+                ["dora", 1]
+            ])
+        );
+
+        // the discard after the replacement tile of a kan is not houtei
+        let mut after_kan = referee(tehais, &[]);
+        after_kan.wall = 2;
+        for event in [
+            r#"{"type":"tsumo","actor":0,"pai":"?"}"#,
+            r#"{"type":"ankan","actor":0,"consumed":["N","N","N","N"]}"#,
+            r#"{"type":"tsumo","actor":0,"pai":"?"}"#,
+            r#"{"type":"dahai","actor":0,"pai":"9m","tsumogiri":true}"#,
+        ] {
+            after_kan
+                .event(&serde_json::from_str(event).unwrap())
+                .unwrap();
+        }
+        assert_eq!(
+            hora(&mut after_kan, ron)["yakus"],
+            json!([["bakaze", 1], ["jikaze", 1], ["dora", 1]])
+        );
+    }
+
+    #[test]
+    fn double_ron_bonus_by_seat_order() {
+        let tehais = [
+            HIDDEN_TEHAI,
+            r#"["E","E","E","S","S","S","2p","3p","4p","6s","7s","8s","9m"]"#,
+        ];
+        let discard = [
+            r#"{"type":"tsumo","actor":0,"pai":"?"}"#,
+            r#"{"type":"dahai","actor":0,"pai":"9m","tsumogiri":false}"#,
+        ];
+        let near = r#"{"type":"hora","actor":1,"target":0}"#;
+        let far = r#"{"type":"hora","actor":2,"target":0,"pai":"9m",
+            "hora_tehais":["P","P","P","2p","3p","4p","5s","6s","7s","3m","4m","5m","9m"]}"#;
+
+        let mut in_order = referee(tehais, &discard);
+        assert_eq!(
+            hora(&mut in_order, near)["deltas"],
+            json!([-6_700, 6_700, 0, 0])
+        );
+        assert_eq!(
+            hora(&mut in_order, far)["deltas"],
+            json!([-2_600, 0, 2_600, 0])
+        );
+
+        // the nearer winner takes the honba back from the winner that came first
+        let mut reversed = referee(tehais, &discard);
+        assert_eq!(
+            hora(&mut reversed, far)["deltas"],
+            json!([-2_900, 0, 2_900, 0])
+        );
+        assert_eq!(
+            hora(&mut reversed, near)["deltas"],
+            json!([-6_400, 6_700, -300, 0])
+        );
+        assert_eq!(in_order.scores(), [15_700, 31_700, 27_600, 25_000]);
+        assert_eq!(reversed.scores(), in_order.scores());
+    }
+
+    #[test]
+    fn ryukyoku_between_hands() {
+        let mut referee = referee([HIDDEN_TEHAI, HIDDEN_TEHAI], &[]);
+        for event in [
+            r#"{"type":"ryukyoku","reason":"fanpai","tenpais":[true,false,true,false],
+            "deltas":[1500,-1500,1500,-1500]}"#,
+            r#"{"type":"end_kyoku"}"#,
+        ] {
+            referee
+                .event(&serde_json::from_str(event).unwrap())
+                .unwrap();
+        }
+        assert_eq!(referee.scores(), [26_500, 23_500, 26_500, 23_500]);
+
+        // the next hand starts from the scores after the draw
+        let start = format!(
+            r#"{{"type":"start_kyoku","bakaze":"E","kyoku":1,"honba":2,"kyotaku":0,"oya":0,
+            "dora_marker":"1p","tehais":[{HIDDEN_TEHAI},
+            ["E","E","E","S","S","S","2p","3p","4p","6s","7s","8s","9m"],
+            {HIDDEN_TEHAI},{HIDDEN_TEHAI}]}}"#
+        );
+        for event in [
+            start.as_str(),
+            r#"{"type":"tsumo","actor":0,"pai":"?"}"#,
+            r#"{"type":"dahai","actor":0,"pai":"9m","tsumogiri":false}"#,
+        ] {
+            referee
+                .event(&serde_json::from_str(event).unwrap())
+                .unwrap();
+        }
+        let scored = hora(&mut referee, r#"{"type":"hora","actor":1,"target":0}"#);
+        assert_eq!(scored["scores"], json!([19_500, 30_500, 26_500, 23_500]));
+
+        // scores on the draw are taken as they are
+        let ryukyoku = r#"{"type":"ryukyoku","deltas":[0,0,0,0],"scores":[1,2,3,4]}"#;
+        referee
+            .event(&serde_json::from_str(ryukyoku).unwrap())
+            .unwrap();
+        assert_eq!(referee.scores(), [1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn run_writes_hora_and_errors() {
+        let input = [
+            r#"{"type":"start_game","names":["a","b","c","d"]}"#,
+            r#"{"type":"tsumo","actor":0,"pai":"1x"}"#,
+            "not json",
+            r#"{"type":"end_game"}"#,
+        ]
+        .join("\n");
+        let mut output = vec![];
+        Referee::new().run(input.as_bytes(), &mut output).unwrap();
+
+        let error = r#"{"message":"Invalid Mjai Event given","type":"error"}"#;
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            format!("{error}\n{error}\n")
+        );
+    }
+}
@@ -1,8 +1,14 @@
+use crate::hand::error::HandErr;
 use crate::score::{FuValue, HanValue, HonbaCounter};
 
 /// Number of points players pay to the winner.
-// NOTE: `u64` allows for scoring with aotenjou (no limits).
-pub(crate) type Points = u64;
+// NOTE: Aotenjou (no limits) scores can overflow `u64`, so they use `aotenjou::BigPoints`.
+pub type Points = u64;
+/// A player's point total or change in points, which can drop below zero.
+pub type PlayerPoints = i64;
+
+/// Number of points a riichi declaration puts on the table.
+pub const RIICHI_STICK: Points = 1_000;
 
 const DEALER_RON_MULTIPLIER: u64 = 6;
 const DEALER_TSUMO_MULTIPLIER: u64 = 2;
@@ -82,12 +88,25 @@ const NON_DEALER_TSUMO_TO_DEALER_MULTIPLIER: u64 = 2;
 /// let expected_dealer_tsumo = 4_200;
 /// assert_eq!(payment.dealer_tsumo(honba), expected_dealer_tsumo);
 /// ```
-#[derive(Debug)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Payment {
     /// Base score for the hand.
     base_points: Points,
     /// The number of points each honba (repeat counter) is worth.
     tsumibou: Points,
+    /// How a tsumo is paid in a three-player game, if the game is sanma.
+    sanma: Option<SanmaTsumo>,
+}
+
+/// How the share of the missing fourth player is paid when someone wins by tsumo in sanma.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SanmaTsumo {
+    /// Nobody pays the missing share, so a tsumo is worth less than a ron.
+    TsumoLoss,
+    /// The two players that pay split the missing share between them.
+    NorthBisection,
 }
 
 impl Payment {
@@ -96,6 +115,56 @@ impl Payment {
         Self {
             base_points,
             tsumibou: 300,
+            sanma: None,
+        }
+    }
+
+    /// Change the base score, keeping how the payment is paid out.
+    pub(crate) fn set_base_points(&mut self, base_points: Points) {
+        self.base_points = base_points;
+    }
+
+    /// Pay out as a three-player (sanma) game, where each honba is worth `tsumibou` points.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use mahc::payment::{Payment, SanmaTsumo};
+    ///
+    /// let mut payment = Payment::new(2_000);
+    /// payment.set_sanma(SanmaTsumo::NorthBisection, 1_000);
+    ///
+    /// // the missing 2,000 of a mangan tsumo is split between the two other players
+    /// assert_eq!(payment.dealer_tsumo(1), 6_500);
+    /// assert_eq!(payment.non_dealer_tsumo_to_dealer(0), 5_000);
+    /// assert_eq!(payment.non_dealer_tsumo_to_non_dealer(0), 3_000);
+    /// assert_eq!(payment.non_dealer_ron(1), 9_000);
+    /// ```
+    pub fn set_sanma(&mut self, tsumo: SanmaTsumo, tsumibou: Points) {
+        self.sanma = Some(tsumo);
+        self.tsumibou = tsumibou;
+    }
+
+    /// Check if the payment is for a three-player (sanma) game.
+    pub fn is_sanma(&self) -> bool {
+        self.sanma.is_some()
+    }
+
+    /// Get the number of players that pay when someone wins by tsumo.
+    fn tsumo_payers(&self) -> Points {
+        if self.is_sanma() {
+            2
+        } else {
+            3
+        }
+    }
+
+    /// Get the unrounded part of the missing player's share each player pays on a tsumo, as the
+    /// multiplier of the base points the missing player would have paid.
+    fn bisection(&self, missing_multiplier: Points) -> Points {
+        match self.sanma {
+            Some(SanmaTsumo::NorthBisection) => self.base_points * missing_multiplier / 2,
+            _ => 0,
         }
     }
 
@@ -124,7 +193,7 @@ impl Payment {
             fu
         } else {
             // Round up to the nearest 10.
-            (fu + 9) / 10 * 10
+            fu.div_ceil(10) * 10
         };
 
         Self::new(fu * 2u64.pow(han + 2))
@@ -137,7 +206,7 @@ impl Payment {
 
     /// Round the payment amount to the nearest hundredth.
     fn round_payment(&self, unrounded_payment: Points) -> Points {
-        (unrounded_payment + 99) / 100 * 100
+        unrounded_payment.div_ceil(100) * 100
     }
 
     /// Get the amount of points the player that dealt-in has to pay to a dealer.
@@ -147,9 +216,11 @@ impl Payment {
 
     /// Get the amount of points each player pays when the dealer tsumos.
     pub fn dealer_tsumo(&self, honba: HonbaCounter) -> Points {
-        // NOTE: This is assuming a 4-player game where the tsumibou is divided by the number of other players.
+        // NOTE: The tsumibou is divided by the number of other players.
         self.round_payment(
-            (self.base_points * DEALER_TSUMO_MULTIPLIER) + ((self.tsumibou / 3) * honba),
+            (self.base_points * DEALER_TSUMO_MULTIPLIER)
+                + self.bisection(DEALER_TSUMO_MULTIPLIER)
+                + ((self.tsumibou / self.tsumo_payers()) * honba),
         )
     }
 
@@ -160,19 +231,94 @@ impl Payment {
 
     /// Get the amount the dealer pays when a non-dealer wins by tsumo.
     pub fn non_dealer_tsumo_to_dealer(&self, honba: HonbaCounter) -> Points {
-        // NOTE: This is assuming a 4-player game where the tsumibou is divided by the number of other players.
+        // NOTE: The tsumibou is divided by the number of other players.
         self.round_payment(
             (self.base_points * NON_DEALER_TSUMO_TO_DEALER_MULTIPLIER)
-                + ((self.tsumibou / 3) * honba),
+                + self.bisection(NON_DEALER_TSUMO_TO_NON_DEALER_MULTIPLIER)
+                + ((self.tsumibou / self.tsumo_payers()) * honba),
         )
     }
 
+    /// Get the total amount of points the winner receives from the other players.
+    pub fn total(&self, dealer: bool, tsumo: bool, honba: HonbaCounter) -> Points {
+        match (dealer, tsumo) {
+            (true, false) => self.dealer_ron(honba),
+            (true, true) => self.dealer_tsumo(honba) * self.tsumo_payers(),
+            (false, false) => self.non_dealer_ron(honba),
+            (false, true) => {
+                self.non_dealer_tsumo_to_dealer(honba)
+                    + self.non_dealer_tsumo_to_non_dealer(honba) * (self.tsumo_payers() - 1)
+            }
+        }
+    }
+
     /// Get the amount non-dealer players pay when a non-dealer wins by tsumo.
     pub fn non_dealer_tsumo_to_non_dealer(&self, honba: HonbaCounter) -> Points {
-        // NOTE: This is assuming a 4-player game where the tsumibou is divided by the number of other players.
+        // NOTE: The tsumibou is divided by the number of other players.
         self.round_payment(
             (self.base_points * NON_DEALER_TSUMO_TO_NON_DEALER_MULTIPLIER)
-                + ((self.tsumibou / 3) * honba),
+                + self.bisection(NON_DEALER_TSUMO_TO_NON_DEALER_MULTIPLIER)
+                + ((self.tsumibou / self.tsumo_payers()) * honba),
         )
     }
 }
+
+/// A payment as it is called out at the table or written on a scoresheet.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ClaimedPayment {
+    /// Points paid by the player that dealt-in (ex: `3900`).
+    Ron(Points),
+    /// Points paid by each player when the dealer tsumos (ex: `2600all`).
+    DealerTsumo(Points),
+    /// Points paid by the non-dealers and the dealer when a non-dealer tsumos (ex: `1300-2600`).
+    NonDealerTsumo { non_dealer: Points, dealer: Points },
+}
+
+impl std::fmt::Display for ClaimedPayment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Ron(points) => write!(f, "{points}"),
+            Self::DealerTsumo(points) => write!(f, "{points}all"),
+            Self::NonDealerTsumo { non_dealer, dealer } => write!(f, "{non_dealer}-{dealer}"),
+        }
+    }
+}
+
+impl TryFrom<String> for ClaimedPayment {
+    type Error = HandErr;
+
+    /// Parse the payment from the string.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use mahc::payment::ClaimedPayment;
+    ///
+    /// let actual: ClaimedPayment = "1300-2600".to_string().try_into().unwrap();
+    /// let expected = ClaimedPayment::NonDealerTsumo {
+    ///     non_dealer: 1_300,
+    ///     dealer: 2_600,
+    /// };
+    ///
+    /// assert_eq!(actual, expected);
+    /// ```
+    fn try_from(payment: String) -> Result<Self, Self::Error> {
+        let parse = |points: &str| -> Result<Points, HandErr> {
+            points.trim().parse().map_err(|_| HandErr::InvalidPayment)
+        };
+
+        if let Some(each) = payment.strip_suffix("all") {
+            return Ok(Self::DealerTsumo(parse(each)?));
+        }
+
+        if let Some((non_dealer, dealer)) = payment.split_once('-') {
+            return Ok(Self::NonDealerTsumo {
+                non_dealer: parse(non_dealer)?,
+                dealer: parse(dealer)?,
+            });
+        }
+
+        Ok(Self::Ron(parse(&payment)?))
+    }
+}
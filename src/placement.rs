@@ -0,0 +1,208 @@
+use crate::hand::error::HandErr;
+use crate::payment::PlayerPoints;
+
+/// Points added to each placement, from first to last.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Uma(pub [PlayerPoints; 4]);
+
+impl TryFrom<String> for Uma {
+    type Error = HandErr;
+
+    /// Parse the uma in thousands of points, from first to last place.
+    ///
+    /// The uma has to add up to zero.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use mahc::placement::Uma;
+    ///
+    /// let actual: Uma = "30/10/-10/-30".to_string().try_into().unwrap();
+    /// assert_eq!(actual, Uma([30_000, 10_000, -10_000, -30_000]));
+    ///
+    /// let actual = Uma::try_from("15/5/-5".to_string());
+    /// assert!(actual.is_err());
+    /// ```
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let uma: Vec<PlayerPoints> = value
+            .split('/')
+            .map(|part| part.trim().parse::<PlayerPoints>().map(|uma| uma * 1_000))
+            .collect::<Result<_, _>>()
+            .map_err(|_| HandErr::InvalidUma)?;
+        let uma: [PlayerPoints; 4] = uma.try_into().map_err(|_| HandErr::InvalidUma)?;
+
+        if uma.iter().sum::<PlayerPoints>() != 0 {
+            return Err(HandErr::InvalidUma);
+        }
+
+        Ok(Self(uma))
+    }
+}
+
+impl std::fmt::Display for Uma {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let uma: Vec<String> = self.0.iter().map(|uma| (uma / 1_000).to_string()).collect();
+        write!(f, "{}", uma.join("/"))
+    }
+}
+
+/// How players with the same final score are placed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TieRule {
+    /// Share the uma and oka of the places they cover.
+    Split,
+    /// The player seated closest to the first dealer places higher.
+    SeatOrder,
+}
+
+/// League rules for turning final scores into placement results.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlacementRules {
+    /// Points each player started with.
+    pub starting_points: PlayerPoints,
+    /// Points taken off every final score (the return point).
+    pub return_points: PlayerPoints,
+    /// Points added to each placement.
+    pub uma: Uma,
+    /// How ties are placed.
+    pub ties: TieRule,
+}
+
+impl Default for PlacementRules {
+    fn default() -> Self {
+        Self {
+            starting_points: 25_000,
+            return_points: 30_000,
+            uma: Uma([15_000, 5_000, -5_000, -15_000]),
+            ties: TieRule::SeatOrder,
+        }
+    }
+}
+
+impl PlacementRules {
+    /// Get the oka, the difference between the return point and the starting points of every
+    /// player, which goes to first place.
+    pub fn oka(&self) -> PlayerPoints {
+        4 * (self.return_points - self.starting_points)
+    }
+}
+
+/// The placement and result of a player.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Placement {
+    /// Player, numbered from `0` in seating order starting from the first dealer.
+    pub player: usize,
+    /// Place from `1` to `4`. Split ties share the highest place they cover.
+    pub place: usize,
+    /// Final score.
+    pub score: PlayerPoints,
+    /// Result in thousands of points, after the return point, uma, and oka.
+    pub result: f64,
+}
+
+/// Turn the final scores of each player into placement results, from first to last place.
+///
+/// # Examples
+///
+/// ```rust
+/// use mahc::placement::{placements, PlacementRules};
+///
+/// let results = placements(&[42_300, 23_900, 30_600, 3_200], &PlacementRules::default());
+///
+/// assert_eq!(results[0].player, 0);
+/// assert_eq!(results[0].result, 47.3);
+/// assert_eq!(results[1].result, 5.6);
+/// assert_eq!(results[2].result, -11.1);
+/// assert_eq!(results[3].result, -41.8);
+/// ```
+pub fn placements(scores: &[PlayerPoints; 4], rules: &PlacementRules) -> Vec<Placement> {
+    let mut players = [0, 1, 2, 3];
+    players.sort_by_key(|&player| (std::cmp::Reverse(scores[player]), player));
+
+    let mut bonus = rules.uma.0;
+    bonus[0] += rules.oka();
+
+    let mut results: Vec<Placement> = vec![];
+    let mut place = 0;
+    while place < 4 {
+        let score = scores[players[place]];
+        let tied = match rules.ties {
+            TieRule::Split => players[place..]
+                .iter()
+                .take_while(|&&player| scores[player] == score)
+                .count(),
+            TieRule::SeatOrder => 1,
+        };
+
+        let shared = bonus[place..place + tied].iter().sum::<PlayerPoints>() as f64 / tied as f64;
+        for &player in &players[place..place + tied] {
+            results.push(Placement {
+                player,
+                place: place + 1,
+                score,
+                result: ((score - rules.return_points) as f64 + shared) / 1_000.0,
+            });
+        }
+        place += tied;
+    }
+
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn placement_seat_order_ties() {
+        let rules = PlacementRules {
+            uma: Uma([30_000, 10_000, -10_000, -30_000]),
+            ..PlacementRules::default()
+        };
+        let results = placements(&[20_000, 40_000, 20_000, 20_000], &rules);
+
+        let players: Vec<usize> = results.iter().map(|result| result.player).collect();
+        assert_eq!(players, [1, 0, 2, 3]);
+        let places: Vec<usize> = results.iter().map(|result| result.place).collect();
+        assert_eq!(places, [1, 2, 3, 4]);
+        let points: Vec<f64> = results.iter().map(|result| result.result).collect();
+        assert_eq!(points, [60.0, 0.0, -20.0, -40.0]);
+    }
+
+    #[test]
+    fn placement_split_ties() {
+        let rules = PlacementRules {
+            ties: TieRule::Split,
+            ..PlacementRules::default()
+        };
+        let results = placements(&[35_000, 35_000, 20_000, 10_000], &rules);
+
+        assert_eq!(results[0].place, 1);
+        assert_eq!(results[1].place, 1);
+        // first and second uma plus the oka, shared between two
+        assert_eq!(results[0].result, 25.0);
+        assert_eq!(results[1].result, 25.0);
+        assert_eq!(results[2].result, -15.0);
+        assert_eq!(results[3].result, -35.0);
+    }
+
+    #[test]
+    fn placement_results_add_up() {
+        let rules = PlacementRules {
+            ties: TieRule::Split,
+            ..PlacementRules::default()
+        };
+        let results = placements(&[25_000, 25_000, 25_000, 25_000], &rules);
+        let total: f64 = results.iter().map(|result| result.result).sum();
+        assert!(total.abs() < 1e-9);
+        assert!(results.iter().all(|result| result.place == 1));
+    }
+
+    #[test]
+    fn uma_invalid() {
+        let uma = Uma::try_from("15/5/-5/-10".to_string());
+        assert_eq!(uma.unwrap_err(), HandErr::InvalidUma);
+        let uma = Uma::try_from("a/b/c/d".to_string());
+        assert_eq!(uma.unwrap_err(), HandErr::InvalidUma);
+    }
+}
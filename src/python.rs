@@ -0,0 +1,368 @@
+//! Python module, built with maturin and the `python` feature.
+//!
+//! [`Yaku`] and [`Fu`] are Python classes of their own (ex: `mahc.Yaku.Riichi`), and errors are
+//! raised as `mahc.MahcError` with the code of the [`HandErr`] (ex: `"no_yaku"`).
+
+use pyo3::create_exception;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use crate::calc::{self, YonmaRules};
+use crate::fu::Fu;
+use crate::hand::error::HandErr;
+use crate::hand::Hand;
+use crate::payment::{Payment, Points};
+use crate::score::{FuValue, HanValue, HonbaCounter, Score};
+use crate::tile_group::TileGroup;
+use crate::yaku::Yaku;
+
+create_exception!(
+    mahc,
+    MahcError,
+    PyValueError,
+    "An error of the scorer, with its `code`."
+);
+
+fn error(err: HandErr) -> PyErr {
+    let py_err = MahcError::new_err(err.to_string());
+    Python::with_gil(|py| {
+        // the message is kept even if the code cannot be set
+        let _ = py_err.value(py).setattr("code", err.code());
+    });
+    py_err
+}
+
+/// A tile or group of tiles, parsed from notation (ex: `TileGroup("123po")`).
+#[pyclass(name = "TileGroup", module = "mahc", frozen, eq)]
+#[derive(Clone, PartialEq)]
+pub struct PyTileGroup(TileGroup);
+
+#[pymethods]
+impl PyTileGroup {
+    #[new]
+    fn new(notation: String) -> PyResult<Self> {
+        TileGroup::try_from(notation).map(Self).map_err(error)
+    }
+
+    #[getter]
+    fn value(&self) -> &str {
+        &self.0.value
+    }
+
+    #[getter]
+    fn suit(&self) -> String {
+        format!("{:?}", self.0.suit)
+    }
+
+    #[getter]
+    fn group_type(&self) -> String {
+        format!("{:?}", self.0.group_type)
+    }
+
+    #[getter]
+    fn is_open(&self) -> bool {
+        self.0.isopen
+    }
+
+    #[getter]
+    fn is_terminal(&self) -> bool {
+        self.0.isterminal
+    }
+
+    #[getter]
+    fn is_aka(&self) -> bool {
+        self.0.isaka
+    }
+
+    /// Split the group into its single tiles.
+    fn tiles(&self) -> Vec<Self> {
+        self.0.tiles().into_iter().map(Self).collect()
+    }
+
+    fn __str__(&self) -> String {
+        self.0.to_string()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("TileGroup('{}')", self.0)
+    }
+}
+
+/// A winning hand, with the winning group last.
+#[pyclass(name = "Hand", module = "mahc", frozen)]
+#[derive(Clone)]
+pub struct PyHand(Hand);
+
+#[pymethods]
+impl PyHand {
+    #[new]
+    #[pyo3(signature = (tiles, win, seat = "Ew".to_string(), prev = "Ew".to_string(), nukidora = 0))]
+    fn new(
+        tiles: Vec<String>,
+        win: String,
+        seat: String,
+        prev: String,
+        nukidora: u8,
+    ) -> PyResult<Self> {
+        Hand::new_from_strings(tiles, win, prev, seat)
+            .and_then(|hand| hand.with_nukidora(nukidora))
+            .map(Self)
+            .map_err(error)
+    }
+
+    #[getter]
+    fn groups(&self) -> Vec<PyTileGroup> {
+        self.0.groups().iter().cloned().map(PyTileGroup).collect()
+    }
+
+    #[getter]
+    fn win_tile(&self) -> PyTileGroup {
+        PyTileGroup(self.0.win_tile())
+    }
+
+    #[getter]
+    fn seat_tile(&self) -> PyTileGroup {
+        PyTileGroup(self.0.seat_tile())
+    }
+
+    #[getter]
+    fn prev_tile(&self) -> PyTileGroup {
+        PyTileGroup(self.0.prev_tile())
+    }
+
+    #[getter]
+    fn is_open(&self) -> bool {
+        self.0.is_open()
+    }
+
+    #[getter]
+    fn nukidora(&self) -> u8 {
+        self.0.nukidora()
+    }
+
+    /// How the winning tile completed the hand (ex: `"ryanmen"`), if it could be told.
+    #[getter]
+    fn wait(&self) -> Option<String> {
+        self.0.wait().map(|wait| wait.to_string().to_lowercase())
+    }
+
+    /// Calculate the fu types in the hand.
+    #[pyo3(signature = (tsumo = false))]
+    fn calculate_fu(&self, tsumo: bool) -> Vec<Fu> {
+        self.0.calculate_fu(tsumo)
+    }
+
+    fn __repr__(&self) -> String {
+        let groups: Vec<String> = self.0.groups().iter().map(|g| format!("'{g}'")).collect();
+        format!(
+            "Hand([{}], '{}', seat='{}', prev='{}')",
+            groups.join(", "),
+            self.0.win_tile(),
+            self.0.seat_tile(),
+            self.0.prev_tile()
+        )
+    }
+}
+
+/// Payment amounts of a hand, before honba unless one is given.
+#[pyclass(name = "Payment", module = "mahc", frozen)]
+#[derive(Clone)]
+pub struct PyPayment(Payment);
+
+#[pymethods]
+impl PyPayment {
+    #[getter]
+    fn base_points(&self) -> Points {
+        self.0.base_points()
+    }
+
+    #[pyo3(signature = (honba = 0))]
+    fn dealer_ron(&self, honba: HonbaCounter) -> Points {
+        self.0.dealer_ron(honba)
+    }
+
+    /// Paid by each non-dealer.
+    #[pyo3(signature = (honba = 0))]
+    fn dealer_tsumo(&self, honba: HonbaCounter) -> Points {
+        self.0.dealer_tsumo(honba)
+    }
+
+    #[pyo3(signature = (honba = 0))]
+    fn non_dealer_ron(&self, honba: HonbaCounter) -> Points {
+        self.0.non_dealer_ron(honba)
+    }
+
+    #[pyo3(signature = (honba = 0))]
+    fn non_dealer_tsumo_to_dealer(&self, honba: HonbaCounter) -> Points {
+        self.0.non_dealer_tsumo_to_dealer(honba)
+    }
+
+    #[pyo3(signature = (honba = 0))]
+    fn non_dealer_tsumo_to_non_dealer(&self, honba: HonbaCounter) -> Points {
+        self.0.non_dealer_tsumo_to_non_dealer(honba)
+    }
+}
+
+/// The score of a hand.
+#[pyclass(name = "Score", module = "mahc", frozen)]
+#[derive(Clone)]
+pub struct PyScore(Score);
+
+#[pymethods]
+impl PyScore {
+    #[getter]
+    fn han(&self) -> HanValue {
+        self.0.han()
+    }
+
+    #[getter]
+    fn fu(&self) -> FuValue {
+        self.0.fu_score()
+    }
+
+    #[getter]
+    fn honba(&self) -> HonbaCounter {
+        self.0.honba()
+    }
+
+    #[getter]
+    fn is_open(&self) -> bool {
+        self.0.is_open()
+    }
+
+    #[getter]
+    fn dora(&self) -> u32 {
+        self.0.dora_count()
+    }
+
+    #[getter]
+    fn yaku(&self) -> Vec<Yaku> {
+        self.0.yaku().to_vec()
+    }
+
+    #[getter]
+    fn fu_breakdown(&self) -> Vec<Fu> {
+        self.0.fu().to_vec()
+    }
+
+    #[getter]
+    fn payment(&self) -> PyPayment {
+        PyPayment(self.0.payment().clone())
+    }
+}
+
+#[pymethods]
+impl Yaku {
+    /// Id of the yaku, which does not change between versions (ex: `"sanshoku_doujun"`).
+    #[getter(id)]
+    fn py_id(&self) -> &'static str {
+        self.id()
+    }
+
+    #[getter(name)]
+    fn py_name(&self) -> &'static str {
+        self.name()
+    }
+
+    #[getter(is_yakuman)]
+    fn py_is_yakuman(&self) -> bool {
+        self.is_yakuman()
+    }
+
+    /// Han of the yaku, which some yaku lose when the hand is open.
+    #[pyo3(name = "han", signature = (is_open = false))]
+    fn py_han(&self, is_open: bool) -> HanValue {
+        self.get_han(is_open)
+    }
+}
+
+#[pymethods]
+impl Fu {
+    /// Id of the fu, which does not change between versions (ex: `"closed_ron"`).
+    #[getter(id)]
+    fn py_id(&self) -> &'static str {
+        self.id()
+    }
+
+    #[getter(name)]
+    fn py_name(&self) -> &'static str {
+        self.name()
+    }
+
+    #[getter(value)]
+    fn py_value(&self) -> FuValue {
+        self.value()
+    }
+}
+
+/// Get the score of the hand, given the dora indicators.
+///
+/// `nukidora` lets a four-player hand set aside north tiles, and `nuki_rinshan` lets the draw
+/// after one count for rinshan kaihou.
+#[pyfunction]
+#[pyo3(signature = (
+    hand, dora = None, tsumo = false, riichi = false, double_riichi = false, ippatsu = false,
+    haitei = false, rinshan = false, chankan = false, tenhou = false, honba = 0,
+    nukidora = false, nuki_rinshan = false
+))]
+#[allow(clippy::too_many_arguments)]
+fn get_hand_score(
+    hand: &PyHand,
+    dora: Option<Vec<String>>,
+    tsumo: bool,
+    riichi: bool,
+    double_riichi: bool,
+    ippatsu: bool,
+    haitei: bool,
+    rinshan: bool,
+    chankan: bool,
+    tenhou: bool,
+    honba: HonbaCounter,
+    nukidora: bool,
+    nuki_rinshan: bool,
+) -> PyResult<PyScore> {
+    let dora = dora
+        .map(|dora| dora.into_iter().map(TileGroup::try_from).collect())
+        .transpose()
+        .map_err(error)?;
+    let rules = YonmaRules {
+        nukidora,
+        nuki_rinshan,
+    };
+    rules
+        .get_hand_score(
+            hand.0.clone(),
+            dora,
+            tsumo,
+            riichi,
+            double_riichi,
+            ippatsu,
+            haitei,
+            rinshan,
+            chankan,
+            tenhou,
+            honba,
+        )
+        .map(PyScore)
+        .map_err(error)
+}
+
+/// Calculate the payment of a han and fu.
+#[pyfunction]
+fn calculate(han: HanValue, fu: FuValue) -> PyResult<PyPayment> {
+    calc::calculate(han, fu).map(PyPayment).map_err(error)
+}
+
+#[pymodule]
+fn mahc(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add("MahcError", m.py().get_type::<MahcError>())?;
+    m.add_class::<PyTileGroup>()?;
+    m.add_class::<PyHand>()?;
+    m.add_class::<PyPayment>()?;
+    m.add_class::<PyScore>()?;
+    m.add_class::<Yaku>()?;
+    m.add_class::<Fu>()?;
+    m.add_function(wrap_pyfunction!(get_hand_score, m)?)?;
+    m.add_function(wrap_pyfunction!(calculate, m)?)?;
+    Ok(())
+}
@@ -0,0 +1,428 @@
+use crate::hand::error::HandErr;
+use crate::hand::Hand;
+use crate::suit::Suit;
+use crate::tile_group::{GroupType, TileGroup};
+
+/// Output format of a hand diagram.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RenderFormat {
+    /// A self-contained SVG image.
+    Svg,
+    /// Monospace ASCII art.
+    Ascii,
+}
+
+impl TryFrom<String> for RenderFormat {
+    type Error = HandErr;
+
+    /// Parse the format from its name.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use mahc::render::RenderFormat;
+    ///
+    /// let actual: RenderFormat = "svg".to_string().try_into().unwrap();
+    /// assert_eq!(actual, RenderFormat::Svg);
+    ///
+    /// assert!(RenderFormat::try_from("png".to_string()).is_err());
+    /// ```
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        match value.to_lowercase().as_str() {
+            "svg" => Ok(Self::Svg),
+            "ascii" | "txt" | "text" => Ok(Self::Ascii),
+            _ => Err(HandErr::InvalidRenderFormat),
+        }
+    }
+}
+
+/// A single tile as it is drawn.
+#[derive(Debug, Clone, PartialEq)]
+struct Face {
+    tile: TileGroup,
+    /// Turned sideways, for the called tile of a meld.
+    rotated: bool,
+    /// Upside down, for the outer tiles of a closed kan.
+    hidden: bool,
+}
+
+impl Face {
+    fn new(tile: TileGroup) -> Self {
+        Self {
+            tile,
+            rotated: false,
+            hidden: false,
+        }
+    }
+}
+
+/// Split the hand into the groups that are drawn: the closed tiles, every called meld, and
+/// the winning tile on its own.
+///
+/// The first tile of a called meld is the one drawn sideways, as the player it was called
+/// from is not known.
+fn layout(hand: &Hand) -> Vec<Vec<Face>> {
+    let win = hand.win_tile();
+    let (last, rest) = hand
+        .groups()
+        .split_last()
+        .expect("a hand always has groups");
+
+    let mut last_tiles = last.tiles();
+    let win_at = last_tiles
+        .iter()
+        .position(|tile| tile.is_same_tile(&win) && tile.isaka == win.isaka)
+        .or_else(|| last_tiles.iter().position(|tile| tile.is_same_tile(&win)));
+    if let Some(i) = win_at {
+        last_tiles.remove(i);
+    }
+
+    let mut closed: Vec<Face> = vec![];
+    let mut melds: Vec<Vec<Face>> = vec![];
+    for group in rest.iter().chain(std::iter::once(last)) {
+        let tiles = if group == last {
+            last_tiles.clone()
+        } else {
+            group.tiles()
+        };
+        let mut faces: Vec<Face> = tiles.into_iter().map(Face::new).collect();
+
+        if group.isopen {
+            if let Some(called) = faces.first_mut() {
+                called.rotated = true;
+            }
+            melds.push(faces);
+        } else if group.group_type == GroupType::Kan {
+            faces[0].hidden = true;
+            faces[3].hidden = true;
+            melds.push(faces);
+        } else {
+            closed.extend(faces);
+        }
+    }
+
+    let mut win = Face::new(win);
+    win.tile.group_type = GroupType::None;
+
+    let mut groups = vec![closed];
+    groups.extend(melds);
+    groups.push(vec![win]);
+    groups
+}
+
+/// Draw the hand and its dora indicators in the format.
+pub fn render(hand: &Hand, dora: &[TileGroup], format: RenderFormat) -> String {
+    match format {
+        RenderFormat::Svg => svg(hand, dora),
+        RenderFormat::Ascii => ascii(hand, dora),
+    }
+}
+
+/// The label of a tile in notation, with akadora as `0`.
+fn label(tile: &TileGroup) -> String {
+    let suit = match tile.suit {
+        Suit::Manzu => "m",
+        Suit::Pinzu => "p",
+        Suit::Souzu => "s",
+        Suit::Wind => "w",
+        Suit::Dragon => "d",
+    };
+    let value = if tile.isaka { "0" } else { tile.value.as_str() };
+    format!("{value}{suit}")
+}
+
+/// The insides of the four rows of a tile in ASCII art, without its sides.
+///
+/// Upright tiles are 2 columns wide, sideways tiles are 4 columns wide and one row shorter.
+fn ascii_inside(face: &Face) -> [String; 4] {
+    let label = label(&face.tile);
+    if face.rotated {
+        [
+            " ".repeat(4),
+            "----".to_string(),
+            format!(" {label} "),
+            "----".to_string(),
+        ]
+    } else if face.hidden {
+        ["--", "##", "##", "--"].map(String::from)
+    } else {
+        ["--".to_string(), label, "  ".to_string(), "--".to_string()]
+    }
+}
+
+/// The sides of the four rows of a tile in ASCII art.
+fn ascii_side(face: Option<&Face>) -> [char; 4] {
+    match face {
+        Some(face) if face.rotated => [' ', '+', '|', '+'],
+        Some(_) => ['+', '|', '|', '+'],
+        None => [' '; 4],
+    }
+}
+
+/// Draw groups of tiles side by side, with tiles of a group sharing their sides.
+fn ascii_rows(groups: &[Vec<Face>], gaps: &[&str]) -> [String; 4] {
+    // a corner wins over a side, which wins over nothing
+    let rank = |c: char| [' ', '|', '+'].iter().position(|&r| r == c);
+
+    let mut rows: [String; 4] = Default::default();
+    for (i, group) in groups.iter().enumerate() {
+        for (row, out) in rows.iter_mut().enumerate() {
+            if i > 0 {
+                out.push_str(gaps[(i == groups.len() - 1) as usize]);
+            }
+            out.push(ascii_side(group.first())[row]);
+            for (j, face) in group.iter().enumerate() {
+                out.push_str(&ascii_inside(face)[row]);
+                let left = ascii_side(Some(face))[row];
+                let right = ascii_side(group.get(j + 1))[row];
+                out.push(if rank(left) >= rank(right) {
+                    left
+                } else {
+                    right
+                });
+            }
+        }
+    }
+    rows.map(|row| row.trim_end().to_string())
+}
+
+/// Draw the hand as monospace ASCII art.
+///
+/// Called melds are drawn apart from the closed tiles with their called tile sideways, and
+/// the winning tile is drawn last, further apart. Dora indicators are drawn underneath.
+///
+/// # Examples
+///
+/// ```rust
+/// use mahc::hand::Hand;
+/// use mahc::render;
+///
+/// let tiles = ["123p", "456p", "789m", "555so", "99s"].map(|t| t.to_string()).to_vec();
+/// let hand = Hand::new_from_strings(tiles, "9s".into(), "Ew".into(), "Ew".into()).unwrap();
+/// let dora = vec!["4p".to_string().try_into().unwrap()];
+///
+/// let expected = "\
+/// +--+--+--+--+--+--+--+--+--+--+      +--+--+   +--+
+/// |1p|2p|3p|4p|5p|6p|7m|8m|9m|9s| +----+5s|5s|   |9s|
+/// |  |  |  |  |  |  |  |  |  |  | | 5s |  |  |   |  |
+/// +--+--+--+--+--+--+--+--+--+--+ +----+--+--+   +--+
+/// Dora
+/// +--+
+/// |4p|
+/// |  |
+/// +--+";
+/// assert_eq!(render::ascii(&hand, &dora), expected);
+/// ```
+pub fn ascii(hand: &Hand, dora: &[TileGroup]) -> String {
+    let groups = layout(hand);
+    let mut out = ascii_rows(&groups, &[" ", "   "]).join("\n");
+
+    if !dora.is_empty() {
+        let dora: Vec<Face> = dora.iter().cloned().map(Face::new).collect();
+        out.push_str("\nDora\n");
+        out.push_str(&ascii_rows(&[dora], &[" ", " "]).join("\n"));
+    }
+
+    out
+}
+
+const TILE_WIDTH: u32 = 30;
+const TILE_HEIGHT: u32 = 40;
+const GROUP_GAP: u32 = 8;
+const WIN_GAP: u32 = 20;
+const MARGIN: u32 = 10;
+
+/// The face of a tile, drawn in a 30x40 box from the origin.
+fn svg_face(tile: &TileGroup) -> String {
+    let number_colour = if tile.isaka { "#c0392b" } else { "#222" };
+    let number = |mark: &str| {
+        format!(
+            r##"<text x="15" y="18" font-size="16" text-anchor="middle" fill="{number_colour}">{}</text>{mark}"##,
+            tile.value
+        )
+    };
+    let kanji = |text: &str, colour: &str| {
+        format!(
+            r##"<text x="15" y="27" font-size="20" text-anchor="middle" fill="{colour}">{text}</text>"##
+        )
+    };
+
+    match tile.suit {
+        Suit::Manzu => number(&format!(
+            r##"<text x="15" y="35" font-size="11" text-anchor="middle" fill="#c0392b">{}</text>"##,
+            "萬"
+        )),
+        Suit::Pinzu => number(
+            r##"<circle cx="15" cy="30" r="6" fill="none" stroke="#1f618d" stroke-width="2"/><circle cx="15" cy="30" r="2" fill="#1f618d"/>"##,
+        ),
+        Suit::Souzu => number(
+            r##"<rect x="13" y="23" width="4" height="13" rx="2" fill="#1e8449"/><line x1="13" y1="29.5" x2="17" y2="29.5" stroke="#fff"/>"##,
+        ),
+        Suit::Wind => {
+            let wind = match tile.value.as_str() {
+                "E" => "東",
+                "S" => "南",
+                "W" => "西",
+                _ => "北",
+            };
+            kanji(wind, "#222")
+        }
+        Suit::Dragon => match tile.value.as_str() {
+            "g" => kanji("發", "#1e8449"),
+            "r" => kanji("中", "#c0392b"),
+            _ => r##"<rect x="7" y="8" width="16" height="24" fill="none" stroke="#1f618d" stroke-width="2"/>"##
+                .to_string(),
+        },
+    }
+}
+
+/// A tile with its top-left corner at (x, y) of the row it sits in.
+fn svg_tile(face: &Face, x: u32, y: u32) -> String {
+    let body = if face.hidden {
+        r##"<rect width="30" height="40" rx="3" fill="#d35400" stroke="#333"/>"##.to_string()
+    } else {
+        format!(
+            r##"<rect width="30" height="40" rx="3" fill="#fdfefe" stroke="#333"/>{}"##,
+            svg_face(&face.tile)
+        )
+    };
+    let transform = if face.rotated {
+        // turn the tile over its right side, so it lies on the bottom of the row
+        format!(
+            "translate({},{}) rotate(90)",
+            x + TILE_HEIGHT,
+            y + TILE_HEIGHT - TILE_WIDTH
+        )
+    } else {
+        format!("translate({x},{y})")
+    };
+    format!(
+        r#"<g transform="{transform}"><title>{}</title>{body}</g>"#,
+        label(&face.tile)
+    )
+}
+
+/// Draw a row of groups, returning the drawing and its width.
+fn svg_row(groups: &[Vec<Face>], y: u32, win_gap: bool) -> (String, u32) {
+    let mut out = String::new();
+    let mut x = MARGIN;
+    for (i, group) in groups.iter().enumerate() {
+        if i > 0 {
+            x += if win_gap && i == groups.len() - 1 {
+                WIN_GAP
+            } else {
+                GROUP_GAP
+            };
+        }
+        for face in group {
+            out.push_str(&svg_tile(face, x, y));
+            x += if face.rotated {
+                TILE_HEIGHT
+            } else {
+                TILE_WIDTH
+            };
+        }
+    }
+    (out, x + MARGIN)
+}
+
+/// Draw the hand as a self-contained SVG image.
+///
+/// Tiles are laid out like [`ascii`], with the dora indicators on a second row. Tile faces
+/// are drawn with shapes and text, without any external images or fonts.
+///
+/// # Examples
+///
+/// ```rust
+/// use mahc::hand::Hand;
+/// use mahc::render;
+///
+/// let tiles = ["123p", "456p", "789m", "555so", "99s"].map(|t| t.to_string()).to_vec();
+/// let hand = Hand::new_from_strings(tiles, "9s".into(), "Ew".into(), "Ew".into()).unwrap();
+///
+/// let svg = render::svg(&hand, &[]);
+/// assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+/// assert!(svg.contains("rotate(90)"));
+/// ```
+pub fn svg(hand: &Hand, dora: &[TileGroup]) -> String {
+    let (hand_row, mut width) = svg_row(&layout(hand), MARGIN, true);
+    let mut body = hand_row;
+    let mut height = MARGIN + TILE_HEIGHT + MARGIN;
+
+    if !dora.is_empty() {
+        let label_y = height + 12;
+        body.push_str(&format!(
+            r##"<text x="{MARGIN}" y="{label_y}" font-size="12" fill="#222">Dora</text>"##
+        ));
+        let dora: Vec<Face> = dora.iter().cloned().map(Face::new).collect();
+        let (dora_row, dora_width) = svg_row(&[dora], label_y + 6, false);
+        body.push_str(&dora_row);
+        width = width.max(dora_width);
+        height = label_y + 6 + TILE_HEIGHT + MARGIN;
+    }
+
+    format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}" font-family="sans-serif">{body}</svg>"#
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hand(tiles: &[&str], win: &str) -> Hand {
+        let tiles = tiles.iter().map(|t| t.to_string()).collect();
+        Hand::new_from_strings(tiles, win.into(), "Ew".into(), "Ew".into()).unwrap()
+    }
+
+    #[test]
+    fn layout_splits_melds_and_win_tile() {
+        let hand = hand(&["123p", "EEEEw", "555so", "406m", "99s"], "9s");
+        let groups = layout(&hand);
+
+        let labels: Vec<Vec<String>> = groups
+            .iter()
+            .map(|group| group.iter().map(|face| label(&face.tile)).collect())
+            .collect();
+        assert_eq!(
+            labels,
+            [
+                vec!["1p", "2p", "3p", "4m", "0m", "6m", "9s"],
+                vec!["Ew", "Ew", "Ew", "Ew"],
+                vec!["5s", "5s", "5s"],
+                vec!["9s"],
+            ]
+        );
+        assert!(groups[1][0].hidden && !groups[1][1].hidden && groups[1][3].hidden);
+        assert!(groups[2][0].rotated && !groups[2][1].rotated);
+    }
+
+    #[test]
+    fn ascii_hand() {
+        let hand = hand(&["123p", "456p", "789m", "555so", "99s"], "9s");
+        let dora = vec!["4p".to_string().try_into().unwrap()];
+
+        let expected = "\
++--+--+--+--+--+--+--+--+--+--+      +--+--+   +--+
+|1p|2p|3p|4p|5p|6p|7m|8m|9m|9s| +----+5s|5s|   |9s|
+|  |  |  |  |  |  |  |  |  |  | | 5s |  |  |   |  |
++--+--+--+--+--+--+--+--+--+--+ +----+--+--+   +--+
+Dora
++--+
+|4p|
+|  |
++--+";
+        assert_eq!(ascii(&hand, &dora), expected);
+    }
+
+    #[test]
+    fn svg_is_self_contained() {
+        let hand = hand(&["123p", "456p", "555so", "rrrd", "99s"], "9s");
+        let out = svg(&hand, &["0m".to_string().try_into().unwrap()]);
+
+        assert_eq!(out.matches("<title>").count(), 15);
+        assert!(out.contains("<title>0m</title>"));
+        assert!(!out.contains("href"));
+        assert!(out.ends_with("</svg>"));
+    }
+}
@@ -0,0 +1,616 @@
+//! Versioned reports of scored hands, as written by `--json`.
+//!
+//! The layout of a report only changes along with [`SCHEMA_VERSION`], and is described by the
+//! JSON Schemas in the `schema` directory of the repository.
+
+use crate::aotenjou::{AotenjouPayment, AotenjouScore};
+use crate::calc::HandValue;
+use crate::fu::Fu;
+use crate::hand::{Hand, Wait};
+use crate::limit_hand::LimitHands;
+use crate::payment::{Payment, Points};
+use crate::score::{FuValue, HanValue, HonbaCounter, Score};
+use crate::verify::Verification;
+use crate::yaku::Yaku;
+
+/// Version of the report layout.
+pub const SCHEMA_VERSION: u32 = 2;
+
+/// A yaku that was awarded.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct YakuEntry {
+    /// Id of the yaku (ex: `sanshoku_doujun`).
+    pub id: &'static str,
+    pub name: &'static str,
+    /// Han the yaku was worth, or 1 for each yakuman.
+    pub han: HanValue,
+    pub yakuman: bool,
+    /// Was the yaku scored for an open hand?
+    pub open: bool,
+}
+
+impl YakuEntry {
+    fn new(yaku: &Yaku, open: bool) -> Self {
+        Self {
+            id: yaku.id(),
+            name: yaku.name(),
+            han: yaku.get_han(open),
+            yakuman: yaku.is_yakuman(),
+            open,
+        }
+    }
+}
+
+/// A fu that was awarded.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct FuEntry {
+    /// Id of the fu (ex: `simple_closed_triplet`).
+    pub id: &'static str,
+    pub name: &'static str,
+    pub fu: FuValue,
+    /// Was the meld open, for triplets and kans?
+    pub open: Option<bool>,
+}
+
+impl FuEntry {
+    fn new(fu: &Fu) -> Self {
+        let open = match fu {
+            Fu::NonSimpleClosedTriplet
+            | Fu::SimpleClosedTriplet
+            | Fu::NonSimpleClosedKan
+            | Fu::SimpleClosedKan => Some(false),
+            Fu::NonSimpleOpenTriplet
+            | Fu::SimpleOpenTriplet
+            | Fu::NonSimpleOpenKan
+            | Fu::SimpleOpenKan => Some(true),
+            _ => None,
+        };
+        Self {
+            id: fu.id(),
+            name: fu.name(),
+            fu: fu.value(),
+            open,
+        }
+    }
+}
+
+/// Where the dora of a hand came from.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct DoraBreakdown {
+    /// Dora from the dora indicators, including extracted North tiles that are dora.
+    pub dora: u32,
+    pub akadora: u32,
+    /// Extracted North tiles (sanma).
+    pub nukidora: u32,
+    pub total: u32,
+}
+
+impl DoraBreakdown {
+    /// Split the total dora of the hand.
+    pub fn new(hand: &Hand, total: u32) -> Self {
+        let akadora = hand.groups().iter().filter(|group| group.isaka).count() as u32;
+        let nukidora = hand.nukidora() as u32;
+        Self {
+            dora: total.saturating_sub(akadora + nukidora),
+            akadora,
+            nukidora,
+            total,
+        }
+    }
+}
+
+/// Points paid for a tsumo by a non-dealer.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct NonDealerTsumo<P = Points> {
+    pub dealer: P,
+    pub non_dealer: P,
+}
+
+/// Points paid by a ron or a tsumo of the dealer.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct DealerPoints<P = Points> {
+    pub ron: P,
+    /// Points paid by each player.
+    pub tsumo: P,
+}
+
+/// Points paid by a ron or a tsumo of a non-dealer.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct NonDealerPoints<P = Points> {
+    pub ron: P,
+    pub tsumo: NonDealerTsumo<P>,
+}
+
+/// Points paid for a win by the dealer or by a non-dealer, honba included.
+///
+/// Aotenjou points can be far above any integer, so they are written as decimal strings.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct PointsReport<P = Points> {
+    pub dealer: DealerPoints<P>,
+    pub non_dealer: NonDealerPoints<P>,
+}
+
+impl PointsReport {
+    pub fn new(payment: &Payment, honba: HonbaCounter) -> Self {
+        Self {
+            dealer: DealerPoints {
+                ron: payment.dealer_ron(honba),
+                tsumo: payment.dealer_tsumo(honba),
+            },
+            non_dealer: NonDealerPoints {
+                ron: payment.non_dealer_ron(honba),
+                tsumo: NonDealerTsumo {
+                    dealer: payment.non_dealer_tsumo_to_dealer(honba),
+                    non_dealer: payment.non_dealer_tsumo_to_non_dealer(honba),
+                },
+            },
+        }
+    }
+}
+
+impl PointsReport<String> {
+    pub fn aotenjou(payment: &AotenjouPayment, honba: HonbaCounter) -> Self {
+        Self {
+            dealer: DealerPoints {
+                ron: payment.dealer_ron(honba).to_string(),
+                tsumo: payment.dealer_tsumo(honba).to_string(),
+            },
+            non_dealer: NonDealerPoints {
+                ron: payment.non_dealer_ron(honba).to_string(),
+                tsumo: NonDealerTsumo {
+                    dealer: payment.non_dealer_tsumo_to_dealer(honba).to_string(),
+                    non_dealer: payment.non_dealer_tsumo_to_non_dealer(honba).to_string(),
+                },
+            },
+        }
+    }
+}
+
+/// Id of the limit the payment is for, if any.
+///
+/// Payments are limited from 2,000 base points, so kiriage mangan is a mangan as well.
+fn limit_id(payment: &Payment, yakuman: bool) -> Option<&'static str> {
+    match payment.base_points() {
+        _ if yakuman => Some("yakuman"),
+        8_000.. => Some("kazoe_yakuman"),
+        6_000.. => Some("sanbaiman"),
+        4_000.. => Some("baiman"),
+        3_000.. => Some("haneman"),
+        2_000.. => Some("mangan"),
+        _ => None,
+    }
+}
+
+/// Report of a scored hand.
+///
+/// # Examples
+///
+/// ```rust
+/// use mahc::calc;
+/// use mahc::hand::{Hand, Wait};
+/// use mahc::report::HandReport;
+///
+/// let tiles = ["123p", "456p", "99s", "234s", "678m"].map(|t| t.to_string()).to_vec();
+/// let hand = Hand::new_from_strings(tiles, "6m".into(), "Ew".into(), "Sw".into()).unwrap();
+/// let score = calc::get_hand_score(
+///     hand.clone(), None, false, true, false, false, false, false, false, false, 0,
+/// )
+/// .unwrap();
+///
+/// let report = HandReport::new(&hand, &score);
+/// assert_eq!(report.wait, Some(Wait::Ryanmen));
+/// assert_eq!(report.yaku[1].id, "pinfu");
+/// assert_eq!(report.points.non_dealer.ron, 2_000);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct HandReport {
+    pub schema_version: u32,
+    pub han: HanValue,
+    pub fu: FuValue,
+    pub honba: HonbaCounter,
+    pub open: bool,
+    /// `mangan`, `haneman`, `baiman`, `sanbaiman`, `kazoe_yakuman` or `yakuman`.
+    pub limit: Option<&'static str>,
+    pub wait: Option<Wait>,
+    pub dora: DoraBreakdown,
+    pub yaku: Vec<YakuEntry>,
+    pub fu_breakdown: Vec<FuEntry>,
+    pub points: PointsReport,
+}
+
+impl HandReport {
+    /// Build the report of the hand from its score.
+    pub fn new(hand: &Hand, score: &Score) -> Self {
+        let yakuman = score.yaku().iter().any(Yaku::is_yakuman);
+
+        Self {
+            schema_version: SCHEMA_VERSION,
+            han: score.han(),
+            fu: score.fu_score(),
+            honba: score.honba(),
+            open: score.is_open(),
+            limit: limit_id(score.payment(), yakuman),
+            wait: hand.wait(),
+            dora: DoraBreakdown::new(hand, score.dora_count()),
+            yaku: score
+                .yaku()
+                .iter()
+                .map(|yaku| YakuEntry::new(yaku, score.is_open()))
+                .collect(),
+            fu_breakdown: score.fu().iter().map(FuEntry::new).collect(),
+            points: PointsReport::new(score.payment(), score.honba()),
+        }
+    }
+}
+
+/// Report of a calculator mode payment.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct CalcReport {
+    pub schema_version: u32,
+    pub han: HanValue,
+    pub fu: FuValue,
+    pub honba: HonbaCounter,
+    pub limit: Option<&'static str>,
+    pub points: PointsReport,
+}
+
+impl CalcReport {
+    pub fn new(payment: &Payment, han: HanValue, fu: FuValue, honba: HonbaCounter) -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION,
+            han,
+            fu,
+            honba,
+            limit: limit_id(payment, false),
+            points: PointsReport::new(payment, honba),
+        }
+    }
+}
+
+/// Report of a hand scored with no limits (aotenjou).
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct AotenjouHandReport {
+    pub schema_version: u32,
+    /// Total han, with 13 for each yakuman.
+    pub han: HanValue,
+    pub fu: FuValue,
+    pub honba: HonbaCounter,
+    pub open: bool,
+    pub wait: Option<Wait>,
+    pub dora: DoraBreakdown,
+    pub yaku: Vec<YakuEntry>,
+    pub fu_breakdown: Vec<FuEntry>,
+    pub points: PointsReport<String>,
+}
+
+impl AotenjouHandReport {
+    /// Build the report of the hand from its aotenjou score.
+    pub fn new(hand: &Hand, aotenjou: &AotenjouScore) -> Self {
+        let report = HandReport::new(hand, aotenjou.score());
+
+        Self {
+            schema_version: SCHEMA_VERSION,
+            han: aotenjou.han(),
+            fu: report.fu,
+            honba: report.honba,
+            open: report.open,
+            wait: report.wait,
+            dora: report.dora,
+            yaku: report.yaku,
+            fu_breakdown: report.fu_breakdown,
+            points: PointsReport::aotenjou(aotenjou.payment(), report.honba),
+        }
+    }
+}
+
+/// Report of a calculator mode payment with no limits (aotenjou).
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct AotenjouCalcReport {
+    pub schema_version: u32,
+    pub han: HanValue,
+    pub fu: FuValue,
+    pub honba: HonbaCounter,
+    pub points: PointsReport<String>,
+}
+
+impl AotenjouCalcReport {
+    pub fn new(payment: &AotenjouPayment, han: HanValue, fu: FuValue, honba: HonbaCounter) -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION,
+            han,
+            fu,
+            honba,
+            points: PointsReport::aotenjou(payment, honba),
+        }
+    }
+}
+
+/// One payment of a claim, next to what it should be.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ComponentEntry {
+    /// Who pays (`Ron`, `Each`, `Non-dealer`, or `Dealer`).
+    pub payer: &'static str,
+    pub claimed: Points,
+    pub actual: Points,
+    /// How much the claim is over, or under if negative.
+    pub difference: i64,
+}
+
+/// Han and fu, or a limit hand, that a claim would be correct for.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ValueEntry {
+    pub han: Option<HanValue>,
+    pub fu: Option<FuValue>,
+    /// `mangan`, `haneman`, `baiman`, `sanbaiman` or `kazoe_yakuman`.
+    pub limit: Option<&'static str>,
+}
+
+impl ValueEntry {
+    fn new(value: &HandValue) -> Self {
+        match *value {
+            HandValue::HanFu(han, fu) => Self {
+                han: Some(han),
+                fu: Some(fu),
+                limit: None,
+            },
+            HandValue::Limit(limit) => Self {
+                han: None,
+                fu: None,
+                limit: Some(match limit {
+                    LimitHands::Mangan => "mangan",
+                    LimitHands::Haneman => "haneman",
+                    LimitHands::Baiman => "baiman",
+                    LimitHands::Sanbaiman => "sanbaiman",
+                    LimitHands::KazoeYakuman => "kazoe_yakuman",
+                }),
+            },
+        }
+    }
+}
+
+/// Report of a claimed payment checked against a scored hand.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct VerifyReport {
+    pub schema_version: u32,
+    /// The claimed payment (ex: `1000-2000`).
+    pub claim: String,
+    /// What the hand pays, written like the claim.
+    pub actual: String,
+    pub correct: bool,
+    pub components: Vec<ComponentEntry>,
+    pub claimed_values: Vec<ValueEntry>,
+    pub hand: HandReport,
+}
+
+impl VerifyReport {
+    /// Build the report of the claim from the hand and its score.
+    pub fn new(verification: &Verification, hand: &Hand, score: &Score) -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION,
+            claim: verification.claim().to_string(),
+            actual: verification.actual().to_string(),
+            correct: verification.is_correct(),
+            components: verification
+                .components()
+                .iter()
+                .map(|component| ComponentEntry {
+                    payer: component.payer,
+                    claimed: component.claimed,
+                    actual: component.actual,
+                    difference: component.difference(),
+                })
+                .collect(),
+            claimed_values: verification
+                .claimed_values()
+                .iter()
+                .map(ValueEntry::new)
+                .collect(),
+            hand: HandReport::new(hand, score),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calc;
+    use crate::tile_group::TileGroup;
+
+    fn score(hand: &Hand, dora: &[&str], tsumo: bool) -> Score {
+        let dora: Vec<TileGroup> = dora
+            .iter()
+            .map(|tile| tile.to_string().try_into().unwrap())
+            .collect();
+        calc::get_hand_score(
+            hand.clone(),
+            Some(dora),
+            tsumo,
+            false,
+            false,
+            false,
+            false,
+            false,
+            false,
+            false,
+            0,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn report_open_yaku_and_fu() {
+        let tiles = ["123p", "456p", "789p", "rrrdo", "99p"].map(|t| t.to_string());
+        let hand =
+            Hand::new_from_strings(tiles.to_vec(), "9p".into(), "Ew".into(), "Ew".into()).unwrap();
+        let report = HandReport::new(&hand, &score(&hand, &["9p"], false));
+
+        assert_eq!(report.limit, Some("mangan"));
+        assert_eq!(report.wait, Some(Wait::Tanki));
+        let honitsu = &report.yaku[0];
+        assert_eq!(
+            (honitsu.id, honitsu.han, honitsu.open),
+            ("honitsu", 2, true)
+        );
+        let triplet = &report.fu_breakdown[1];
+        assert_eq!(triplet.id, "non_simple_open_triplet");
+        assert_eq!(triplet.open, Some(true));
+        assert_eq!(report.fu_breakdown[0].open, None);
+    }
+
+    #[test]
+    fn report_dora_breakdown() {
+        let tiles = ["234p", "406s", "567s", "888m", "55p"].map(|t| t.to_string());
+        let hand =
+            Hand::new_from_strings(tiles.to_vec(), "5p".into(), "Ew".into(), "Sw".into()).unwrap();
+        let report = HandReport::new(&hand, &score(&hand, &["7m"], true));
+
+        assert_eq!(
+            report.dora,
+            DoraBreakdown {
+                dora: 3,
+                akadora: 1,
+                nukidora: 0,
+                total: 4
+            }
+        );
+    }
+
+    /// Check that the report has exactly the properties its schema requires.
+    #[cfg(feature = "serde")]
+    fn assert_schema_keys(value: &serde_json::Value, schema: &serde_json::Value) {
+        let mut keys: Vec<&String> = value.as_object().unwrap().keys().collect();
+        let mut required: Vec<&str> = schema["required"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|key| key.as_str().unwrap())
+            .collect();
+        keys.sort();
+        required.sort();
+        assert_eq!(keys, required);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn reports_match_schema() {
+        let hand_schema: serde_json::Value =
+            serde_json::from_str(include_str!("../schema/hand.schema.json")).unwrap();
+        let calc_schema: serde_json::Value =
+            serde_json::from_str(include_str!("../schema/calc.schema.json")).unwrap();
+        assert_eq!(
+            hand_schema["properties"]["schema_version"]["const"],
+            SCHEMA_VERSION
+        );
+        assert_eq!(
+            calc_schema["properties"]["schema_version"]["const"],
+            SCHEMA_VERSION
+        );
+
+        let tiles = ["111p", "456p", "99s", "234s", "678m"].map(|t| t.to_string());
+        let hand =
+            Hand::new_from_strings(tiles.to_vec(), "6m".into(), "Ew".into(), "Sw".into()).unwrap();
+        let report =
+            serde_json::to_value(HandReport::new(&hand, &score(&hand, &[], true))).unwrap();
+        assert_schema_keys(&report, &hand_schema);
+        let properties = &hand_schema["properties"];
+        assert_schema_keys(&report["dora"], &properties["dora"]);
+        assert_schema_keys(&report["yaku"][0], &properties["yaku"]["items"]);
+        assert_schema_keys(
+            &report["fu_breakdown"][0],
+            &properties["fu_breakdown"]["items"],
+        );
+        assert_eq!(report["wait"], "ryanmen");
+
+        let payment = calc::calculate(1, 30).unwrap();
+        let report = serde_json::to_value(CalcReport::new(&payment, 1, 30, 0)).unwrap();
+        assert_schema_keys(&report, &calc_schema);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn verify_and_aotenjou_reports_match_schema() {
+        use crate::aotenjou;
+        use crate::payment::ClaimedPayment;
+        use crate::score_table::TableRules;
+
+        let schema = |text: &str| -> serde_json::Value { serde_json::from_str(text).unwrap() };
+        let verify_schema = schema(include_str!("../schema/verify.schema.json"));
+        let hand_schema = schema(include_str!("../schema/aotenjou_hand.schema.json"));
+        let calc_schema = schema(include_str!("../schema/aotenjou_calc.schema.json"));
+        let points_schema = schema(include_str!("../schema/aotenjou_points.schema.json"));
+        for schema in [&verify_schema, &hand_schema, &calc_schema] {
+            assert_eq!(
+                schema["properties"]["schema_version"]["const"],
+                SCHEMA_VERSION
+            );
+        }
+
+        let tiles = ["111p", "456p", "99s", "234s", "678m"].map(|t| t.to_string());
+        let hand =
+            Hand::new_from_strings(tiles.to_vec(), "6m".into(), "Ew".into(), "Sw".into()).unwrap();
+        let score = score(&hand, &[], true);
+        let claim: ClaimedPayment = "300-500".to_string().try_into().unwrap();
+        let verification =
+            Verification::new(&score, false, true, claim, &TableRules::default()).unwrap();
+        let report = serde_json::to_value(VerifyReport::new(&verification, &hand, &score)).unwrap();
+        assert_schema_keys(&report, &verify_schema);
+        let properties = &verify_schema["properties"];
+        assert_schema_keys(&report["components"][0], &properties["components"]["items"]);
+        assert_schema_keys(
+            &report["claimed_values"][0],
+            &properties["claimed_values"]["items"],
+        );
+        assert_eq!(report["hand"]["schema_version"], SCHEMA_VERSION);
+
+        let score = aotenjou::get_hand_score(
+            &calc::YonmaRules::default(),
+            hand.clone(),
+            None,
+            true,
+            false,
+            false,
+            false,
+            false,
+            false,
+            false,
+            false,
+            0,
+        )
+        .unwrap();
+        let report = serde_json::to_value(AotenjouHandReport::new(&hand, &score)).unwrap();
+        assert_schema_keys(&report, &hand_schema);
+        assert_schema_keys(&report["points"], &points_schema);
+
+        let payment = AotenjouPayment::from_han_and_fu(100, 30).unwrap();
+        let report = serde_json::to_value(AotenjouCalcReport::new(&payment, 100, 30, 0)).unwrap();
+        assert_schema_keys(&report, &calc_schema);
+        assert!(report["points"]["dealer"]["ron"].is_string());
+    }
+
+    #[test]
+    fn calc_report_limit() {
+        let payment = calc::calculate(4, 40).unwrap();
+        let report = CalcReport::new(&payment, 4, 40, 1);
+        assert_eq!(report.limit, Some("mangan"));
+        assert_eq!(report.points.non_dealer.tsumo.non_dealer, 2_100);
+
+        let payment = calc::calculate_kiriage(4, 30).unwrap();
+        assert_eq!(CalcReport::new(&payment, 4, 30, 0).limit, Some("mangan"));
+        let payment = calc::calculate(4, 30).unwrap();
+        assert_eq!(CalcReport::new(&payment, 4, 30, 0).limit, None);
+    }
+}
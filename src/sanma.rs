@@ -0,0 +1,308 @@
+use crate::calc;
+use crate::hand::error::HandErr;
+use crate::hand::Hand;
+use crate::payment::{Points, SanmaTsumo};
+use crate::score::{HonbaCounter, Score};
+use crate::suit::Suit;
+use crate::tile_group::{GroupType, TileGroup};
+
+/// Rules for three-player (sanma) games.
+///
+/// Sanma is played without 2m to 8m and without chi, and nobody sits North. North tiles can be
+/// set aside as nukidora.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SanmaRules {
+    /// How the missing player's share of a tsumo is paid.
+    pub tsumo: SanmaTsumo,
+    /// Points each honba is worth, usually 200 or 1,000.
+    pub tsumibou: Points,
+    /// Whether the replacement draw after a nukidora counts for rinshan kaihou.
+    pub nuki_rinshan: bool,
+}
+
+impl Default for SanmaRules {
+    fn default() -> Self {
+        Self {
+            tsumo: SanmaTsumo::TsumoLoss,
+            tsumibou: 200,
+            nuki_rinshan: false,
+        }
+    }
+}
+
+impl SanmaRules {
+    /// Check that the hand can be played in sanma.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use mahc::hand::error::HandErr;
+    /// use mahc::hand::Hand;
+    /// use mahc::sanma::SanmaRules;
+    ///
+    /// let to_strings = |tiles: &[&str]| tiles.iter().map(|t| t.to_string()).collect();
+    /// let rules = SanmaRules::default();
+    ///
+    /// let tiles = to_strings(&["111m", "999m", "456p", "789s", "ggd"]);
+    /// let hand = Hand::new_from_strings(tiles, "gd".into(), "Ew".into(), "Sw".into()).unwrap();
+    /// assert!(rules.validate(&hand).is_ok());
+    ///
+    /// let tiles = to_strings(&["234m", "999m", "456p", "789s", "ggd"]);
+    /// let hand = Hand::new_from_strings(tiles, "gd".into(), "Ew".into(), "Sw".into()).unwrap();
+    /// assert_eq!(rules.validate(&hand).unwrap_err(), HandErr::SanmaManzu);
+    /// ```
+    pub fn validate(&self, hand: &Hand) -> Result<(), HandErr> {
+        if hand.sequences().iter().any(|sequence| sequence.isopen) {
+            return Err(HandErr::SanmaChi);
+        }
+
+        let manzu = |tile: &TileGroup| {
+            tile.suit == Suit::Manzu && !matches!(tile.value.as_str(), "1" | "9")
+        };
+        if hand.groups().iter().any(|group| {
+            group.suit == Suit::Manzu && (group.group_type == GroupType::Sequence || manzu(group))
+        }) || manzu(&hand.win_tile())
+        {
+            return Err(HandErr::SanmaManzu);
+        }
+
+        if hand.seat_tile().value == "N" || hand.prev_tile().value == "N" {
+            return Err(HandErr::SanmaNorth);
+        }
+
+        Ok(())
+    }
+
+    /// Get the dora tile shown by a dora indicator.
+    ///
+    /// As 2m to 8m are not played, 1m shows 9m and 9m shows 1m.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use mahc::sanma::SanmaRules;
+    /// use mahc::tile_group::TileGroup;
+    ///
+    /// let indicator: TileGroup = "1m".to_string().try_into().unwrap();
+    /// let dora = SanmaRules::dora_tile(&indicator).unwrap();
+    /// assert_eq!(dora.to_string(), "9m");
+    /// ```
+    pub fn dora_tile(indicator: &TileGroup) -> Result<TileGroup, HandErr> {
+        if indicator.suit == Suit::Manzu && indicator.value == "1" {
+            return "9m".to_string().try_into();
+        }
+        indicator.next_tile()
+    }
+
+    /// Get the score breakdown of a sanma hand, with the payment following these rules.
+    ///
+    /// Takes the same options as [`calc::get_hand_score`].
+    #[allow(clippy::too_many_arguments)]
+    pub fn get_hand_score(
+        &self,
+        hand: Hand,
+        dora: Option<Vec<TileGroup>>,
+        tsumo: bool,
+        riichi: bool,
+        doubleriichi: bool,
+        ippatsu: bool,
+        haitei: bool,
+        rinshan: bool,
+        chankan: bool,
+        tenhou: bool,
+        honba: HonbaCounter,
+    ) -> Result<Score, HandErr> {
+        self.validate(&hand)?;
+        let nuki_rinshan = self.nuki_rinshan && hand.nukidora() > 0;
+        if hand.kans().is_empty() && !nuki_rinshan && rinshan {
+            return Err(HandErr::RinshanKanWithoutKan);
+        }
+        let dora_tiles: Vec<TileGroup> = dora
+            .unwrap_or_default()
+            .iter()
+            .map(Self::dora_tile)
+            .collect::<Result<_, _>>()?;
+
+        let mut score = calc::score_hand(
+            hand,
+            &dora_tiles,
+            tsumo,
+            riichi,
+            doubleriichi,
+            ippatsu,
+            haitei,
+            rinshan,
+            chankan,
+            tenhou,
+            honba,
+        )?;
+        score.payment_mut().set_sanma(self.tsumo, self.tsumibou);
+
+        Ok(score)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hand(tiles: &[&str], win: &str, prev: &str, seat: &str) -> Hand {
+        Hand::new_from_strings(
+            tiles.iter().map(|t| t.to_string()).collect(),
+            win.to_string(),
+            prev.to_string(),
+            seat.to_string(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn sanma_no_chi() {
+        let hand = hand(&["111m", "999m", "456po", "789s", "ggd"], "gd", "Ew", "Sw");
+        let rules = SanmaRules::default();
+        assert_eq!(rules.validate(&hand).unwrap_err(), HandErr::SanmaChi);
+    }
+
+    #[test]
+    fn sanma_no_north_seat() {
+        let hand = hand(&["111m", "999m", "456p", "789s", "ggd"], "gd", "Ew", "Nw");
+        let rules = SanmaRules::default();
+        assert_eq!(rules.validate(&hand).unwrap_err(), HandErr::SanmaNorth);
+    }
+
+    #[test]
+    fn sanma_dora_and_tsumo_loss() {
+        // toitoi with 999m as dora from the 1m indicator
+        let hand = hand(&["111m", "999m", "444p", "777so", "ggd"], "gd", "Ew", "Sw");
+        let rules = SanmaRules::default();
+        let dora = vec!["1m".to_string().try_into().unwrap()];
+        let score = rules
+            .get_hand_score(
+                hand,
+                Some(dora),
+                true,
+                false,
+                false,
+                false,
+                false,
+                false,
+                false,
+                false,
+                2,
+            )
+            .unwrap();
+
+        assert_eq!(score.dora_count(), 3);
+        let payment = score.payment();
+        assert!(payment.is_sanma());
+        // sanankou, toitoi and 3 dora is haneman, with 200 per honba split between two
+        assert_eq!(payment.non_dealer_tsumo_to_dealer(2), 6_200);
+        assert_eq!(payment.non_dealer_tsumo_to_non_dealer(2), 3_200);
+        assert_eq!(payment.total(false, true, 2), 9_400);
+    }
+
+    #[test]
+    fn sanma_north_bisection() {
+        let rules = SanmaRules {
+            tsumo: SanmaTsumo::NorthBisection,
+            tsumibou: 1_000,
+            ..SanmaRules::default()
+        };
+        let hand = hand(&["123p", "456p", "789p", "99s", "234s"], "2s", "Ew", "Ew");
+        let score = rules
+            .get_hand_score(
+                hand, None, true, false, false, false, false, false, false, false, 0,
+            )
+            .unwrap();
+
+        // pinfu, ittsu and menzen tsumo: 2,600 all is 3,900 all with the missing share split
+        let payment = score.payment();
+        assert_eq!(payment.dealer_tsumo(0), 3_900);
+        assert_eq!(payment.total(true, true, 0), 7_800);
+    }
+
+    #[test]
+    fn sanma_rinshan_after_nukidora() {
+        let tiles = ["111m", "999m", "456p", "789s", "ggd", "Nwk"];
+        let rules = SanmaRules::default();
+        let score = rules.get_hand_score(
+            hand(&tiles, "gd", "Ew", "Sw"),
+            None,
+            true,
+            false,
+            false,
+            false,
+            false,
+            true,
+            false,
+            false,
+            0,
+        );
+        assert_eq!(score.unwrap_err(), HandErr::RinshanKanWithoutKan);
+
+        let rules = SanmaRules {
+            nuki_rinshan: true,
+            ..SanmaRules::default()
+        };
+        let score = rules
+            .get_hand_score(
+                hand(&tiles, "gd", "Ew", "Sw"),
+                None,
+                true,
+                false,
+                false,
+                false,
+                false,
+                true,
+                false,
+                false,
+                0,
+            )
+            .unwrap();
+        // rinshan kaihou, menzen tsumo and the nukidora
+        assert_eq!(score.dora_count(), 1);
+        assert_eq!(score.han(), 3);
+    }
+
+    #[test]
+    fn four_player_nukidora_by_rule() {
+        let tiles = ["111m", "999m", "456p", "789s", "ggd", "Nwk"];
+        let score = |rules: calc::YonmaRules, rinshan: bool| {
+            rules.get_hand_score(
+                hand(&tiles, "gd", "Ew", "Sw"),
+                None,
+                true,
+                false,
+                false,
+                false,
+                false,
+                rinshan,
+                false,
+                false,
+                0,
+            )
+        };
+
+        let off = calc::YonmaRules::default();
+        assert_eq!(
+            score(off, false).unwrap_err(),
+            HandErr::NukidoraOutsideSanma
+        );
+
+        let on = calc::YonmaRules {
+            nukidora: true,
+            ..calc::YonmaRules::default()
+        };
+        // menzen tsumo and the nukidora
+        let scored = score(on, false).unwrap();
+        assert_eq!(scored.dora_count(), 1);
+        assert_eq!(scored.han(), 2);
+        assert_eq!(score(on, true).unwrap_err(), HandErr::RinshanKanWithoutKan);
+
+        let nuki_rinshan = calc::YonmaRules {
+            nuki_rinshan: true,
+            ..on
+        };
+        assert_eq!(score(nuki_rinshan, true).unwrap().han(), 3);
+    }
+}
@@ -1,4 +1,8 @@
+#[cfg(feature = "serde")]
+use crate::fu::calculate_total_fu_value;
 use crate::fu::Fu;
+#[cfg(feature = "serde")]
+use crate::hand::error::HandErr;
 use crate::payment::Payment;
 use crate::yaku::Yaku;
 
@@ -8,9 +12,38 @@ pub type HanValue = u32;
 pub type FuValue = u64;
 /// Number of honba (repeat counts).
 pub type HonbaCounter = u64;
+/// Number of riichi deposits (sticks) on the table.
+pub type RiichiCounter = u64;
 
 /// Detailed breakdown of the winning hand's score.
-#[derive(Debug)]
+///
+/// With the `serde` feature, a score is (de)serialized as:
+///
+/// ```json
+/// {
+///   "han": 2,
+///   "fu": 30,
+///   "honba": 0,
+///   "dora": 0,
+///   "open": false,
+///   "yaku": [
+///     { "name": "Riichi", "han": 1, "yakuman": false },
+///     { "name": "Pinfu", "han": 1, "yakuman": false }
+///   ],
+///   "fu_breakdown": [{ "name": "BasePoints", "fu": 20 }, { "name": "ClosedRon", "fu": 10 }],
+///   "payment": { "base_points": 480, "tsumibou": 300, "sanma": null }
+/// }
+/// ```
+///
+/// The han of each yaku and the fu of each fu entry are for reading only, and are worked out
+/// again from the names when deserializing. The total han has to be the han of the yaku and the
+/// dora, and the total fu the fu of the breakdown rounded up.
+#[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "ScoreRepr", into = "ScoreRepr")
+)]
 pub struct Score {
     /// Breakdown of payment amounts.
     payment: Payment,
@@ -26,12 +59,13 @@ pub struct Score {
     honba: HonbaCounter,
     /// Is the hand open when it scored?
     is_open: bool,
-    /// total number of han from dora 
+    /// total number of han from dora
     dora_count: u32,
 }
 
 impl Score {
     /// Create a new [`Score`].
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         payment: Payment,
         yaku: Vec<Yaku>,
@@ -54,6 +88,11 @@ impl Score {
         }
     }
 
+    /// Get the payment breakdown to change how it is paid out.
+    pub(crate) fn payment_mut(&mut self) -> &mut Payment {
+        &mut self.payment
+    }
+
     /// Get the payment breakdown.
     pub fn payment(&self) -> &Payment {
         &self.payment
@@ -89,8 +128,173 @@ impl Score {
         self.is_open
     }
 
-    /// Get the total number of han from dora. 
-    pub fn dora_count(&self) -> u32{
+    /// Get the total number of han from dora.
+    pub fn dora_count(&self) -> u32 {
         self.dora_count
     }
 }
+
+/// A yaku with the han it was worth.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct YakuEntry {
+    name: Yaku,
+    #[serde(default)]
+    han: HanValue,
+    #[serde(default)]
+    yakuman: bool,
+}
+
+/// A fu with the minipoints it was worth.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct FuEntry {
+    name: Fu,
+    #[serde(default)]
+    fu: FuValue,
+}
+
+/// How a [`Score`] is (de)serialized.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct ScoreRepr {
+    han: HanValue,
+    fu: FuValue,
+    honba: HonbaCounter,
+    dora: u32,
+    open: bool,
+    yaku: Vec<YakuEntry>,
+    fu_breakdown: Vec<FuEntry>,
+    payment: Payment,
+}
+
+#[cfg(feature = "serde")]
+impl From<Score> for ScoreRepr {
+    fn from(score: Score) -> Self {
+        let is_open = score.is_open;
+        Self {
+            han: score.han,
+            fu: score.fu_score,
+            honba: score.honba,
+            dora: score.dora_count,
+            open: is_open,
+            yaku: score
+                .yaku
+                .into_iter()
+                .map(|name| YakuEntry {
+                    name,
+                    han: name.get_han(is_open),
+                    yakuman: name.is_yakuman(),
+                })
+                .collect(),
+            fu_breakdown: score
+                .fu
+                .into_iter()
+                .map(|name| FuEntry {
+                    name,
+                    fu: name.value(),
+                })
+                .collect(),
+            payment: score.payment,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<ScoreRepr> for Score {
+    type Error = HandErr;
+
+    fn try_from(repr: ScoreRepr) -> Result<Self, Self::Error> {
+        let yaku: Vec<Yaku> = repr.yaku.into_iter().map(|entry| entry.name).collect();
+        let fu: Vec<Fu> = repr
+            .fu_breakdown
+            .into_iter()
+            .map(|entry| entry.name)
+            .collect();
+
+        // yakuman hands count one han for each yakuman, as they are scored
+        let yakuman = yaku.iter().filter(|yaku| yaku.is_yakuman()).count() as HanValue;
+        let yaku_han = if yakuman != 0 {
+            yakuman
+        } else {
+            yaku.iter().map(|yaku| yaku.get_han(repr.open)).sum()
+        };
+        if repr.han != yaku_han + repr.dora || repr.fu != calculate_total_fu_value(&fu) {
+            return Err(HandErr::InvalidScore);
+        }
+
+        Ok(Self {
+            payment: repr.payment,
+            yaku,
+            fu,
+            han: repr.han,
+            fu_score: repr.fu,
+            honba: repr.honba,
+            is_open: repr.open,
+            dora_count: repr.dora,
+        })
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::*;
+    use crate::calc;
+    use crate::hand::Hand;
+
+    #[test]
+    fn score_serde_han_per_yaku() {
+        let tiles = ["123p", "456p", "99s", "234s", "678m"].map(|t| t.to_string());
+        let hand =
+            Hand::new_from_strings(tiles.to_vec(), "6m".into(), "Ew".into(), "Sw".into()).unwrap();
+        let score = calc::get_hand_score(
+            hand, None, true, true, false, false, false, false, false, false, 0,
+        )
+        .unwrap();
+
+        let json = serde_json::to_value(&score).unwrap();
+        assert_eq!(json["han"], 3);
+        assert_eq!(json["fu"], 20);
+        assert_eq!(
+            json["yaku"][0],
+            serde_json::json!({ "name" : "Riichi", "han" : 1, "yakuman" : false })
+        );
+        assert_eq!(json["fu_breakdown"][0]["fu"], 20);
+
+        let actual: Score = serde_json::from_value(json).unwrap();
+        assert_eq!(actual.yaku(), score.yaku());
+        assert_eq!(actual.fu(), score.fu());
+        assert_eq!(
+            actual.payment().non_dealer_ron(0),
+            score.payment().non_dealer_ron(0)
+        );
+    }
+
+    #[test]
+    fn score_serde_rejects_wrong_totals() {
+        let tiles = ["123p", "456p", "99s", "234s", "678m"].map(|t| t.to_string());
+        let hand =
+            Hand::new_from_strings(tiles.to_vec(), "6m".into(), "Ew".into(), "Sw".into()).unwrap();
+        let score = calc::get_hand_score(
+            hand, None, true, true, false, false, false, false, false, false, 0,
+        )
+        .unwrap();
+        let json = serde_json::to_value(&score).unwrap();
+
+        let mut han = json.clone();
+        han["han"] = 13.into();
+        let actual = serde_json::from_value::<Score>(han).unwrap_err();
+        assert_eq!(actual.to_string(), HandErr::InvalidScore.to_string());
+
+        let mut fu = json.clone();
+        fu["fu"] = 110.into();
+        assert!(serde_json::from_value::<Score>(fu).is_err());
+
+        // han from dora count towards the total
+        let mut dora = json;
+        dora["han"] = 5.into();
+        dora["dora"] = 2.into();
+        let actual: Score = serde_json::from_value(dora).unwrap();
+        assert_eq!(actual.han(), 5);
+    }
+}
@@ -0,0 +1,327 @@
+use crate::calc;
+use crate::hand::error::HandErr;
+use crate::limit_hand::LimitHands;
+use crate::payment::Payment;
+use crate::sanma::SanmaRules;
+use crate::score::{HanValue, HonbaCounter};
+
+/// Han values in the table before the limit hands.
+const HAN: [HanValue; 4] = [1, 2, 3, 4];
+/// Limit hands, with the han they start at and their han range.
+const LIMITS: [(LimitHands, HanValue, &str); 5] = [
+    (LimitHands::Mangan, 5, "5"),
+    (LimitHands::Haneman, 6, "6-7"),
+    (LimitHands::Baiman, 8, "8-10"),
+    (LimitHands::Sanbaiman, 11, "11-12"),
+    (LimitHands::KazoeYakuman, 13, "13+"),
+];
+
+/// Output format of a score table.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TableFormat {
+    Markdown,
+    Csv,
+    Html,
+    Text,
+}
+
+impl TryFrom<String> for TableFormat {
+    type Error = HandErr;
+
+    /// Parse the format from its name or file extension.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use mahc::score_table::TableFormat;
+    ///
+    /// let actual: TableFormat = "md".to_string().try_into().unwrap();
+    /// assert_eq!(actual, TableFormat::Markdown);
+    ///
+    /// assert!(TableFormat::try_from("pdf".to_string()).is_err());
+    /// ```
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        match value.to_lowercase().as_str() {
+            "md" | "markdown" => Ok(Self::Markdown),
+            "csv" => Ok(Self::Csv),
+            "html" => Ok(Self::Html),
+            "txt" | "text" => Ok(Self::Text),
+            _ => Err(HandErr::InvalidTableFormat),
+        }
+    }
+}
+
+/// Rules the score table is built under.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct TableRules {
+    /// Round 4 han 30 fu and 3 han 60 fu up to mangan.
+    pub kiriage: bool,
+    /// Number of repeat counters added to every payment.
+    pub honba: HonbaCounter,
+    /// Three-player rules, if the game is sanma.
+    pub sanma: Option<SanmaRules>,
+}
+
+/// A titled grid of cells, with a header row.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Grid {
+    pub title: String,
+    pub header: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+/// Dealer or non-dealer score table, as `ron (tsumo)` for every han and fu and for the limit
+/// hands.
+///
+/// Combinations no hand can score are left as `-`.
+///
+/// # Examples
+///
+/// ```rust
+/// use mahc::score_table::{ScoreTable, TableFormat, TableRules};
+///
+/// let table = ScoreTable::new(&TableRules::default(), false).unwrap();
+/// let out = table.render(TableFormat::Csv);
+///
+/// assert!(out.contains("30,1000 (300/500),2000 (500/1000),3900 (1000/2000),7700 (2000/3900)"));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScoreTable {
+    grids: [Grid; 2],
+}
+
+impl ScoreTable {
+    /// Build the score table for the dealer, or for a non-dealer.
+    pub fn new(rules: &TableRules, dealer: bool) -> Result<Self, HandErr> {
+        let who = if dealer { "Dealer" } else { "Non-dealer" };
+
+        let mut header = vec!["Fu".to_string()];
+        header.extend(HAN.iter().map(|han| format!("{han} Han")));
+        let mut rows = vec![];
+        for fu in calc::TABLE_FU {
+            let mut row = vec![fu.to_string()];
+            for han in HAN {
+                let payment = if rules.kiriage {
+                    calc::calculate_kiriage(han, fu)?
+                } else {
+                    calc::calculate(han, fu)?
+                };
+                let ron = calc::is_possible_han_fu(han, fu, false);
+                let tsumo = calc::is_possible_han_fu(han, fu, true);
+                row.push(Self::cell(rules, dealer, &payment, ron, tsumo));
+            }
+            rows.push(row);
+        }
+        let fu_grid = Grid {
+            title: who.to_string(),
+            header,
+            rows,
+        };
+
+        let mut rows = vec![];
+        for (limit, han, range) in LIMITS {
+            let payment = calc::calculate(han, 30)?;
+            let cell = Self::cell(rules, dealer, &payment, true, true);
+            rows.push(vec![limit.to_string(), range.to_string(), cell]);
+        }
+        let limit_grid = Grid {
+            title: format!("{who} Limit Hands"),
+            header: vec!["Limit".to_string(), "Han".to_string(), "Points".to_string()],
+            rows,
+        };
+
+        Ok(Self {
+            grids: [fu_grid, limit_grid],
+        })
+    }
+
+    /// Get the grids of the table, the han and fu grid first.
+    pub fn grids(&self) -> &[Grid] {
+        &self.grids
+    }
+
+    /// Render the table in the given format.
+    pub fn render(&self, format: TableFormat) -> String {
+        let grids: Vec<String> = self
+            .grids
+            .iter()
+            .map(|grid| match format {
+                TableFormat::Markdown => markdown(grid),
+                TableFormat::Csv => csv(grid),
+                TableFormat::Html => html(grid),
+                TableFormat::Text => text(grid),
+            })
+            .collect();
+        grids.join("\n\n")
+    }
+
+    fn cell(rules: &TableRules, dealer: bool, payment: &Payment, ron: bool, tsumo: bool) -> String {
+        let mut payment = payment.clone();
+        if let Some(sanma) = rules.sanma {
+            payment.set_sanma(sanma.tsumo, sanma.tsumibou);
+        }
+        let honba = rules.honba;
+
+        let ron = if !ron {
+            "-".to_string()
+        } else if dealer {
+            payment.dealer_ron(honba).to_string()
+        } else {
+            payment.non_dealer_ron(honba).to_string()
+        };
+        let tsumo = if !tsumo {
+            "-".to_string()
+        } else if dealer {
+            payment.dealer_tsumo(honba).to_string()
+        } else {
+            format!(
+                "{}/{}",
+                payment.non_dealer_tsumo_to_non_dealer(honba),
+                payment.non_dealer_tsumo_to_dealer(honba)
+            )
+        };
+
+        if ron == "-" && tsumo == "-" {
+            return "-".to_string();
+        }
+        format!("{ron} ({tsumo})")
+    }
+}
+
+fn markdown(grid: &Grid) -> String {
+    let row = |cells: &[String]| format!("| {} |", cells.join(" | "));
+    let mut lines = vec![format!("### {}", grid.title), row(&grid.header)];
+    lines.push(format!("|{}", "---|".repeat(grid.header.len())));
+    lines.extend(grid.rows.iter().map(|cells| row(cells)));
+    lines.join("\n")
+}
+
+fn csv(grid: &Grid) -> String {
+    let field = |cell: &String| {
+        if cell.contains([',', '"', '\n']) {
+            format!("\"{}\"", cell.replace('"', "\"\""))
+        } else {
+            cell.clone()
+        }
+    };
+    let row = |cells: &[String]| cells.iter().map(field).collect::<Vec<String>>().join(",");
+    let mut lines = vec![field(&grid.title), row(&grid.header)];
+    lines.extend(grid.rows.iter().map(|cells| row(cells)));
+    lines.join("\n")
+}
+
+fn html(grid: &Grid) -> String {
+    let escape = |cell: &String| {
+        cell.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+    };
+    let row = |tag: &str, cells: &[String]| {
+        let cells: String = cells
+            .iter()
+            .map(|cell| format!("<{tag}>{}</{tag}>", escape(cell)))
+            .collect();
+        format!("<tr>{cells}</tr>")
+    };
+
+    let mut lines = vec![
+        "<table>".to_string(),
+        format!("<caption>{}</caption>", escape(&grid.title)),
+        format!("<thead>{}</thead>", row("th", &grid.header)),
+        "<tbody>".to_string(),
+    ];
+    lines.extend(grid.rows.iter().map(|cells| row("td", cells)));
+    lines.push("</tbody>".to_string());
+    lines.push("</table>".to_string());
+    lines.join("\n")
+}
+
+fn text(grid: &Grid) -> String {
+    let mut widths: Vec<usize> = grid.header.iter().map(String::len).collect();
+    for cells in &grid.rows {
+        for (width, cell) in widths.iter_mut().zip(cells) {
+            *width = (*width).max(cell.len());
+        }
+    }
+    let row = |cells: &[String]| {
+        let cells: Vec<String> = cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{cell:<width$}"))
+            .collect();
+        cells.join("  ").trim_end().to_string()
+    };
+
+    let mut lines = vec![grid.title.clone(), row(&grid.header)];
+    lines.extend(grid.rows.iter().map(|cells| row(cells)));
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::payment::SanmaTsumo;
+
+    fn cell(table: &ScoreTable, fu: &str, han: usize) -> String {
+        let row = table.grids()[0]
+            .rows
+            .iter()
+            .find(|row| row[0] == fu)
+            .unwrap();
+        row[han].clone()
+    }
+
+    #[test]
+    fn table_impossible_combinations() {
+        let table = ScoreTable::new(&TableRules::default(), true).unwrap();
+        assert_eq!(cell(&table, "20", 1), "-");
+        assert_eq!(cell(&table, "20", 2), "- (700)");
+        assert_eq!(cell(&table, "25", 2), "2400 (-)");
+        assert_eq!(cell(&table, "25", 3), "4800 (1600)");
+    }
+
+    #[test]
+    fn table_kiriage_and_honba() {
+        let rules = TableRules {
+            kiriage: true,
+            honba: 1,
+            ..TableRules::default()
+        };
+        let table = ScoreTable::new(&rules, false).unwrap();
+        assert_eq!(cell(&table, "30", 4), "8300 (2100/4100)");
+        assert_eq!(cell(&table, "60", 3), "8300 (2100/4100)");
+        assert_eq!(cell(&table, "30", 3), "4200 (1100/2100)");
+
+        let limits = &table.grids()[1].rows;
+        assert_eq!(limits[4], ["Yakuman", "13+", "32300 (8100/16100)"]);
+    }
+
+    #[test]
+    fn table_sanma_tsumo() {
+        let rules = TableRules {
+            sanma: Some(SanmaRules {
+                tsumo: SanmaTsumo::NorthBisection,
+                ..SanmaRules::default()
+            }),
+            ..TableRules::default()
+        };
+        let table = ScoreTable::new(&rules, true).unwrap();
+        assert_eq!(table.grids()[1].rows[0][2], "12000 (6000)");
+    }
+
+    #[test]
+    fn table_formats() {
+        let table = ScoreTable::new(&TableRules::default(), true).unwrap();
+
+        let markdown = table.render(TableFormat::Markdown);
+        assert!(markdown.starts_with("### Dealer\n| Fu | 1 Han | 2 Han | 3 Han | 4 Han |\n|---|"));
+        assert!(markdown.contains("| Mangan | 5 | 12000 (4000) |"));
+
+        let html = table.render(TableFormat::Html);
+        assert!(html.contains("<caption>Dealer Limit Hands</caption>"));
+        assert!(html.contains("<tr><td>30</td><td>1500 (500)</td>"));
+
+        let text = table.render(TableFormat::Text);
+        assert!(text.contains("\nMangan     5      12000 (4000)"));
+    }
+}
@@ -0,0 +1,81 @@
+use crate::hand::error::HandErr;
+use crate::suit::Suit;
+use crate::tile_group::TileGroup;
+
+/// Seat wind of a player at the table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Seat {
+    East,
+    South,
+    West,
+    North,
+}
+
+impl TryFrom<String> for Seat {
+    type Error = HandErr;
+
+    /// Parse the seat from a wind tile string.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use mahc::seat::Seat;
+    ///
+    /// let actual: Seat = "Sw".to_string().try_into().unwrap();
+    /// assert_eq!(actual, Seat::South);
+    ///
+    /// let actual: Seat = "4z".to_string().try_into().unwrap();
+    /// assert_eq!(actual, Seat::North);
+    /// ```
+    fn try_from(wind: String) -> Result<Self, Self::Error> {
+        let tile: TileGroup = wind.try_into()?;
+        if tile.suit != Suit::Wind {
+            return Err(HandErr::InvalidGroup);
+        }
+
+        match tile.value.as_str() {
+            "E" => Ok(Self::East),
+            "S" => Ok(Self::South),
+            "W" => Ok(Self::West),
+            "N" => Ok(Self::North),
+            _ => Err(HandErr::InvalidGroup),
+        }
+    }
+}
+
+impl std::fmt::Display for Seat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::East => write!(f, "East"),
+            Self::South => write!(f, "South"),
+            Self::West => write!(f, "West"),
+            Self::North => write!(f, "North"),
+        }
+    }
+}
+
+impl Seat {
+    /// All seats in turn order, starting from the dealer.
+    pub const ALL: [Self; 4] = [Self::East, Self::South, Self::West, Self::North];
+
+    /// Get the position of the seat in turn order, starting from the dealer at `0`.
+    pub fn index(&self) -> usize {
+        match self {
+            Self::East => 0,
+            Self::South => 1,
+            Self::West => 2,
+            Self::North => 3,
+        }
+    }
+
+    /// Check if the seat is the dealer.
+    pub fn is_dealer(&self) -> bool {
+        *self == Self::East
+    }
+
+    /// Get the seat that plays after this one.
+    pub fn next(&self) -> Self {
+        Self::ALL[(self.index() + 1) % 4]
+    }
+}
@@ -0,0 +1,299 @@
+use std::io::{BufRead, BufReader, Write};
+use std::net::{Ipv4Addr, TcpListener, TcpStream};
+use std::thread;
+use std::time::Duration;
+
+use serde_json::json;
+
+use crate::hand::error::HandErr;
+
+/// Largest request body read, in bytes.
+const MAX_BODY: usize = 64 * 1024;
+/// How long a client can stay idle while sending its request or reading the response.
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// An HTTP request, with only what the server uses.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Request {
+    pub method: String,
+    /// Path without the query string (ex: `/hand`).
+    pub path: String,
+    pub body: String,
+}
+
+impl Request {
+    /// Read a request from a stream.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use mahc::serve::Request;
+    ///
+    /// let raw = "POST /calc?x=1 HTTP/1.1\r\nContent-Length: 19\r\n\r\n{\"han\":3,\"fu\":30}  ";
+    /// let request = Request::read(&mut raw.as_bytes()).unwrap();
+    ///
+    /// assert_eq!(request.method, "POST");
+    /// assert_eq!(request.path, "/calc");
+    /// assert_eq!(request.body, "{\"han\":3,\"fu\":30}  ");
+    /// ```
+    pub fn read(stream: &mut impl BufRead) -> Result<Self, HandErr> {
+        let mut line = String::new();
+        stream
+            .read_line(&mut line)
+            .map_err(|_| HandErr::InvalidRequest)?;
+        let mut parts = line.split_whitespace();
+        let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+            return Err(HandErr::InvalidRequest);
+        };
+        let method = method.to_string();
+        let path = target.split('?').next().unwrap_or_default().to_string();
+
+        let mut length = 0;
+        loop {
+            let mut header = String::new();
+            stream
+                .read_line(&mut header)
+                .map_err(|_| HandErr::InvalidRequest)?;
+            let header = header.trim_end();
+            if header.is_empty() {
+                break;
+            }
+            if let Some((name, value)) = header.split_once(':') {
+                if name.eq_ignore_ascii_case("content-length") {
+                    length = value.trim().parse().map_err(|_| HandErr::InvalidRequest)?;
+                }
+            }
+        }
+        if length > MAX_BODY {
+            return Err(HandErr::InvalidRequest);
+        }
+
+        let mut body = vec![0; length];
+        stream
+            .read_exact(&mut body)
+            .map_err(|_| HandErr::InvalidRequest)?;
+        let body = String::from_utf8(body).map_err(|_| HandErr::InvalidRequest)?;
+
+        Ok(Self { method, path, body })
+    }
+}
+
+/// An HTTP response with a JSON body.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Response {
+    pub status: u16,
+    pub body: String,
+}
+
+impl Response {
+    /// A successful response.
+    pub fn ok(body: String) -> Self {
+        Self { status: 200, body }
+    }
+
+    /// A response for the error, with its code and message.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use mahc::hand::error::HandErr;
+    /// use mahc::serve::Response;
+    ///
+    /// let response = Response::error(&HandErr::NoYaku);
+    /// assert_eq!(response.status, 400);
+    /// assert_eq!(response.body, r#"{"error":{"code":"no_yaku","message":"No Yaku"}}"#);
+    /// ```
+    pub fn error(err: &HandErr) -> Self {
+        let status = match err {
+            HandErr::UnknownEndpoint => 404,
+            _ => 400,
+        };
+        let body = json!({
+            "error": {
+                "code": err.code(),
+                "message": err.to_string(),
+            }
+        });
+        Self {
+            status,
+            body: body.to_string(),
+        }
+    }
+
+    /// Write the response, letting a web page from `allow_origin` read it, if given.
+    pub fn write(
+        &self,
+        stream: &mut impl Write,
+        allow_origin: Option<&str>,
+    ) -> std::io::Result<()> {
+        let reason = match self.status {
+            200 => "OK",
+            204 => "No Content",
+            404 => "Not Found",
+            _ => "Bad Request",
+        };
+        let cors = match allow_origin {
+            Some(origin) => format!(
+                "Access-Control-Allow-Origin: {origin}\r\n\
+                 Access-Control-Allow-Methods: GET, POST, OPTIONS\r\n\
+                 Access-Control-Allow-Headers: Content-Type\r\n"
+            ),
+            None => String::new(),
+        };
+        write!(
+            stream,
+            "HTTP/1.1 {} {reason}\r\n\
+             Content-Type: application/json\r\n\
+             Content-Length: {}\r\n\
+             {cors}\
+             Connection: close\r\n\r\n{}",
+            self.status,
+            self.body.len(),
+            self.body
+        )?;
+        stream.flush()
+    }
+}
+
+/// A local HTTP server, answering one request per connection.
+///
+/// Web pages from other origins cannot read its responses unless an origin is allowed.
+#[derive(Debug)]
+pub struct Server {
+    listener: TcpListener,
+    /// Origin of the web pages allowed to call the API (CORS).
+    allow_origin: Option<String>,
+}
+
+impl Server {
+    /// Listen on the port of localhost only. Port 0 picks a free port.
+    pub fn bind(port: u16) -> std::io::Result<Self> {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port))?;
+        Ok(Self {
+            listener,
+            allow_origin: None,
+        })
+    }
+
+    /// Let web pages from the origin call the API (ex: `http://localhost:3000`, or `*` for any).
+    pub fn allow_origin(mut self, origin: String) -> Self {
+        self.allow_origin = Some(origin);
+        self
+    }
+
+    /// Get the port the server listens on.
+    pub fn port(&self) -> u16 {
+        self.listener
+            .local_addr()
+            .map(|addr| addr.port())
+            .unwrap_or_default()
+    }
+
+    /// Answer the next connection with the handler.
+    pub fn handle_one(&self, handler: impl Fn(&Request) -> Response) -> std::io::Result<()> {
+        let (stream, _) = self.listener.accept()?;
+        self.handle(stream, &handler)
+    }
+
+    /// Answer connections with the handler, each on its own thread, until the process ends.
+    pub fn run(&self, handler: impl Fn(&Request) -> Response + Sync) {
+        thread::scope(|scope| {
+            for stream in self.listener.incoming().map_while(Result::ok) {
+                // a client hanging up early or going idle only loses its own response
+                let handler = &handler;
+                scope.spawn(move || self.handle(stream, handler));
+            }
+        });
+    }
+
+    fn handle(
+        &self,
+        stream: TcpStream,
+        handler: &impl Fn(&Request) -> Response,
+    ) -> std::io::Result<()> {
+        stream.set_read_timeout(Some(TIMEOUT))?;
+        stream.set_write_timeout(Some(TIMEOUT))?;
+        let mut reader = BufReader::new(stream.try_clone()?);
+        let response = match Request::read(&mut reader) {
+            // preflight of a cross-origin request from a web page
+            Ok(request) if request.method == "OPTIONS" => Response {
+                status: 204,
+                body: String::new(),
+            },
+            Ok(request) => handler(&request),
+            Err(e) => Response::error(&e),
+        };
+        let mut stream = stream;
+        response.write(&mut stream, self.allow_origin.as_deref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use super::*;
+
+    #[test]
+    fn bad_request_line() {
+        assert_eq!(
+            Request::read(&mut "\r\n".as_bytes()).unwrap_err(),
+            HandErr::InvalidRequest
+        );
+        let too_big = format!(
+            "POST / HTTP/1.1\r\nContent-Length: {}\r\n\r\n",
+            MAX_BODY + 1
+        );
+        assert_eq!(
+            Request::read(&mut too_big.as_bytes()).unwrap_err(),
+            HandErr::InvalidRequest
+        );
+    }
+
+    #[test]
+    fn serves_on_localhost() {
+        let server = Server::bind(0).unwrap();
+        let port = server.port();
+        let client = std::thread::spawn(move || {
+            let mut stream = TcpStream::connect((Ipv4Addr::LOCALHOST, port)).unwrap();
+            stream
+                .write_all(b"POST /echo HTTP/1.1\r\nContent-Length: 2\r\n\r\n{}")
+                .unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            response
+        });
+
+        server
+            .handle_one(|request| Response::ok(format!("{:?}", request.path)))
+            .unwrap();
+        let response = client.join().unwrap();
+
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.contains("\r\nContent-Length: 7\r\n"));
+        assert!(!response.contains("Access-Control-Allow-Origin"));
+        assert!(response.ends_with("\r\n\r\n\"/echo\""));
+    }
+
+    #[test]
+    fn idle_client_does_not_block() {
+        let server = Server::bind(0)
+            .unwrap()
+            .allow_origin("http://localhost:3000".to_string());
+        let port = server.port();
+        thread::spawn(move || server.run(|request| Response::ok(format!("{:?}", request.path))));
+
+        // connects and never sends its request
+        let _idle = TcpStream::connect((Ipv4Addr::LOCALHOST, port)).unwrap();
+
+        let start = std::time::Instant::now();
+        let mut stream = TcpStream::connect((Ipv4Addr::LOCALHOST, port)).unwrap();
+        stream.write_all(b"GET /busy HTTP/1.1\r\n\r\n").unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+
+        assert!(start.elapsed() < TIMEOUT);
+        assert!(response.contains("\r\nAccess-Control-Allow-Origin: http://localhost:3000\r\n"));
+        assert!(response.ends_with("\"/busy\""));
+    }
+}
@@ -0,0 +1,369 @@
+use crate::hand::error::HandErr;
+use crate::payment::{Payment, PlayerPoints, RIICHI_STICK};
+use crate::score::{HonbaCounter, RiichiCounter};
+use crate::seat::Seat;
+
+/// How the hand is won.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WinMethod {
+    /// Win off a discard from the given seat.
+    Ron(Seat),
+    /// Win by self-draw.
+    Tsumo,
+}
+
+impl std::fmt::Display for WinMethod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Ron(seat) => write!(f, "Ron from {seat}"),
+            Self::Tsumo => write!(f, "Tsumo"),
+        }
+    }
+}
+
+/// Who won a hand, how, and what was on the table.
+///
+/// # Examples
+///
+/// ```rust
+/// use mahc::payment::Payment;
+/// use mahc::seat::Seat;
+/// use mahc::settlement::{Settlement, WinMethod};
+///
+/// // South tsumo for 1,000/2,000 with 1 honba and 2 riichi sticks on the table.
+/// let settlement = Settlement::new(Seat::South, WinMethod::Tsumo, 1, 2).unwrap();
+/// let payment = Payment::new(960);
+///
+/// assert_eq!(settlement.deltas(&payment), [-2_100, 6_300, -1_100, -1_100]);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Settlement {
+    /// Seat that won the hand.
+    winner: Seat,
+    /// How the hand is won.
+    method: WinMethod,
+    /// Number of repeat counters.
+    honba: HonbaCounter,
+    /// Number of riichi sticks on the table, including any declared this hand.
+    riichi_sticks: RiichiCounter,
+}
+
+impl Settlement {
+    /// Create a new [`Settlement`].
+    ///
+    /// The winner cannot ron off their own discard.
+    pub fn new(
+        winner: Seat,
+        method: WinMethod,
+        honba: HonbaCounter,
+        riichi_sticks: RiichiCounter,
+    ) -> Result<Self, HandErr> {
+        if method == WinMethod::Ron(winner) {
+            return Err(HandErr::RonOwnDiscard);
+        }
+
+        Ok(Self {
+            winner,
+            method,
+            honba,
+            riichi_sticks,
+        })
+    }
+
+    /// Get the seat that won the hand.
+    pub fn winner(&self) -> Seat {
+        self.winner
+    }
+
+    /// Get how the hand is won.
+    pub fn method(&self) -> WinMethod {
+        self.method
+    }
+
+    /// Get the change in points of every seat, indexed by seat.
+    ///
+    /// The winner also collects every riichi stick on the table. Sticks were already paid when
+    /// riichi was declared, so they are not taken from anyone here.
+    pub fn deltas(&self, payment: &Payment) -> [PlayerPoints; 4] {
+        let honba = self.honba;
+        let winner = self.winner;
+        let mut deltas: [PlayerPoints; 4] = [0; 4];
+
+        match self.method {
+            WinMethod::Ron(from) => {
+                let paid = if winner.is_dealer() {
+                    payment.dealer_ron(honba)
+                } else {
+                    payment.non_dealer_ron(honba)
+                };
+                deltas[from.index()] -= paid as PlayerPoints;
+            }
+            WinMethod::Tsumo => {
+                for other in Seat::ALL.into_iter().filter(|&other| other != winner) {
+                    let paid = if winner.is_dealer() {
+                        payment.dealer_tsumo(honba)
+                    } else if other.is_dealer() {
+                        payment.non_dealer_tsumo_to_dealer(honba)
+                    } else {
+                        payment.non_dealer_tsumo_to_non_dealer(honba)
+                    };
+                    deltas[other.index()] -= paid as PlayerPoints;
+                }
+            }
+        }
+
+        let paid: PlayerPoints = -deltas.iter().sum::<PlayerPoints>();
+        deltas[winner.index()] = paid + (self.riichi_sticks * RIICHI_STICK) as PlayerPoints;
+        deltas
+    }
+}
+
+/// Who collects the honba and riichi sticks when more than one player wins off the same
+/// discard.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MultiRonBonus {
+    /// The winner nearest to the discarder in turn order collects them.
+    Nearest,
+    /// Every winner is paid the honba, and the riichi sticks are split between the winners with
+    /// any left over going to the nearest winner.
+    All,
+}
+
+/// Rules for more than one player winning off the same discard.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MultiRonRules {
+    /// Who collects the honba and riichi sticks.
+    pub bonus: MultiRonBonus,
+    /// Whether three players winning off the same discard is an abortive draw.
+    pub triple_ron_draw: bool,
+    /// Whether only the winner nearest to the discarder wins (atamahane).
+    pub atamahane: bool,
+}
+
+impl Default for MultiRonRules {
+    fn default() -> Self {
+        Self {
+            bonus: MultiRonBonus::Nearest,
+            triple_ron_draw: true,
+            atamahane: false,
+        }
+    }
+}
+
+/// One or more players winning off the same discard, each scored separately.
+///
+/// # Examples
+///
+/// ```rust
+/// use mahc::payment::Payment;
+/// use mahc::seat::Seat;
+/// use mahc::settlement::{MultiRon, MultiRonRules};
+///
+/// // West deals into a 3,900 for East and a 2,000 for South, with 1 honba and 1 riichi stick.
+/// let winners = vec![(Seat::South, Payment::new(480)), (Seat::East, Payment::new(640))];
+/// let rules = MultiRonRules::default();
+/// let multi_ron = MultiRon::new(Seat::West, winners, 1, 1, rules).unwrap();
+///
+/// // Turn order from West is North, East, then South, so East is the nearest winner.
+/// assert_eq!(multi_ron.winners(), [Seat::East, Seat::South]);
+/// assert_eq!(multi_ron.deltas(), [5_200, 2_000, -6_200, 0]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct MultiRon {
+    /// Seat that dealt in.
+    from: Seat,
+    /// Winners and their payments, in turn order from the discarder.
+    winners: Vec<(Seat, Payment)>,
+    /// Number of repeat counters.
+    honba: HonbaCounter,
+    /// Number of riichi sticks on the table, including any declared this hand.
+    riichi_sticks: RiichiCounter,
+    rules: MultiRonRules,
+}
+
+impl MultiRon {
+    /// Create a new [`MultiRon`] off the discard of `from`.
+    ///
+    /// There has to be one to three winners, none of them twice and none of them the discarder.
+    pub fn new(
+        from: Seat,
+        mut winners: Vec<(Seat, Payment)>,
+        honba: HonbaCounter,
+        riichi_sticks: RiichiCounter,
+        rules: MultiRonRules,
+    ) -> Result<Self, HandErr> {
+        if winners.iter().any(|(winner, _)| *winner == from) {
+            return Err(HandErr::RonOwnDiscard);
+        }
+        winners.sort_by_key(|(winner, _)| (winner.index() + 4 - from.index()) % 4);
+        if winners.is_empty()
+            || winners.len() > 3
+            || winners.windows(2).any(|pair| pair[0].0 == pair[1].0)
+        {
+            return Err(HandErr::InvalidWinners);
+        }
+
+        Ok(Self {
+            from,
+            winners,
+            honba,
+            riichi_sticks,
+            rules,
+        })
+    }
+
+    /// Whether the hand ends in an abortive draw instead (triple ron).
+    pub fn is_draw(&self) -> bool {
+        self.rules.triple_ron_draw && self.winners.len() == 3
+    }
+
+    /// Get the seats that win, in turn order from the discarder.
+    ///
+    /// With atamahane only the nearest winner wins, and nobody wins an abortive draw.
+    pub fn winners(&self) -> Vec<Seat> {
+        if self.is_draw() {
+            return vec![];
+        }
+        let count = if self.rules.atamahane {
+            1
+        } else {
+            self.winners.len()
+        };
+        self.winners
+            .iter()
+            .take(count)
+            .map(|(winner, _)| *winner)
+            .collect()
+    }
+
+    /// Get the change in points of every seat, indexed by seat.
+    ///
+    /// An abortive draw changes nothing, and the riichi sticks stay on the table.
+    pub fn deltas(&self) -> [PlayerPoints; 4] {
+        let winners = self.winners();
+        let count = winners.len() as RiichiCounter;
+        let mut deltas: [PlayerPoints; 4] = [0; 4];
+
+        for (nearest, (winner, payment)) in self.winners.iter().take(winners.len()).enumerate() {
+            let (honba, riichi_sticks) = match (self.rules.bonus, nearest) {
+                (MultiRonBonus::Nearest, 0) => (self.honba, self.riichi_sticks),
+                (MultiRonBonus::Nearest, _) => (0, 0),
+                (MultiRonBonus::All, 0) => {
+                    let left_over = self.riichi_sticks % count;
+                    (self.honba, self.riichi_sticks / count + left_over)
+                }
+                (MultiRonBonus::All, _) => (self.honba, self.riichi_sticks / count),
+            };
+            // winners are never the discarder, so this cannot fail
+            let settlement = Settlement {
+                winner: *winner,
+                method: WinMethod::Ron(self.from),
+                honba,
+                riichi_sticks,
+            };
+            for (delta, change) in deltas.iter_mut().zip(settlement.deltas(payment)) {
+                *delta += change;
+            }
+        }
+
+        deltas
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::payment::Points;
+
+    #[test]
+    fn settle_dealer_ron() {
+        let settlement = Settlement::new(Seat::East, WinMethod::Ron(Seat::West), 2, 1).unwrap();
+        let payment = Payment::new(2_000);
+        assert_eq!(settlement.deltas(&payment), [13_600, 0, -12_600, 0]);
+    }
+
+    #[test]
+    fn settle_dealer_tsumo() {
+        let settlement = Settlement::new(Seat::East, WinMethod::Tsumo, 0, 0).unwrap();
+        let payment = Payment::new(320);
+        assert_eq!(settlement.deltas(&payment), [2_100, -700, -700, -700]);
+    }
+
+    #[test]
+    fn settle_non_dealer_ron_from_dealer() {
+        let settlement = Settlement::new(Seat::North, WinMethod::Ron(Seat::East), 0, 3).unwrap();
+        let payment = Payment::new(960);
+        assert_eq!(settlement.deltas(&payment), [-3_900, 0, 0, 6_900]);
+    }
+
+    #[test]
+    fn settle_ron_own_discard() {
+        let settlement = Settlement::new(Seat::South, WinMethod::Ron(Seat::South), 0, 0);
+        assert_eq!(settlement.unwrap_err(), HandErr::RonOwnDiscard);
+    }
+
+    fn ron_off_north(winners: &[(Seat, Points)], rules: MultiRonRules) -> MultiRon {
+        let winners = winners
+            .iter()
+            .map(|&(winner, base)| (winner, Payment::new(base)))
+            .collect();
+        MultiRon::new(Seat::North, winners, 2, 3, rules).unwrap()
+    }
+
+    #[test]
+    fn multi_ron_bonus_to_all() {
+        let rules = MultiRonRules {
+            bonus: MultiRonBonus::All,
+            ..MultiRonRules::default()
+        };
+        let multi_ron = ron_off_north(&[(Seat::West, 480), (Seat::East, 2_000)], rules);
+        assert_eq!(multi_ron.winners(), [Seat::East, Seat::West]);
+        // East gets 12,000 + 600 and 2 sticks, West gets 2,000 + 600 and 1 stick
+        assert_eq!(multi_ron.deltas(), [14_600, 0, 3_600, -15_200]);
+    }
+
+    #[test]
+    fn multi_ron_triple_ron_draw() {
+        let winners = [(Seat::East, 480), (Seat::South, 480), (Seat::West, 480)];
+        let multi_ron = ron_off_north(&winners, MultiRonRules::default());
+        assert!(multi_ron.is_draw());
+        assert!(multi_ron.winners().is_empty());
+        assert_eq!(multi_ron.deltas(), [0; 4]);
+
+        let rules = MultiRonRules {
+            triple_ron_draw: false,
+            ..MultiRonRules::default()
+        };
+        let multi_ron = ron_off_north(&winners, rules);
+        assert_eq!(multi_ron.deltas(), [6_500, 2_000, 2_000, -7_500]);
+    }
+
+    #[test]
+    fn multi_ron_atamahane() {
+        let rules = MultiRonRules {
+            atamahane: true,
+            ..MultiRonRules::default()
+        };
+        let multi_ron = ron_off_north(&[(Seat::West, 2_000), (Seat::South, 480)], rules);
+        assert_eq!(multi_ron.winners(), [Seat::South]);
+        assert_eq!(multi_ron.deltas(), [0, 5_600, 0, -2_600]);
+    }
+
+    #[test]
+    fn multi_ron_invalid_winners() {
+        let winners = vec![
+            (Seat::East, Payment::new(480)),
+            (Seat::East, Payment::new(480)),
+        ];
+        let rules = MultiRonRules::default();
+        let multi_ron = MultiRon::new(Seat::West, winners, 0, 0, rules);
+        assert_eq!(multi_ron.unwrap_err(), HandErr::InvalidWinners);
+
+        let multi_ron = MultiRon::new(Seat::West, vec![], 0, 0, rules);
+        assert_eq!(multi_ron.unwrap_err(), HandErr::InvalidWinners);
+
+        let winners = vec![(Seat::West, Payment::new(480))];
+        let multi_ron = MultiRon::new(Seat::West, winners, 0, 0, rules);
+        assert_eq!(multi_ron.unwrap_err(), HandErr::RonOwnDiscard);
+    }
+}
@@ -0,0 +1,358 @@
+use std::collections::HashMap;
+
+use crate::hand::error::HandErr;
+use crate::payment::Points;
+use crate::tenpai::{TileCounts, WinContext, TILE_KINDS};
+use crate::tile_group::TileGroup;
+
+/// Results of a simulation of our own draws from the rest of the wall.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Estimate {
+    /// Number of simulated hands.
+    pub trials: u32,
+    /// Number of hands that were tenpai at some point.
+    pub tenpai: u32,
+    /// Number of hands won by tsumo.
+    pub wins: u32,
+    /// Total points of every win.
+    pub points: Points,
+}
+
+impl Estimate {
+    /// Get the chance of reaching tenpai.
+    pub fn tenpai_rate(&self) -> f64 {
+        f64::from(self.tenpai) / f64::from(self.trials)
+    }
+
+    /// Get the chance of winning by tsumo.
+    pub fn win_rate(&self) -> f64 {
+        f64::from(self.wins) / f64::from(self.trials)
+    }
+
+    /// Get the average points of a hand, counting hands that did not win as `0`.
+    pub fn expected_value(&self) -> f64 {
+        self.points as f64 / f64::from(self.trials)
+    }
+
+    /// Get the average points of a winning hand.
+    pub fn average_win(&self) -> f64 {
+        if self.wins == 0 {
+            return 0.0;
+        }
+        self.points as f64 / f64::from(self.wins)
+    }
+}
+
+/// Seeded pseudo random number generator (SplitMix64), so results can be reproduced.
+struct Rng(u64);
+
+impl Rng {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Get a number in `0..bound`.
+    fn below(&mut self, bound: usize) -> usize {
+        ((u128::from(self.next_u64()) * bound as u128) >> 64) as usize
+    }
+}
+
+/// Monte Carlo simulation of a hand drawing from the rest of the wall.
+///
+/// Every tile that is not in our hand, our melds, the visible tiles, or the dora indicators is
+/// taken to be in the wall. Only our own draws are simulated: after each draw the hand wins by
+/// tsumo if it is complete with a yaku, and otherwise discards the tile that keeps it closest to
+/// tenpai with the most tiles that improve it.
+pub struct Simulation {
+    concealed: TileCounts,
+    melds: Vec<TileGroup>,
+    context: WinContext,
+    /// Copies of each tile kind seen outside of the concealed tiles.
+    known: [u8; TILE_KINDS],
+    /// Chosen discard for each hand seen so far.
+    discards: HashMap<TileCounts, usize>,
+    /// Points of each complete hand and winning tile seen so far.
+    wins: HashMap<(TileCounts, usize), Option<Points>>,
+}
+
+impl Simulation {
+    /// Create a new [`Simulation`] of a 13 tile hand.
+    ///
+    /// Wins are scored with the context as tsumo.
+    pub fn new(
+        concealed: &TileCounts,
+        melds: &[TileGroup],
+        visible: &[TileGroup],
+        context: &WinContext,
+    ) -> Result<Self, HandErr> {
+        if concealed.total() + 3 * melds.len() != 13 {
+            return Err(HandErr::InvalidShape);
+        }
+
+        let mut seen: Vec<TileGroup> = melds.iter().chain(visible.iter()).cloned().collect();
+        seen.extend(context.dora.iter().flatten().cloned());
+        let seen = TileCounts::new(&seen);
+
+        let mut known = [0; TILE_KINDS];
+        for (index, count) in known.iter_mut().enumerate() {
+            *count = seen.count(index);
+            if usize::from(*count + concealed.count(index)) > 4 {
+                return Err(HandErr::InvalidShape);
+            }
+        }
+
+        let mut context = context.clone();
+        context.tsumo = true;
+
+        Ok(Self {
+            concealed: concealed.clone(),
+            melds: melds.to_vec(),
+            context,
+            known,
+            discards: HashMap::new(),
+            wins: HashMap::new(),
+        })
+    }
+
+    /// Play the hand out `trials` times, drawing up to `draws` tiles each time.
+    ///
+    /// The same seed always gives the same estimate. There has to be at least one trial.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use mahc::simulation::Simulation;
+    /// use mahc::tenpai::{split_hand, TileCounts, WinContext};
+    ///
+    /// let tokens: Vec<String> = ["234m", "567p", "345s", "99p", "67s"]
+    ///     .iter()
+    ///     .map(|t| t.to_string())
+    ///     .collect();
+    /// let (concealed, melds) = split_hand(&tokens).unwrap();
+    /// let context = WinContext {
+    ///     seat: "Sw".to_string().try_into().unwrap(),
+    ///     prev: "Ew".to_string().try_into().unwrap(),
+    ///     dora: None,
+    ///     tsumo: true,
+    ///     riichi: false,
+    ///     honba: 0,
+    /// };
+    ///
+    /// let mut simulation = Simulation::new(&TileCounts::new(&concealed), &melds, &[], &context)
+    ///     .unwrap();
+    /// let estimate = simulation.run(200, 10, 7).unwrap();
+    ///
+    /// assert_eq!(estimate.tenpai_rate(), 1.0);
+    /// assert!(estimate.win_rate() > 0.0);
+    /// assert_eq!(estimate, simulation.run(200, 10, 7).unwrap());
+    /// ```
+    pub fn run(&mut self, trials: u32, draws: usize, seed: u64) -> Result<Estimate, HandErr> {
+        if trials == 0 {
+            return Err(HandErr::InvalidTrials);
+        }
+
+        let mut wall: Vec<usize> = (0..TILE_KINDS)
+            .flat_map(|index| {
+                let unseen = 4 - self.known[index] - self.concealed.count(index);
+                vec![index; unseen.into()]
+            })
+            .collect();
+        let draws = draws.min(wall.len());
+        let start_tenpai = self.concealed.shanten(self.melds.len()) <= 0;
+
+        let mut rng = Rng(seed);
+        let mut estimate = Estimate {
+            trials,
+            tenpai: 0,
+            wins: 0,
+            points: 0,
+        };
+
+        for _ in 0..trials {
+            let mut hand = self.concealed.clone();
+            let mut tenpai = start_tenpai;
+
+            for draw in 0..draws {
+                // only shuffle as much of the wall as is drawn
+                let pick = draw + rng.below(wall.len() - draw);
+                wall.swap(draw, pick);
+                let tile = wall[draw];
+                hand.add(tile);
+
+                if let Some(points) = self.win(&hand, tile) {
+                    tenpai = true;
+                    estimate.wins += 1;
+                    estimate.points += points;
+                    break;
+                }
+
+                let discard = self.discard(&hand);
+                hand.remove(discard);
+                tenpai |= hand.shanten(self.melds.len()) <= 0;
+            }
+
+            if tenpai {
+                estimate.tenpai += 1;
+            }
+        }
+
+        Ok(estimate)
+    }
+
+    /// Get the points of the best tsumo win with the hand, if it is complete with a yaku.
+    fn win(&mut self, hand: &TileCounts, tile: usize) -> Option<Points> {
+        if !hand.is_complete(self.melds.len()) {
+            return None;
+        }
+
+        let key = (hand.clone(), tile);
+        if let Some(points) = self.wins.get(&key) {
+            return *points;
+        }
+
+        let points = hand
+            .winning_hands(&self.melds, tile, &self.context)
+            .into_iter()
+            .filter_map(|hand| self.context.score(hand).ok())
+            .map(|score| self.context.points(&score))
+            .max();
+        self.wins.insert(key, points);
+
+        points
+    }
+
+    /// Choose the tile to discard from a 14 tile hand.
+    ///
+    /// Keeps the lowest shanten, then the most unseen tiles that lower it further, then discards
+    /// honors before terminals before middle tiles.
+    fn discard(&mut self, hand: &TileCounts) -> usize {
+        if let Some(&discard) = self.discards.get(hand) {
+            return discard;
+        }
+
+        let melds = self.melds.len();
+        let options: Vec<(usize, TileCounts, i8)> = (0..TILE_KINDS)
+            .filter(|&index| hand.count(index) != 0)
+            .map(|index| {
+                let mut after = hand.clone();
+                after.remove(index);
+                let shanten = after.shanten(melds);
+                (index, after, shanten)
+            })
+            .collect();
+        let lowest = options.iter().map(|option| option.2).min().unwrap();
+
+        let discard = options
+            .into_iter()
+            .filter(|option| option.2 == lowest)
+            .map(|(index, after, shanten)| {
+                let improving: usize = (0..TILE_KINDS)
+                    .map(|draw| {
+                        let unseen = 4u8.saturating_sub(self.known[draw] + after.count(draw));
+                        if unseen == 0 {
+                            return 0;
+                        }
+                        let mut next = after.clone();
+                        next.add(draw);
+                        if next.shanten(melds) < shanten {
+                            usize::from(unseen)
+                        } else {
+                            0
+                        }
+                    })
+                    .sum();
+                let kind = match index {
+                    27.. => 0,
+                    _ if index % 9 == 0 || index % 9 == 8 => 1,
+                    _ => 2,
+                };
+                (index, (std::cmp::Reverse(improving), kind))
+            })
+            .min_by_key(|option| option.1)
+            .unwrap()
+            .0;
+        self.discards.insert(hand.clone(), discard);
+
+        discard
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tenpai::split_hand;
+
+    fn context() -> WinContext {
+        WinContext {
+            seat: "Sw".to_string().try_into().unwrap(),
+            prev: "Ew".to_string().try_into().unwrap(),
+            dora: None,
+            tsumo: false,
+            riichi: false,
+            honba: 0,
+        }
+    }
+
+    fn simulation(tokens: &[&str], visible: &[&str]) -> Simulation {
+        let tokens: Vec<String> = tokens.iter().map(|t| t.to_string()).collect();
+        let (concealed, melds) = split_hand(&tokens).unwrap();
+        let visible: Vec<TileGroup> = visible
+            .iter()
+            .map(|t| t.to_string().try_into().unwrap())
+            .collect();
+        Simulation::new(&TileCounts::new(&concealed), &melds, &visible, &context()).unwrap()
+    }
+
+    #[test]
+    fn simulate_same_seed() {
+        let mut sim = simulation(&["234m", "567p", "35s", "99p", "6s", "Ew", "rd"], &[]);
+
+        let first = sim.run(100, 12, 42).unwrap();
+        assert_eq!(first, sim.run(100, 12, 42).unwrap());
+        assert_ne!(first, sim.run(100, 12, 43).unwrap());
+        assert!(first.win_rate() <= first.tenpai_rate());
+    }
+
+    #[test]
+    fn simulate_dead_wait() {
+        // every other 8s is visible, so the next draw can never win
+        let mut sim = simulation(&["123m", "456m", "789p", "EEEw", "8s"], &["888s"]);
+        let estimate = sim.run(50, 1, 0).unwrap();
+        assert_eq!(estimate.tenpai, 50);
+        assert_eq!(estimate.wins, 0);
+        assert_eq!(estimate.average_win(), 0.0);
+    }
+
+    #[test]
+    fn simulate_tenpai_wins() {
+        let mut sim = simulation(&["234m", "567p", "345s", "99p", "67s"], &[]);
+        let estimate = sim.run(200, 18, 0).unwrap();
+
+        assert_eq!(estimate.tenpai, 200);
+        assert!(estimate.wins > 100);
+        // mentsumo pinfu is at least 400/700
+        assert!(estimate.average_win() >= 1500.0);
+        assert_eq!(
+            estimate.expected_value(),
+            estimate.points as f64 / f64::from(estimate.trials)
+        );
+    }
+
+    #[test]
+    fn simulate_no_trials() {
+        let mut sim = simulation(&["234m", "567p", "345s", "99p", "67s"], &[]);
+        assert_eq!(sim.run(0, 10, 0).unwrap_err(), HandErr::InvalidTrials);
+    }
+
+    #[test]
+    fn simulate_wrong_tile_count() {
+        let tokens = vec!["123m".to_string()];
+        let (concealed, melds) = split_hand(&tokens).unwrap();
+        let out = Simulation::new(&TileCounts::new(&concealed), &melds, &[], &context());
+        assert_eq!(out.err().unwrap(), HandErr::InvalidShape);
+    }
+}
@@ -1,6 +1,7 @@
 use crate::hand::error::HandErr;
 
 #[derive(Debug, Clone, PartialEq, Hash, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Suit {
     Manzu,
     Pinzu,
@@ -29,9 +30,10 @@ impl Suit {
     ///
     /// assert_eq!(actual_suit, expected);
     /// ```
+    #[allow(clippy::ptr_arg)]
     pub fn suit_from_string(suit: &String, value: &String) -> Result<Self, HandErr> {
-        if vec!["s", "p", "m"].contains(&suit.as_str())
-            && !vec!["0", "1", "2", "3", "4", "5", "6", "7", "8", "9"].contains(&value.as_str())
+        if ["s", "p", "m"].contains(&suit.as_str())
+            && !["0", "1", "2", "3", "4", "5", "6", "7", "8", "9"].contains(&value.as_str())
         {
             return Err(HandErr::InvalidGroup);
         }
@@ -40,23 +42,23 @@ impl Suit {
             "p" => Ok(Self::Pinzu),
             "m" => Ok(Self::Manzu),
             "w" => {
-                if !vec!["E", "S", "W", "N"].contains(&value.as_str()) {
+                if !["E", "S", "W", "N"].contains(&value.as_str()) {
                     Err(HandErr::InvalidGroup)
                 } else {
                     Ok(Self::Wind)
                 }
             }
             "d" => {
-                if !vec!["r", "g", "w"].contains(&value.as_str()) {
+                if !["r", "g", "w"].contains(&value.as_str()) {
                     Err(HandErr::InvalidGroup)
                 } else {
                     Ok(Self::Dragon)
                 }
             }
             "z" => {
-                if vec!["1", "2", "3", "4"].contains(&value.as_str()) {
+                if ["1", "2", "3", "4"].contains(&value.as_str()) {
                     Ok(Self::Wind)
-                } else if vec!["5", "6", "7"].contains(&value.as_str()) {
+                } else if ["5", "6", "7"].contains(&value.as_str()) {
                     Ok(Self::Dragon)
                 } else {
                     Err(HandErr::InvalidGroup)
@@ -131,8 +133,7 @@ mod test {
         let value = "0".to_string();
         let suit = "z".to_string();
         let actual = Suit::suit_from_string(&suit, &value);
-        let expected = Err(HandErr::InvalidGroup); 
+        let expected = Err(HandErr::InvalidGroup);
         assert_eq!(actual, expected);
-
     }
 }
@@ -0,0 +1,678 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use crate::calc;
+use crate::hand::error::HandErr;
+use crate::hand::Hand;
+use crate::payment::Points;
+use crate::score::{HanValue, HonbaCounter, Score};
+use crate::tile_group::{count_tile, GroupType, TileGroup};
+
+/// Number of different kinds of tile.
+pub const TILE_KINDS: usize = 34;
+
+/// Number of sets, partial sets, and whether there is a pair, in a split of the tiles.
+type Shape = (i8, i8, bool);
+
+thread_local! {
+    /// Best splits of each suit seen so far, as shanten is checked many times per hand.
+    static SUIT_SHAPES: RefCell<HashMap<u32, Vec<Shape>>> = RefCell::new(HashMap::new());
+}
+
+/// Tile kinds that make up kokushi musou (thirteen orphans).
+const ORPHANS: [usize; 13] = [0, 8, 9, 17, 18, 26, 27, 28, 29, 30, 31, 32, 33];
+
+/// Round context used to score hands that have not been won yet.
+#[derive(Debug, Clone)]
+pub struct WinContext {
+    /// Seat wind.
+    pub seat: TileGroup,
+    /// Prevalent wind.
+    pub prev: TileGroup,
+    /// Dora indicator tiles.
+    pub dora: Option<Vec<TileGroup>>,
+    /// Score wins as tsumo instead of ron.
+    pub tsumo: bool,
+    /// Score closed hands as riichi.
+    pub riichi: bool,
+    /// Number of repeat counters.
+    pub honba: HonbaCounter,
+}
+
+impl WinContext {
+    /// Score a winning hand in this context.
+    pub fn score(&self, hand: Hand) -> Result<Score, HandErr> {
+        let riichi = self.riichi && !hand.is_open();
+        calc::get_hand_score(
+            hand,
+            self.dora.clone(),
+            self.tsumo,
+            riichi,
+            false,
+            false,
+            false,
+            false,
+            false,
+            false,
+            self.honba,
+        )
+    }
+
+    /// Get the number of points the score is worth to the player in this context.
+    pub fn points(&self, score: &Score) -> Points {
+        score
+            .payment()
+            .total(self.seat.value == "E", self.tsumo, self.honba)
+    }
+}
+
+/// The waits of a tenpai hand and the best score it can win with.
+#[derive(Debug)]
+pub struct Tenpai {
+    /// Tiles that complete the hand.
+    pub waits: Vec<TileGroup>,
+    /// Highest scoring win over all waits, if any wait has a yaku.
+    pub best: Option<Score>,
+}
+
+impl Tenpai {
+    /// Get the base points and han of the best score, for comparing hands.
+    pub fn value(&self) -> (Points, HanValue) {
+        self.best
+            .as_ref()
+            .map_or((0, 0), |score| (score.payment().base_points(), score.han()))
+    }
+}
+
+/// Number of copies of each kind of tile.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TileCounts {
+    counts: [u8; TILE_KINDS],
+    /// Number of akadora fives in manzu, pinzu, and souzu.
+    aka: [u8; 3],
+}
+
+impl TileCounts {
+    /// Count the tiles in the groups.
+    pub fn new(groups: &[TileGroup]) -> Self {
+        let mut counts = Self {
+            counts: [0; TILE_KINDS],
+            aka: [0; 3],
+        };
+        for tile in groups.iter().flat_map(|group| group.tiles()) {
+            let index = Self::index(&tile);
+            counts.counts[index] += 1;
+            if tile.isaka {
+                counts.aka[index / 9] += 1;
+            }
+        }
+
+        counts
+    }
+
+    /// Get the index of the tile kind, in the order manzu, pinzu, souzu, winds, and dragons.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use mahc::tenpai::TileCounts;
+    /// use mahc::tile_group::TileGroup;
+    ///
+    /// let tile: TileGroup = "3p".to_string().try_into().unwrap();
+    /// assert_eq!(TileCounts::index(&tile), 11);
+    /// assert_eq!(TileCounts::tile(11), tile);
+    /// ```
+    pub fn index(tile: &TileGroup) -> usize {
+        let (value, suit) = TileCounts::notation_of(tile);
+        (0..TILE_KINDS)
+            .find(|&index| TileCounts::notation(index) == (value, suit))
+            .unwrap()
+    }
+
+    /// Get a single tile of the tile kind.
+    pub fn tile(index: usize) -> TileGroup {
+        let (value, suit) = Self::notation(index);
+        format!("{value}{suit}").try_into().unwrap()
+    }
+
+    /// Get the number of copies of the tile kind.
+    pub fn count(&self, index: usize) -> u8 {
+        self.counts[index]
+    }
+
+    /// Get the total number of tiles.
+    pub fn total(&self) -> usize {
+        self.counts.iter().map(|&count| usize::from(count)).sum()
+    }
+
+    /// Add a copy of the tile kind.
+    pub fn add(&mut self, index: usize) {
+        self.counts[index] += 1;
+    }
+
+    /// Remove a copy of the tile kind, keeping akadora for as long as possible.
+    pub fn remove(&mut self, index: usize) {
+        self.counts[index] -= 1;
+        if index < 27 && index % 9 == 4 {
+            let aka = &mut self.aka[index / 9];
+            *aka = (*aka).min(self.counts[index]);
+        }
+    }
+
+    /// Get every single tile, in index order.
+    pub fn tiles(&self) -> Vec<TileGroup> {
+        let arrangement: Vec<(GroupType, usize)> = (0..TILE_KINDS)
+            .flat_map(|index| vec![(GroupType::None, index); self.count(index).into()])
+            .collect();
+
+        self.groups(&arrangement)
+    }
+
+    /// Find the waits of the tiles and melds, and the best score they can win with.
+    ///
+    /// Returns `None` if the hand is not tenpai. Waits are left out if every copy is in the hand.
+    pub fn tenpai(&self, melds: &[TileGroup], context: &WinContext) -> Option<Tenpai> {
+        let mut waits: Vec<TileGroup> = vec![];
+        let mut best: Option<Score> = None;
+
+        for win in 0..TILE_KINDS {
+            let win_tile = Self::tile(win);
+            if usize::from(self.count(win)) + count_tile(melds, &win_tile) >= 4 {
+                continue;
+            }
+
+            let mut complete = self.clone();
+            complete.add(win);
+            let hands = complete.winning_hands(melds, win, context);
+            if hands.is_empty() {
+                continue;
+            }
+            waits.push(win_tile);
+
+            for hand in hands {
+                let Ok(score) = context.score(hand) else {
+                    continue;
+                };
+                let value = (score.payment().base_points(), score.han());
+                let best_value = best.as_ref().map(|b| (b.payment().base_points(), b.han()));
+                if best_value.is_none_or(|best_value| value > best_value) {
+                    best = Some(score);
+                }
+            }
+        }
+
+        if waits.is_empty() {
+            return None;
+        }
+
+        Some(Tenpai { waits, best })
+    }
+
+    /// Arrange the tiles and melds into every possible winning hand, with the group the winning
+    /// tile completed last.
+    pub fn winning_hands(
+        &self,
+        melds: &[TileGroup],
+        win: usize,
+        context: &WinContext,
+    ) -> Vec<Hand> {
+        let mut hands: Vec<Hand> = vec![];
+
+        for arrangement in self.arrangements(melds.is_empty()) {
+            let groups = self.groups(&arrangement);
+            let mut tried: Vec<&(GroupType, usize)> = vec![];
+
+            for (i, shape) in arrangement.iter().enumerate() {
+                let contains_win = match shape.0 {
+                    GroupType::Sequence => (shape.1..shape.1 + 3).contains(&win),
+                    _ => shape.1 == win,
+                };
+                if !contains_win || tried.contains(&shape) {
+                    continue;
+                }
+                tried.push(shape);
+
+                let mut hand_groups: Vec<TileGroup> = melds.to_vec();
+                hand_groups.extend(
+                    groups
+                        .iter()
+                        .enumerate()
+                        .filter(|&(j, _)| j != i)
+                        .map(|(_, group)| group.clone()),
+                );
+                hand_groups.push(groups[i].clone());
+
+                if let Ok(hand) = Hand::new(
+                    hand_groups,
+                    Self::tile(win),
+                    context.seat.clone(),
+                    context.prev.clone(),
+                ) {
+                    hands.push(hand);
+                }
+            }
+        }
+
+        hands
+    }
+
+    /// Check if the tiles complete a hand with the given number of melds, ignoring yaku.
+    pub fn is_complete(&self, melds: usize) -> bool {
+        !self.arrangements(melds == 0).is_empty()
+    }
+
+    /// Get the number of tiles the hand is away from tenpai, where `0` is tenpai and `-1` is a
+    /// complete hand.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use mahc::tenpai::{split_hand, TileCounts};
+    ///
+    /// let tokens: Vec<String> = ["123m", "456p", "78s", "5s", "EEw", "Sw", "rd"]
+    ///     .iter()
+    ///     .map(|t| t.to_string())
+    ///     .collect();
+    /// let (concealed, melds) = split_hand(&tokens).unwrap();
+    ///
+    /// assert_eq!(TileCounts::new(&concealed).shanten(melds.len()), 2);
+    /// ```
+    pub fn shanten(&self, melds: usize) -> i8 {
+        // best (sets, partial sets, pair) splits of the suits seen so far
+        let mut splits: Vec<Shape> = vec![(melds as i8, 0, false)];
+        for start in [0, 9, 18, 27] {
+            let suit = Self::suit_best(&self.counts[start..(start + 9).min(TILE_KINDS)]);
+
+            let combined = splits
+                .iter()
+                .flat_map(|a| suit.iter().map(move |b| (a.0 + b.0, a.1 + b.1, a.2, b.2)))
+                .filter(|&(_, _, a, b)| !(a && b))
+                .map(|(sets, partials, a, b)| (sets, partials, a || b))
+                .collect();
+            splits = best_shapes(combined);
+        }
+
+        let mut best = splits
+            .iter()
+            .map(|&(sets, partials, pair)| 8 - 2 * sets - partials.min(4 - sets) - i8::from(pair))
+            .min()
+            .unwrap();
+
+        if melds == 0 {
+            let pairs = self.counts.iter().filter(|&&count| count >= 2).count() as i8;
+            let kinds = self.counts.iter().filter(|&&count| count != 0).count() as i8;
+            best = best.min(6 - pairs + (7 - kinds).max(0));
+
+            let orphans = ORPHANS.iter().filter(|&&i| self.count(i) != 0).count() as i8;
+            let orphan_pair = ORPHANS.iter().any(|&i| self.count(i) >= 2);
+            best = best.min(13 - orphans - i8::from(orphan_pair));
+        }
+
+        best
+    }
+
+    /// Get the best splits of a single suit into sets, partial sets, and a pair.
+    fn suit_best(counts: &[u8]) -> Vec<Shape> {
+        let is_suited = counts.len() == 9;
+        let key = counts.iter().fold(u32::from(is_suited), |key, &count| {
+            key * 5 + u32::from(count)
+        });
+
+        SUIT_SHAPES.with(|cache| {
+            if let Some(shapes) = cache.borrow().get(&key) {
+                return shapes.clone();
+            }
+
+            let mut shapes: Vec<Shape> = vec![];
+            Self::suit_shapes(
+                &mut counts.to_vec(),
+                0,
+                is_suited,
+                (0, 0, false),
+                &mut shapes,
+            );
+            let shapes = best_shapes(shapes);
+            cache.borrow_mut().insert(key, shapes.clone());
+            shapes
+        })
+    }
+
+    /// Search for the splits of a single suit into sets, partial sets, and a pair.
+    fn suit_shapes(
+        counts: &mut [u8],
+        start: usize,
+        is_suited: bool,
+        shape: Shape,
+        shapes: &mut Vec<Shape>,
+    ) {
+        let Some(index) = (start..counts.len()).find(|&i| counts[i] != 0) else {
+            shapes.push(shape);
+            if shapes.len() > 32 {
+                *shapes = best_shapes(std::mem::take(shapes));
+            }
+            return;
+        };
+
+        let (sets, partials, pair) = shape;
+        let mut take = |counts: &mut [u8], tiles: &[usize], shape: Shape| {
+            tiles.iter().for_each(|&i| counts[i] -= 1);
+            Self::suit_shapes(counts, index, is_suited, shape, shapes);
+            tiles.iter().for_each(|&i| counts[i] += 1);
+        };
+
+        if counts[index] >= 3 {
+            take(counts, &[index; 3], (sets + 1, partials, pair));
+        }
+        if is_suited && index <= 6 && counts[index + 1] != 0 && counts[index + 2] != 0 {
+            take(
+                counts,
+                &[index, index + 1, index + 2],
+                (sets + 1, partials, pair),
+            );
+        }
+        if !pair && counts[index] >= 2 {
+            take(counts, &[index; 2], (sets, partials, true));
+        }
+        // partial sets past four sets in total can never be used
+        if sets + partials < 4 {
+            if counts[index] >= 2 {
+                take(counts, &[index; 2], (sets, partials + 1, pair));
+            }
+            if is_suited && index <= 7 && counts[index + 1] != 0 {
+                take(counts, &[index, index + 1], (sets, partials + 1, pair));
+            }
+            if is_suited && index <= 6 && counts[index + 2] != 0 {
+                take(counts, &[index, index + 2], (sets, partials + 1, pair));
+            }
+        }
+
+        // leave the tile on its own
+        take(counts, &[index], shape);
+    }
+
+    /// Find every way to split the tiles into complete shapes, as the group type and first tile
+    /// index of each shape.
+    fn arrangements(&self, closed: bool) -> Vec<Vec<(GroupType, usize)>> {
+        let mut arrangements: Vec<Vec<(GroupType, usize)>> = vec![];
+        let mut counts = self.counts;
+        Self::standard(&mut counts, false, &mut vec![], &mut arrangements);
+
+        if closed && self.total() == 14 {
+            if self.counts.iter().all(|&count| count == 0 || count == 2) {
+                arrangements.push(
+                    (0..TILE_KINDS)
+                        .filter(|&index| self.count(index) == 2)
+                        .map(|index| (GroupType::Pair, index))
+                        .collect(),
+                );
+            }
+
+            if ORPHANS.iter().all(|&index| self.count(index) >= 1) {
+                arrangements.push(
+                    ORPHANS
+                        .iter()
+                        .map(|&index| match self.count(index) {
+                            2 => (GroupType::Pair, index),
+                            _ => (GroupType::None, index),
+                        })
+                        .collect(),
+                );
+            }
+        }
+
+        arrangements
+    }
+
+    /// Split the counts into sets and exactly one pair.
+    fn standard(
+        counts: &mut [u8; TILE_KINDS],
+        has_pair: bool,
+        shapes: &mut Vec<(GroupType, usize)>,
+        arrangements: &mut Vec<Vec<(GroupType, usize)>>,
+    ) {
+        let Some(index) = counts.iter().position(|&count| count != 0) else {
+            if has_pair {
+                arrangements.push(shapes.clone());
+            }
+            return;
+        };
+
+        if !has_pair && counts[index] >= 2 {
+            counts[index] -= 2;
+            shapes.push((GroupType::Pair, index));
+            Self::standard(counts, true, shapes, arrangements);
+            shapes.pop();
+            counts[index] += 2;
+        }
+
+        if counts[index] >= 3 {
+            counts[index] -= 3;
+            shapes.push((GroupType::Triplet, index));
+            Self::standard(counts, has_pair, shapes, arrangements);
+            shapes.pop();
+            counts[index] += 3;
+        }
+
+        if index < 27 && index % 9 <= 6 && counts[index + 1] != 0 && counts[index + 2] != 0 {
+            counts[index..index + 3]
+                .iter_mut()
+                .for_each(|count| *count -= 1);
+            shapes.push((GroupType::Sequence, index));
+            Self::standard(counts, has_pair, shapes, arrangements);
+            shapes.pop();
+            counts[index..index + 3]
+                .iter_mut()
+                .for_each(|count| *count += 1);
+        }
+    }
+
+    /// Turn shapes into concealed groups, handing out akadora to the first groups with a five.
+    fn groups(&self, shapes: &[(GroupType, usize)]) -> Vec<TileGroup> {
+        let mut aka = self.aka;
+
+        shapes
+            .iter()
+            .map(|(group_type, index)| {
+                let (value, suit) = Self::notation(*index);
+                let mut values = match group_type {
+                    GroupType::Sequence => (0..3)
+                        .map(|i| Self::notation(index + i).0)
+                        .collect::<String>(),
+                    _ => value.to_string().repeat(group_type.tile_count().into()),
+                };
+
+                if *index < 27 && values.contains('5') && aka[index / 9] != 0 {
+                    aka[index / 9] -= 1;
+                    values = values.replacen('5', "0", 1);
+                }
+
+                format!("{values}{suit}").try_into().unwrap()
+            })
+            .collect()
+    }
+
+    /// Get the value and suit characters of the tile kind.
+    fn notation(index: usize) -> (char, char) {
+        match index {
+            0..=26 => (
+                char::from_digit(index as u32 % 9 + 1, 10).unwrap(),
+                ['m', 'p', 's'][index / 9],
+            ),
+            27..=30 => (['E', 'S', 'W', 'N'][index - 27], 'w'),
+            _ => (['w', 'g', 'r'][index - 31], 'd'),
+        }
+    }
+
+    /// Get the value and suit characters of a single tile.
+    fn notation_of(tile: &TileGroup) -> (char, char) {
+        let single = tile.tiles().remove(0);
+        let notation = single.to_string().replace('0', "5");
+        let mut chars = notation.chars();
+        (chars.next().unwrap(), chars.next().unwrap())
+    }
+}
+
+/// Keep only the splits that are not beaten by another split with the same pair, with at least
+/// as many sets and partial sets.
+fn best_shapes(mut shapes: Vec<Shape>) -> Vec<Shape> {
+    shapes.sort_unstable_by(|a, b| b.cmp(a));
+    shapes.dedup();
+
+    let mut best: Vec<Shape> = vec![];
+    for shape in shapes {
+        let beaten = best
+            .iter()
+            .any(|other| other.2 == shape.2 && other.0 >= shape.0 && other.1 >= shape.1);
+        if !beaten {
+            best.push(shape);
+        }
+    }
+
+    best
+}
+
+/// Split hand notation into concealed single tiles and melds.
+///
+/// Open groups and kans are kept as melds. Everything else is split into single tiles, so partial
+/// shapes such as `78s` can be given.
+///
+/// # Examples
+///
+/// ```rust
+/// use mahc::tenpai::split_hand;
+///
+/// let tokens = vec!["123m".to_string(), "78s".to_string(), "EEEwo".to_string()];
+/// let (concealed, melds) = split_hand(&tokens).unwrap();
+///
+/// assert_eq!(concealed.len(), 5);
+/// assert_eq!(melds[0].to_string(), "EEEwo");
+/// ```
+pub fn split_hand(tokens: &[String]) -> Result<(Vec<TileGroup>, Vec<TileGroup>), HandErr> {
+    let mut concealed: Vec<TileGroup> = vec![];
+    let mut melds: Vec<TileGroup> = vec![];
+
+    for token in tokens {
+        let Some(suit) = token.chars().last() else {
+            return Err(HandErr::InvalidGroup);
+        };
+        let values = &token[..token.len() - suit.len_utf8()];
+
+        if let Ok(group) = TileGroup::try_from(token.clone()) {
+            let same_values = values
+                .replace('0', "5")
+                .chars()
+                .all(|v| group.value.starts_with(v));
+            if group.isopen || (group.group_type == GroupType::Kan && same_values) {
+                melds.push(group);
+                continue;
+            }
+        }
+
+        if values.is_empty() {
+            return Err(HandErr::InvalidGroup);
+        }
+        for value in values.chars() {
+            concealed.push(format!("{value}{suit}").try_into()?);
+        }
+    }
+
+    Ok((concealed, melds))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::yaku::Yaku;
+
+    fn context() -> WinContext {
+        WinContext {
+            seat: "Sw".to_string().try_into().unwrap(),
+            prev: "Ew".to_string().try_into().unwrap(),
+            dora: None,
+            tsumo: false,
+            riichi: false,
+            honba: 0,
+        }
+    }
+
+    fn counts(tokens: &[&str]) -> (TileCounts, Vec<TileGroup>) {
+        let tokens: Vec<String> = tokens.iter().map(|t| t.to_string()).collect();
+        let (concealed, melds) = split_hand(&tokens).unwrap();
+        (TileCounts::new(&concealed), melds)
+    }
+
+    #[test]
+    fn tenpai_ryanmen_pinfu() {
+        let (hand, melds) = counts(&["234m", "567p", "345s", "99p", "67s"]);
+        let tenpai = hand.tenpai(&melds, &context()).unwrap();
+
+        let waits: Vec<String> = tenpai.waits.iter().map(|t| t.to_string()).collect();
+        assert_eq!(waits, ["2s", "5s", "8s"]);
+        let best = tenpai.best.unwrap();
+        assert_eq!(best.han(), 1);
+        assert_eq!(best.fu_score(), 30);
+    }
+
+    #[test]
+    fn tenpai_multiple_arrangements() {
+        // 2345m waits on either end to make the pair
+        let (hand, melds) = counts(&["2345m", "678p", "345s", "rrrd"]);
+        let waits: Vec<String> = hand
+            .tenpai(&melds, &context())
+            .unwrap()
+            .waits
+            .iter()
+            .map(|t| t.to_string())
+            .collect();
+        assert_eq!(waits, ["2m", "5m"]);
+    }
+
+    #[test]
+    fn tenpai_chiitoitsu_and_kokushi() {
+        let (hand, melds) = counts(&["11m", "44p", "77p", "22s", "55s", "EEw", "rd"]);
+        let tenpai = hand.tenpai(&melds, &context()).unwrap();
+        assert_eq!(tenpai.waits.len(), 1);
+        assert_eq!(tenpai.best.unwrap().yaku(), [Yaku::Chiitoitsu]);
+
+        let (hand, melds) = counts(&["19m", "19p", "19s", "ESWNw", "wgrd"]);
+        let tenpai = hand.tenpai(&melds, &context()).unwrap();
+        assert_eq!(tenpai.waits.len(), 13);
+    }
+
+    #[test]
+    fn not_tenpai() {
+        let (hand, melds) = counts(&["159m", "159p", "159s", "ESWw", "rd"]);
+        assert!(hand.tenpai(&melds, &context()).is_none());
+    }
+
+    #[test]
+    fn tile_counts_keep_akadora() {
+        let (mut hand, _) = counts(&["055p"]);
+        assert_eq!(hand.tiles()[0].to_string(), "0p");
+
+        hand.remove(13);
+        assert_eq!(hand.tiles()[0].to_string(), "0p");
+        hand.remove(13);
+        assert_eq!(hand.tiles()[0].to_string(), "0p");
+        hand.remove(13);
+        assert_eq!(hand.total(), 0);
+    }
+
+    #[test]
+    fn shanten_counts() {
+        let (hand, melds) = counts(&["234m", "567p", "345s", "99p", "67s"]);
+        assert_eq!(hand.shanten(melds.len()), 0);
+
+        let (hand, melds) = counts(&["234m", "567p", "345s", "99p", "67s", "8s"]);
+        assert_eq!(hand.shanten(melds.len()), -1);
+
+        let (hand, melds) = counts(&["11m", "44p", "77p", "22s", "58s", "EEw", "rd"]);
+        assert_eq!(hand.shanten(melds.len()), 1);
+
+        let (hand, melds) = counts(&["19m", "19p", "19s", "ESWNw", "wgd", "5p"]);
+        assert_eq!(hand.shanten(melds.len()), 1);
+
+        let (hand, melds) = counts(&["EEEwo", "rrrdo", "123p", "45s", "9m"]);
+        assert_eq!(hand.shanten(melds.len()), 1);
+    }
+}
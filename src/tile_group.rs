@@ -1,7 +1,16 @@
 use crate::hand::error::HandErr;
 use crate::suit::Suit;
 
+/// A tile or group of tiles.
+///
+/// With the `serde` feature, a group is (de)serialized as the notation it is parsed from
+/// (ex: `"123po"`).
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "String", into = "String")
+)]
 pub struct TileGroup {
     pub value: String,
     pub suit: Suit,
@@ -63,6 +72,32 @@ impl TryFrom<String> for TileGroup {
     }
 }
 
+impl std::fmt::Display for TileGroup {
+    /// Write the group back out in the notation it is parsed from.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let suit = match self.suit {
+            Suit::Manzu => "m",
+            Suit::Pinzu => "p",
+            Suit::Souzu => "s",
+            Suit::Wind => "w",
+            Suit::Dragon => "d",
+        };
+        let mut values: String = self.tiles().iter().map(|t| t.value.as_str()).collect();
+        if self.isaka {
+            values = values.replacen('5', "0", 1);
+        }
+        let open = if self.isopen { "o" } else { "" };
+
+        write!(f, "{values}{suit}{open}")
+    }
+}
+
+impl From<TileGroup> for String {
+    fn from(group: TileGroup) -> Self {
+        group.to_string()
+    }
+}
+
 impl TileGroup {
     fn new(
         value: String,
@@ -89,6 +124,55 @@ impl TileGroup {
         matches!(self.suit, Suit::Wind | Suit::Dragon)
     }
 
+    /// Check if the group starts on the same tile as another group, ignoring akadora and calls.
+    pub fn is_same_tile(&self, other: &TileGroup) -> bool {
+        self.value == other.value && self.suit == other.suit
+    }
+
+    /// Split the group into its single tiles.
+    ///
+    /// The akadora of an akadora group is given to its first five.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use mahc::tile_group::TileGroup;
+    ///
+    /// let group: TileGroup = "406m".to_string().try_into().unwrap();
+    /// let tiles = group.tiles();
+    /// let values: Vec<&str> = tiles.iter().map(|tile| tile.value.as_str()).collect();
+    ///
+    /// assert_eq!(values, ["4", "5", "6"]);
+    /// assert!(tiles[1].isaka);
+    /// ```
+    pub fn tiles(&self) -> Vec<TileGroup> {
+        let values: Vec<String> = match self.group_type {
+            GroupType::Sequence => {
+                let start = self.parse_u8().unwrap();
+                (start..start + 3).map(|value| value.to_string()).collect()
+            }
+            _ => vec![self.value.clone(); self.group_type.tile_count().into()],
+        };
+
+        let mut aka_left = self.isaka;
+        values
+            .into_iter()
+            .map(|value| {
+                let isaka = aka_left && value == "5";
+                aka_left &= !isaka;
+                let isterminal = !self.is_honor() && (value == "1" || value == "9");
+                Self {
+                    value,
+                    suit: self.suit.clone(),
+                    isopen: self.isopen,
+                    group_type: GroupType::None,
+                    isterminal,
+                    isaka,
+                }
+            })
+            .collect()
+    }
+
     /// Parse the group value into an integer.
     pub fn parse_u8(&self) -> Result<u8, std::num::ParseIntError> {
         self.value.parse()
@@ -138,20 +222,44 @@ impl TileGroup {
                 _ => return Err(HandErr::InvalidGroup),
             },
         };
-        Ok(Self::new(
+        Self::new(
             value,
             self.suit.clone(),
             false,
             self.group_type.clone(),
             false,
             false,
-        )?)
+        )
     }
 }
 
+/// Count the copies of a tile across the groups, ignoring akadora and calls.
+///
+/// # Examples
+///
+/// ```rust
+/// use mahc::tile_group::{count_tile, TileGroup};
+///
+/// let groups: Vec<TileGroup> = vec![
+///     "345p".to_string().try_into().unwrap(),
+///     "055p".to_string().try_into().unwrap(),
+/// ];
+/// let tile: TileGroup = "5p".to_string().try_into().unwrap();
+///
+/// assert_eq!(count_tile(&groups, &tile), 4);
+/// ```
+pub fn count_tile(groups: &[TileGroup], tile: &TileGroup) -> usize {
+    groups
+        .iter()
+        .flat_map(|group| group.tiles())
+        .filter(|t| t.is_same_tile(tile))
+        .count()
+}
+
 //AHAHAHAHAHAHAHAH I DONT NEED THIS
 //turns our i did need this :)
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum GroupType {
     Sequence,
     Triplet,
@@ -205,9 +313,9 @@ impl GroupType {
                     .collect::<std::collections::HashSet<&str>>()
                     .contains(group.get(0..count).unwrap())
                 {
-                    return Ok(Self::Sequence);
+                    Ok(Self::Sequence)
                 } else {
-                    return Err(HandErr::InvalidGroup);
+                    Err(HandErr::InvalidGroup)
                 }
             }
             4 => Ok(Self::Kan),
@@ -228,6 +336,7 @@ impl GroupType {
 }
 
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
 mod tests {
     use super::*;
 
@@ -394,4 +503,26 @@ mod tests {
         let next_tile = tile.next_tile().unwrap();
         assert_eq!(next_tile.value, "6");
     }
+
+    #[test]
+    fn tilegroup_to_string() {
+        for group in ["1m", "406s", "055po", "EEEEw", "rrdo", "789p"] {
+            let tile = TileGroup::try_from(group.to_string()).unwrap();
+            assert_eq!(tile.to_string(), group);
+        }
+
+        let tile = TileGroup::try_from("777z".to_string()).unwrap();
+        assert_eq!(tile.to_string(), "rrrd");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn tilegroup_serde_notation() {
+        let tile = TileGroup::try_from("055po".to_string()).unwrap();
+        assert_eq!(serde_json::to_string(&tile).unwrap(), "\"055po\"");
+
+        let actual: TileGroup = serde_json::from_str("\"777z\"").unwrap();
+        assert_eq!(actual.suit, Suit::Dragon);
+        assert!(serde_json::from_str::<TileGroup>("\"12x\"").is_err());
+    }
 }
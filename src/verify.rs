@@ -0,0 +1,280 @@
+use crate::calc::{self, HandValue};
+use crate::hand::error::HandErr;
+use crate::payment::{ClaimedPayment, Points};
+use crate::score::Score;
+use crate::score_table::TableRules;
+
+/// One payment of a claim, next to what it should be.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Component {
+    /// Who pays (`Ron`, `Each`, `Non-dealer`, or `Dealer`).
+    pub payer: &'static str,
+    pub claimed: Points,
+    pub actual: Points,
+}
+
+impl Component {
+    /// Get how much the claim is over (or under, if negative) the actual payment.
+    pub fn difference(&self) -> i64 {
+        self.claimed as i64 - self.actual as i64
+    }
+}
+
+/// A claimed payment checked against the score of the hand.
+///
+/// # Examples
+///
+/// ```rust
+/// use mahc::calc;
+/// use mahc::hand::Hand;
+/// use mahc::payment::ClaimedPayment;
+/// use mahc::score_table::TableRules;
+/// use mahc::verify::Verification;
+///
+/// // riichi, tsumo, and pinfu for a non-dealer
+/// let tiles = ["123p", "456p", "99s", "234s", "678m"].map(|t| t.to_string()).to_vec();
+/// let hand = Hand::new_from_strings(tiles, "6m".into(), "Ew".into(), "Sw".into()).unwrap();
+/// let score = calc::get_hand_score(
+///     hand, None, true, true, false, false, false, false, false, false, 0,
+/// )
+/// .unwrap();
+///
+/// let claim: ClaimedPayment = "1000-2000".to_string().try_into().unwrap();
+/// let rules = TableRules::default();
+/// let verification = Verification::new(&score, false, true, claim, &rules).unwrap();
+///
+/// assert!(!verification.is_correct());
+/// assert_eq!(verification.actual().to_string(), "700-1300");
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Verification {
+    claim: ClaimedPayment,
+    actual: ClaimedPayment,
+    /// Han and fu, or limit hands, the claim would be correct for.
+    claimed_values: Vec<HandValue>,
+}
+
+impl Verification {
+    /// Check the claimed payment of a hand won by the dealer, or by a non-dealer, by tsumo or
+    /// ron.
+    ///
+    /// Ron claims are only for a ron, and tsumo claims only for a tsumo that matches the winner:
+    /// `2600all` for the dealer and `1300-2600` for a non-dealer. The han and fu the claim is
+    /// for are looked up under the rules, with the honba of the score.
+    pub fn new(
+        score: &Score,
+        dealer: bool,
+        tsumo: bool,
+        claim: ClaimedPayment,
+        rules: &TableRules,
+    ) -> Result<Self, HandErr> {
+        if tsumo == matches!(claim, ClaimedPayment::Ron(_)) {
+            return Err(HandErr::ClaimMethodMismatch);
+        }
+        let payment = score.payment();
+        let honba = score.honba();
+        let actual = match (claim, dealer) {
+            (ClaimedPayment::Ron(_), true) => ClaimedPayment::Ron(payment.dealer_ron(honba)),
+            (ClaimedPayment::Ron(_), false) => ClaimedPayment::Ron(payment.non_dealer_ron(honba)),
+            (ClaimedPayment::DealerTsumo(_), true) => {
+                ClaimedPayment::DealerTsumo(payment.dealer_tsumo(honba))
+            }
+            (ClaimedPayment::NonDealerTsumo { .. }, false) => ClaimedPayment::NonDealerTsumo {
+                non_dealer: payment.non_dealer_tsumo_to_non_dealer(honba),
+                dealer: payment.non_dealer_tsumo_to_dealer(honba),
+            },
+            _ => return Err(HandErr::ClaimWinnerMismatch),
+        };
+
+        let rules = TableRules { honba, ..*rules };
+        let lookup = calc::reverse_lookup(&claim, &rules);
+        let claimed_values = if dealer {
+            lookup.dealer
+        } else {
+            lookup.non_dealer
+        };
+
+        Ok(Self {
+            claim,
+            actual,
+            claimed_values,
+        })
+    }
+
+    /// Check if the claim is the payment the hand is worth.
+    pub fn is_correct(&self) -> bool {
+        self.claim == self.actual
+    }
+
+    /// Get the claimed payment.
+    pub fn claim(&self) -> ClaimedPayment {
+        self.claim
+    }
+
+    /// Get the payment the hand is worth, in the same form as the claim.
+    pub fn actual(&self) -> ClaimedPayment {
+        self.actual
+    }
+
+    /// Get each payment of the claim next to what it should be.
+    pub fn components(&self) -> Vec<Component> {
+        let component = |payer, claimed, actual| Component {
+            payer,
+            claimed,
+            actual,
+        };
+        match (self.claim, self.actual) {
+            (ClaimedPayment::Ron(claimed), ClaimedPayment::Ron(actual)) => {
+                vec![component("Ron", claimed, actual)]
+            }
+            (ClaimedPayment::DealerTsumo(claimed), ClaimedPayment::DealerTsumo(actual)) => {
+                vec![component("Each", claimed, actual)]
+            }
+            (
+                ClaimedPayment::NonDealerTsumo { non_dealer, dealer },
+                ClaimedPayment::NonDealerTsumo {
+                    non_dealer: actual_non_dealer,
+                    dealer: actual_dealer,
+                },
+            ) => vec![
+                component("Non-dealer", non_dealer, actual_non_dealer),
+                component("Dealer", dealer, actual_dealer),
+            ],
+            // the actual payment is always built in the same form as the claim
+            _ => unreachable!(),
+        }
+    }
+
+    /// Get the han and fu, or limit hands, the claim would be correct for.
+    pub fn claimed_values(&self) -> &[HandValue] {
+        &self.claimed_values
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hand::Hand;
+    use crate::limit_hand::LimitHands;
+    use crate::payment::SanmaTsumo;
+    use crate::sanma::SanmaRules;
+
+    /// Riichi pinfu, with the given seat.
+    fn score(seat: &str, tsumo: bool, riichi: bool) -> Score {
+        let tiles = ["123p", "456p", "99s", "234s", "678m"].map(|t| t.to_string());
+        let hand =
+            Hand::new_from_strings(tiles.to_vec(), "6m".into(), "Ew".into(), seat.to_string())
+                .unwrap();
+        calc::get_hand_score(
+            hand, None, tsumo, riichi, false, false, false, false, false, false, 0,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn verify_correct_ron() {
+        // dealer riichi pinfu ron
+        let score = score("Ew", false, true);
+        let verification = Verification::new(
+            &score,
+            true,
+            false,
+            ClaimedPayment::Ron(2_900),
+            &TableRules::default(),
+        )
+        .unwrap();
+        assert!(verification.is_correct());
+        assert_eq!(verification.components()[0].difference(), 0);
+        assert_eq!(
+            verification.claimed_values(),
+            [HandValue::HanFu(1, 60), HandValue::HanFu(2, 30)]
+        );
+    }
+
+    #[test]
+    fn verify_overclaimed_limit() {
+        // riichi, tsumo, and pinfu claimed as a mangan
+        let score = score("Sw", true, true);
+        let claim = ClaimedPayment::NonDealerTsumo {
+            non_dealer: 2_000,
+            dealer: 4_000,
+        };
+        let verification =
+            Verification::new(&score, false, true, claim, &TableRules::default()).unwrap();
+
+        assert!(!verification.is_correct());
+        let differences: Vec<i64> = verification
+            .components()
+            .iter()
+            .map(Component::difference)
+            .collect();
+        assert_eq!(differences, [1_300, 2_700]);
+        assert!(verification
+            .claimed_values()
+            .contains(&HandValue::Limit(LimitHands::Mangan)));
+    }
+
+    #[test]
+    fn verify_claim_wrong_winner() {
+        let score = score("Sw", true, true);
+        let verification = Verification::new(
+            &score,
+            false,
+            true,
+            ClaimedPayment::DealerTsumo(700),
+            &TableRules::default(),
+        );
+        assert_eq!(verification.unwrap_err(), HandErr::ClaimWinnerMismatch);
+    }
+
+    #[test]
+    fn verify_claim_wrong_method() {
+        let rules = TableRules::default();
+        let ron = score("Sw", false, true);
+        let claim = ClaimedPayment::NonDealerTsumo {
+            non_dealer: 500,
+            dealer: 1_000,
+        };
+        let verification = Verification::new(&ron, false, false, claim, &rules);
+        assert_eq!(verification.unwrap_err(), HandErr::ClaimMethodMismatch);
+
+        let tsumo = score("Sw", true, true);
+        let verification =
+            Verification::new(&tsumo, false, true, ClaimedPayment::Ron(2_000), &rules);
+        assert_eq!(verification.unwrap_err(), HandErr::ClaimMethodMismatch);
+    }
+
+    #[test]
+    fn verify_lookup_follows_rules() {
+        // riichi and pinfu claimed as 4 han 30 fu, which is a mangan with kiriage
+        let score = score("Sw", false, true);
+        let rules = TableRules {
+            kiriage: true,
+            ..TableRules::default()
+        };
+        let verification =
+            Verification::new(&score, false, false, ClaimedPayment::Ron(8_000), &rules).unwrap();
+        assert!(verification
+            .claimed_values()
+            .contains(&HandValue::HanFu(4, 30)));
+
+        // a mangan tsumo where the missing share is split between the other two players
+        let score = self::score("Sw", true, true);
+        let rules = TableRules {
+            sanma: Some(SanmaRules {
+                tsumo: SanmaTsumo::NorthBisection,
+                ..SanmaRules::default()
+            }),
+            ..TableRules::default()
+        };
+        let claim = ClaimedPayment::NonDealerTsumo {
+            non_dealer: 3_000,
+            dealer: 5_000,
+        };
+        let verification = Verification::new(&score, false, true, claim, &rules).unwrap();
+        assert_eq!(
+            verification.claimed_values(),
+            [HandValue::Limit(LimitHands::Mangan)]
+        );
+    }
+}
@@ -1,6 +1,8 @@
 use crate::score::HanValue;
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "python", pyo3::pyclass(eq, eq_int, module = "mahc"))]
 pub enum Yaku {
     // One Han Yaku
     Tanyao,   //done
@@ -145,6 +147,102 @@ impl Yaku {
         .to_string()
     }
 
+    /// Get the id of the yaku, which does not change between versions.
+    pub fn id(&self) -> &'static str {
+        match self {
+            Self::Tanyao => "tanyao",
+            Self::Iipeikou => "iipeikou",
+            Self::Yakuhai => "yakuhai",
+            Self::MenzenTsumo => "menzen_tsumo",
+            Self::Pinfu => "pinfu",
+            Self::Riichi => "riichi",
+            Self::Ippatsu => "ippatsu",
+            Self::Haitei => "haitei",
+            Self::RinshanKaihou => "rinshan_kaihou",
+            Self::Chankan => "chankan",
+            Self::DoubleRiichi => "double_riichi",
+            Self::Toitoi => "toitoi",
+            Self::Ittsuu => "ittsuu",
+            Self::SanshokuDoujun => "sanshoku_doujun",
+            Self::Chantaiyao => "chantaiyao",
+            Self::Sanankou => "sanankou",
+            Self::SanshokuDoukou => "sanshoku_doukou",
+            Self::Sankantsu => "sankantsu",
+            Self::Honroutou => "honroutou",
+            Self::Shousangen => "shousangen",
+            Self::Chiitoitsu => "chiitoitsu",
+            Self::Honitsu => "honitsu",
+            Self::JunchanTaiyao => "junchan_taiyao",
+            Self::Ryanpeikou => "ryanpeikou",
+            Self::Chinitsu => "chinitsu",
+            Self::KazoeYakuman => "kazoe_yakuman",
+            Self::KokushiMusou => "kokushi_musou",
+            Self::KokushiMusou13SidedWait => "kokushi_musou_13_sided_wait",
+            Self::Suuankou => "suuankou",
+            Self::SuuankouTankiWait => "suuankou_tanki_wait",
+            Self::Daisangen => "daisangen",
+            Self::Shousuushii => "shousuushii",
+            Self::Daisuushii => "daisuushii",
+            Self::Tsuuiisou => "tsuuiisou",
+            Self::Daichiishin => "daichiishin",
+            Self::Chinroutou => "chinroutou",
+            Self::Ryuuiisou => "ryuuiisou",
+            Self::ChuurenPoutou => "chuuren_poutou",
+            Self::ChuurenPoutou9SidedWait => "chuuren_poutou_9_sided_wait",
+            Self::Suukantsu => "suukantsu",
+            Self::Tenhou => "tenhou",
+            Self::Chiihou => "chiihou",
+        }
+    }
+
+    /// Get the name of the yaku.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Tanyao => "Tanyao",
+            Self::Iipeikou => "Iipeikou",
+            Self::Yakuhai => "Yakuhai",
+            Self::MenzenTsumo => "Menzen Tsumo",
+            Self::Pinfu => "Pinfu",
+            Self::Riichi => "Riichi",
+            Self::Ippatsu => "Ippatsu",
+            Self::Haitei => "Haitei",
+            Self::RinshanKaihou => "Rinshan Kaihou",
+            Self::Chankan => "Chankan",
+            Self::DoubleRiichi => "Double Riichi",
+            Self::Toitoi => "Toitoi",
+            Self::Ittsuu => "Ittsuu",
+            Self::SanshokuDoujun => "Sanshoku Doujun",
+            Self::Chantaiyao => "Chantaiyao",
+            Self::Sanankou => "Sanankou",
+            Self::SanshokuDoukou => "Sanshoku Doukou",
+            Self::Sankantsu => "Sankantsu",
+            Self::Honroutou => "Honroutou",
+            Self::Shousangen => "Shousangen",
+            Self::Chiitoitsu => "Chiitoitsu",
+            Self::Honitsu => "Honitsu",
+            Self::JunchanTaiyao => "Junchan Taiyao",
+            Self::Ryanpeikou => "Ryanpeikou",
+            Self::Chinitsu => "Chinitsu",
+            Self::KazoeYakuman => "Kazoe Yakuman",
+            Self::KokushiMusou => "Kokushi Musou",
+            Self::KokushiMusou13SidedWait => "Kokushi Musou 13-sided Wait",
+            Self::Suuankou => "Suuankou",
+            Self::SuuankouTankiWait => "Suuankou Tanki Wait",
+            Self::Daisangen => "Daisangen",
+            Self::Shousuushii => "Shousuushii",
+            Self::Daisuushii => "Daisuushii",
+            Self::Tsuuiisou => "Tsuuiisou",
+            Self::Daichiishin => "Daichiishin",
+            Self::Chinroutou => "Chinroutou",
+            Self::Ryuuiisou => "Ryuuiisou",
+            Self::ChuurenPoutou => "Chuuren Poutou",
+            Self::ChuurenPoutou9SidedWait => "Chuuren Poutou 9-sided Wait",
+            Self::Suukantsu => "Suukantsu",
+            Self::Tenhou => "Tenhou",
+            Self::Chiihou => "Chiihou",
+        }
+    }
+
     /// Get the han value of the yaku.
     pub fn get_han(&self, is_open: bool) -> HanValue {
         match self {
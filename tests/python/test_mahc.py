@@ -0,0 +1,87 @@
+import pytest
+
+import mahc
+
+
+def test_score():
+    hand = mahc.Hand(["123p", "456p", "789s", "rrrd", "EEw"], "3p", seat="Sw")
+    score = mahc.get_hand_score(hand, dora=["1p"], riichi=True)
+
+    assert score.han == 3
+    assert score.fu == 50
+    assert score.dora == 1
+    assert not score.is_open
+    assert mahc.Yaku.Riichi in score.yaku
+    assert "yakuhai" in [yaku.id for yaku in score.yaku]
+    assert sum(fu.value for fu in score.fu_breakdown) == 42
+    assert score.payment.non_dealer_ron() == 6400
+
+
+def test_four_player_nukidora():
+    hand = mahc.Hand(["123p", "456p", "789s", "rrrd", "EEw"], "3p", nukidora=1)
+    with pytest.raises(mahc.MahcError) as err:
+        mahc.get_hand_score(hand)
+    assert err.value.code == "nukidora_outside_sanma"
+
+    score = mahc.get_hand_score(hand, nukidora=True)
+    assert score.dora == 1
+    assert score.han == 2
+
+
+def test_tsumo_payment():
+    hand = mahc.Hand(["234m", "456p", "678s", "22s", "567p"], "5p")
+    score = mahc.get_hand_score(hand, tsumo=True, honba=1)
+
+    assert [yaku.id for yaku in score.yaku] == ["tanyao", "menzen_tsumo", "pinfu"]
+    assert score.fu == 20
+    assert score.honba == 1
+    assert score.payment.dealer_tsumo(score.honba) == 1400
+
+
+def test_yaku_and_fu():
+    assert mahc.Yaku.Riichi.name == "Riichi"
+    assert mahc.Yaku.Honitsu.han() == 3
+    assert mahc.Yaku.Honitsu.han(is_open=True) == 2
+    assert mahc.Yaku.KokushiMusou.is_yakuman
+    assert mahc.Fu.BasePoints.value == 20
+
+
+def test_hand():
+    hand = mahc.Hand(["rrrdo", "123p", "555s", "77m", "678m"], "8m", prev="Sw")
+
+    assert hand.is_open
+    assert hand.win_tile == mahc.TileGroup("8m")
+    assert hand.prev_tile.value == "S"
+    assert hand.wait == "ryanmen"
+    assert [group.group_type for group in hand.groups][0] == "Triplet"
+
+
+def test_tile_group():
+    group = mahc.TileGroup("0p")
+
+    assert group.is_aka
+    assert group.value == "5"
+    assert str(group) == "0p"
+    assert [str(tile) for tile in mahc.TileGroup("123m").tiles()] == ["1m", "2m", "3m"]
+
+
+def test_calculate():
+    payment = mahc.calculate(4, 30)
+
+    assert payment.base_points == 1920
+    assert payment.dealer_ron() == 11600
+    assert payment.non_dealer_ron(honba=2) == 8300
+
+
+def test_errors():
+    with pytest.raises(mahc.MahcError) as err:
+        mahc.TileGroup("12")
+    assert err.value.code == "invalid_suit"
+
+    hand = mahc.Hand(["123mo", "456p", "789s", "111p", "22m"], "2m")
+    with pytest.raises(mahc.MahcError) as err:
+        mahc.get_hand_score(hand)
+    assert err.value.code == "no_yaku"
+
+    with pytest.raises(ValueError):
+        mahc.calculate(0, 30)